@@ -4,7 +4,7 @@ use crate::{
         AD_ADRESSE, AD_PERSON, AD_SITZ, BENUTZER, BYTE_DATEN, FZ_BUCH, FZ_BUCHAUTOR, FZ_BUCHSERIE,
         FZ_BUCHSTATUS, FZ_FAHRRAD, FZ_FAHRRADSTAND, FZ_NOTIZ, HH_BILANZ, HH_BUCHUNG, HH_EREIGNIS,
         HH_KONTO, HH_PERIODE, MA_MANDANT, MA_PARAMETER, SB_EREIGNIS, SB_FAMILIE, SB_KIND,
-        SB_PERSON, SB_QUELLE, SO_KURSE, TB_EINTRAG, TB_EINTRAG_ORT, TB_ORT, WP_ANLAGE, WP_BUCHUNG,
+        SB_PERSON, SB_QUELLE, SO_KURSE, TB_EINTRAG, TB_EINTRAG_ORT, TB_ORT, TB_ORT_ADRESSE, WP_ANLAGE, WP_BUCHUNG,
         WP_KONFIGURATION, WP_STAND, WP_WERTPAPIER,
     },
 };
@@ -854,7 +854,7 @@ impl Revision for FzFahrradstand {
     }
 }
 
-#[derive(Queryable, Insertable, AsChangeset, Debug, Serialize, Deserialize)]
+#[derive(Queryable, Insertable, AsChangeset, Debug, Serialize, Deserialize, QueryableByName)]
 #[table_name = "FZ_NOTIZ"]
 #[allow(non_snake_case)]
 pub struct FzNotiz {
@@ -2095,6 +2095,68 @@ impl Revision for TbOrt {
     }
 }
 
+#[derive(Queryable, Insertable, AsChangeset, Debug, Serialize, Deserialize)]
+#[table_name = "TB_ORT_ADRESSE"]
+#[allow(non_snake_case)]
+pub struct TbOrtAdresse {
+    pub mandant_nr: i32,
+    pub ort_uid: String,
+    pub adresse_uid: String,
+    pub angelegt_von: Option<String>,
+    pub angelegt_am: Option<NaiveDateTime>,
+    pub geaendert_von: Option<String>,
+    pub geaendert_am: Option<NaiveDateTime>,
+}
+
+impl Clone for TbOrtAdresse {
+    fn clone(&self) -> Self {
+        Self {
+            mandant_nr: self.mandant_nr,
+            ort_uid: self.ort_uid.clone(),
+            adresse_uid: self.adresse_uid.clone(),
+            angelegt_von: self.angelegt_von.clone(),
+            angelegt_am: self.angelegt_am.clone(),
+            geaendert_von: self.geaendert_von.clone(),
+            geaendert_am: self.geaendert_am.clone(),
+        }
+    }
+}
+
+impl PartialEq for TbOrtAdresse {
+    fn eq(&self, other: &Self) -> bool {
+        self.mandant_nr == other.mandant_nr
+            && self.ort_uid == other.ort_uid
+            && self.adresse_uid == other.adresse_uid
+    }
+}
+
+impl Revision for TbOrtAdresse {
+    fn get_angelegt_von(&self) -> Option<String> {
+        self.angelegt_von.clone()
+    }
+    fn set_angelegt_von(&mut self, von: &Option<String>) {
+        self.angelegt_von = von.clone();
+    }
+    fn get_angelegt_am(&self) -> Option<NaiveDateTime> {
+        self.angelegt_am
+    }
+    fn set_angelegt_am(&mut self, am: &Option<NaiveDateTime>) {
+        self.angelegt_am = am.clone();
+    }
+    fn get_geaendert_von(&self) -> Option<String> {
+        self.geaendert_von.clone()
+    }
+    fn set_geaendert_von(&mut self, von: &Option<String>) {
+        self.geaendert_von = von.clone();
+    }
+    fn get_geaendert_am(&self) -> Option<NaiveDateTime> {
+        self.geaendert_am
+    }
+    fn set_geaendert_am(&mut self, am: &Option<NaiveDateTime>) {
+        self.geaendert_am = am.clone();
+    }
+}
+
 #[derive(Queryable, Insertable, AsChangeset, Debug, Serialize, Deserialize)]
 #[table_name = "WP_ANLAGE"]
 #[allow(non_snake_case)]
@@ -1,6 +1,10 @@
-use crate::models::{AdPerson, Benutzer};
+use crate::models::{
+    AdAdresse, AdPerson, AdSitz, Benutzer, FzBuch, FzBuchserie, SbPerson, TbEintrag, TbEintragOrt,
+    TbOrt,
+};
 use crate::res::M;
 use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
 
 impl AdPerson {
     /// Get Name as string.
@@ -23,6 +27,56 @@ impl AdPerson {
     }
 }
 
+/// Extension of AdSitz with the optional address.
+#[derive(Debug, Clone)]
+pub struct AdSitzExt {
+    pub sitz: AdSitz,
+    pub adresse: Option<AdAdresse>,
+}
+
+/// Person with its ordered sites and addresses.
+#[derive(Debug, Clone)]
+pub struct AdPersonExt {
+    pub person: AdPerson,
+    pub sitze: Vec<AdSitzExt>,
+}
+
+/// Birthday of a person within a period.
+#[derive(Debug, Clone)]
+pub struct AdBirthday {
+    pub person: AdPerson,
+    /// Date of the birthday
+    pub date: NaiveDate,
+    /// Age turning on the birthday
+    pub age: i32,
+    /// Days from the base date until the birthday, negative if in the past
+    pub days: i64,
+    /// Is it a special birthday like 18, 50 or 60?
+    pub milestone: bool,
+}
+
+impl SbPerson {
+    /// Get full name as string.
+    pub fn full_name(&self) -> String {
+        let mut sb = String::new();
+        sb.push_str(self.name.as_str());
+        if let Some(v) = &self.vorname {
+            if !v.is_empty() {
+                sb.push_str(", ");
+                sb.push_str(v.as_str());
+            }
+        }
+        if let Some(g) = &self.geburtsname {
+            if !g.is_empty() && g != &self.name {
+                sb.push_str(" (");
+                sb.push_str(g.as_str());
+                sb.push_str(")");
+            }
+        }
+        sb
+    }
+}
+
 impl Benutzer {
     /// Get permission as string.
     pub fn permission(&self, is_de: bool) -> String {
@@ -36,6 +90,31 @@ impl Benutzer {
     }
 }
 
+/// Diary entry found by full-text search.
+#[derive(QueryableByName, Debug, Clone)]
+pub struct TbEintragFts {
+    #[sql_type = "diesel::sql_types::Date"]
+    pub datum: NaiveDate,
+    #[sql_type = "diesel::sql_types::Text"]
+    pub eintrag: String,
+    /// Part of the entry with highlighted matches in brackets
+    #[sql_type = "diesel::sql_types::Text"]
+    pub snippet: String,
+    /// Relevance, smaller is better
+    #[sql_type = "diesel::sql_types::Double"]
+    pub score: f64,
+}
+
+/// Diary entries with positions for export and import in JSON.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TbEintragExport {
+    /// Version of the format
+    pub version: i32,
+    pub eintraege: Vec<TbEintrag>,
+    pub orte: Vec<TbOrt>,
+    pub eintrag_orte: Vec<TbEintragOrt>,
+}
+
 /// Extension of TbEintragOrt and TbOrt
 #[derive(Debug)]
 pub struct TbEintragOrtExt {
@@ -73,3 +152,135 @@ impl Clone for TbEintragOrtExt {
         }
     }
 }
+
+/// Pair of possibly duplicate ancestors with matching score.
+#[derive(Debug, Clone)]
+pub struct SbPersonDuplicate {
+    pub person1: SbPerson,
+    pub person2: SbPerson,
+    pub score: i32,
+}
+
+/// Relationship of two ancestors via their closest common ancestors.
+#[derive(Debug, Clone)]
+pub struct SbRelationship {
+    /// Generations from the first ancestor up to the common ancestors.
+    pub generations1: i32,
+    /// Generations from the second ancestor up to the common ancestors.
+    pub generations2: i32,
+    /// Is it a half-relation with only one common parent?
+    pub half: bool,
+    /// Name of the relationship of the second ancestor to the first one.
+    pub name: String,
+    /// Closest common ancestors.
+    pub ancestors: Vec<SbPerson>,
+    /// Connecting paths from the first ancestor via a common ancestor to the second one.
+    pub paths: Vec<Vec<SbPerson>>,
+}
+
+/// Extension of FzBuch with author, series and reading status.
+#[derive(Debug, Clone)]
+pub struct FzBuchExt {
+    pub buch: FzBuch,
+    pub autor_name: String,
+    pub serie_name: String,
+    pub ist_besitz: bool,
+    pub lesedatum: Option<NaiveDate>,
+    pub hoerdatum: Option<NaiveDate>,
+}
+
+/// Completeness and reading order of a book series.
+#[derive(Debug, Clone)]
+pub struct FzSeriesReport {
+    pub serie: FzBuchserie,
+    /// Number of books with series number.
+    pub books: usize,
+    /// Lowest series number.
+    pub from: i32,
+    /// Highest series number.
+    pub to: i32,
+    /// Missing series numbers between lowest and highest.
+    pub missing: Vec<i32>,
+    /// Owned books without read date ordered by series number.
+    pub unread: Vec<FzBuchExt>,
+    /// Suggestion of the next owned book to read.
+    pub next: Option<FzBuchExt>,
+}
+
+/// Book of a catalogue import with matched author, series and duplicate status.
+#[derive(Debug, Clone, Default)]
+pub struct FzBookImport {
+    /// Line or record number in the catalogue.
+    pub line: usize,
+    pub titel: String,
+    pub untertitel: Option<String>,
+    pub autor_name: String,
+    pub autor_vorname: Option<String>,
+    /// Series name, empty for no series.
+    pub serie_name: String,
+    pub seriennummer: i32,
+    pub seiten: i32,
+    pub sprache_nr: i32,
+    pub ist_besitz: bool,
+    /// Existing author or None if it is created.
+    pub autor_uid: Option<String>,
+    /// Existing series or None if it is created.
+    pub serie_uid: Option<String>,
+    /// Book already exists or appears earlier in the catalogue.
+    pub duplicate: bool,
+}
+
+/// Book finished at a day with the diary entry of the day.
+#[derive(Debug, Clone)]
+pub struct FzReadingLog {
+    pub datum: NaiveDate,
+    pub buch: FzBuchExt,
+    /// Is the book heard and not read at the day?
+    pub heard: bool,
+    /// Diary entry of the day.
+    pub eintrag: Option<String>,
+}
+
+/// Books finished in a month.
+#[derive(Debug, Clone)]
+pub struct FzReadingMonth {
+    pub year: i32,
+    pub month: u32,
+    pub books: Vec<FzReadingLog>,
+}
+
+/// Reading statistics of a user for a date.
+#[derive(Debug, Clone, Default)]
+pub struct FzBookStatistics {
+    pub person: String,
+    pub days: i64,
+    pub books: i64,
+    pub read: i64,
+    pub heard: i64,
+    pub english: i64,
+    pub pr_booklets: i64,
+    pub pr_read: i64,
+    pub book_pages: i64,
+    pub pr_pages: i64,
+    pub pages: i64,
+    pub pages_per_day: f64,
+}
+
+/// Mileage statistics per bike and year.
+#[derive(Debug, Clone, Default)]
+pub struct FzMileageStatistics {
+    /// Years in ascending order.
+    pub years: Vec<i32>,
+    /// Descriptions of the bikes.
+    pub bikes: Vec<String>,
+    /// Km per bike (outer) and year (inner).
+    pub km: Vec<Vec<f64>>,
+    /// Km per year of all bikes.
+    pub year_sums: Vec<f64>,
+    /// Km of all bikes and years.
+    pub sum: f64,
+    /// Average km per year.
+    pub year_average: f64,
+    /// Km per day within the last 365 days.
+    pub km_per_day: f64,
+}
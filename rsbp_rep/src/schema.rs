@@ -518,7 +518,21 @@ table! {
 }
 
 allow_tables_to_appear_in_same_query!(TB_EINTRAG_ORT, TB_ORT);
+allow_tables_to_appear_in_same_query!(TB_ORT, TB_ORT_ADRESSE);
 
+table! {
+    use diesel::sql_types::*;
+    #[allow(non_snake_case)]
+    TB_ORT_ADRESSE (mandant_nr, ort_uid) {
+        mandant_nr -> Integer,
+        ort_uid -> Text,
+        adresse_uid -> Text,
+        angelegt_von -> Nullable<Text>,
+        angelegt_am -> Nullable<Timestamp>,
+        geaendert_von -> Nullable<Text>,
+        geaendert_am -> Nullable<Timestamp>,
+    }
+}
 table! {
     use diesel::sql_types::*;
     #[allow(non_snake_case)]
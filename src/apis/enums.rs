@@ -55,3 +55,83 @@ pub enum SearchDirectionEnum {
     /// last entry
     Last = 3,
 }
+
+/// GEDCOM event types of ancestors and families.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GedcomEventEnum {
+    /// Birth
+    Birth,
+    /// Christening
+    Christening,
+    /// Death
+    Death,
+    /// Burial
+    Burial,
+    /// Marriage
+    Marriage,
+}
+
+impl GedcomEventEnum {
+    pub fn to_str(e: GedcomEventEnum) -> &'static str {
+        return match e {
+            GedcomEventEnum::Birth => "BIRT",
+            GedcomEventEnum::Christening => "CHR",
+            GedcomEventEnum::Death => "DEAT",
+            GedcomEventEnum::Burial => "BURI",
+            GedcomEventEnum::Marriage => "MARR",
+        };
+    }
+}
+
+/// Languages of books.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LanguageEnum {
+    /// German
+    German = 0,
+    /// English
+    English = 1,
+    /// French
+    French = 2,
+    /// Other language
+    Other = 3,
+}
+
+impl LanguageEnum {
+    pub fn to_i32(e: LanguageEnum) -> i32 {
+        return match e {
+            LanguageEnum::German => 0,
+            LanguageEnum::English => 1,
+            LanguageEnum::French => 2,
+            LanguageEnum::Other => 3,
+        };
+    }
+}
+
+/// Types of bikes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BikeTypeEnum {
+    /// Mileages per tour
+    Tour = 1,
+    /// Weekly mileages
+    Weekly = 2,
+}
+
+impl BikeTypeEnum {
+    pub fn to_i32(e: BikeTypeEnum) -> i32 {
+        return match e {
+            BikeTypeEnum::Tour => 1,
+            BikeTypeEnum::Weekly => 2,
+        };
+    }
+}
+
+/// Formats of the diary export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiaryFormatEnum {
+    /// Markdown with one heading per day
+    Markdown,
+    /// Standalone HTML document
+    Html,
+    /// JSON with positions and revision data, importable
+    Json,
+}
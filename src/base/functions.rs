@@ -1,5 +1,5 @@
 use crate::res::messages::M;
-use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, Weekday};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, Utc, Weekday};
 use lazy_static::lazy_static;
 use rand::Rng;
 use regex::{Regex, RegexBuilder};
@@ -99,6 +99,18 @@ pub fn f64_to_str<'a>(i: f64) -> String {
     s
 }
 
+/// Convert integer to string with thousands separator.
+/// * i: Affected integer.
+/// * is_de: German format?
+/// return: Formatted number.
+pub fn i64_to_str_0<'a>(i: &i64, is_de: bool) -> String {
+    let s = i.separate_with_commas();
+    if is_de {
+        return switch_en_de(&s);
+    }
+    s
+}
+
 /// Convert float to string with 2 digits.
 /// * f: Affected float.
 /// * is_de: German format?
@@ -179,6 +191,15 @@ pub fn ostr_to_str(s: &Option<String>) -> String {
     "".into()
 }
 
+/// Wandelt String in optionalen String, leerer String wird None.
+/// * s: Betroffener String.
+pub fn to_ostr(s: &str) -> Option<String> {
+    if s.is_empty() {
+        return None;
+    }
+    Some(s.to_string())
+}
+
 /// Ist der String None oder leer.
 /// * s: Betroffener String.
 pub fn is_empty(s: &Option<String>) -> bool {
@@ -221,6 +242,53 @@ pub fn cmpuo(s: &Option<String>, s2: &str) -> bool {
     l1 == s2
 }
 
+/// Join values to a CSV line with semicolon as separator and quoted texts.
+/// * values: Affected values.
+pub fn to_csv(values: &[&str]) -> String {
+    let l: Vec<String> = values
+        .iter()
+        .map(|v| format!("\"{}\"", v.replace("\"", "\"\"")))
+        .collect();
+    l.join(";")
+}
+
+/// Split a CSV line into values with optionally quoted texts.
+/// * line: Affected line.
+/// * sep: Affected separator.
+/// * returns: Values or None if a quote is not closed.
+pub fn from_csv(line: &str, sep: char) -> Option<Vec<String>> {
+    let mut v: Vec<String> = vec![];
+    let mut s = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    s.push('"');
+                    chars.next();
+                } else {
+                    quoted = false;
+                }
+            } else {
+                s.push(c);
+            }
+        } else if c == '"' {
+            quoted = true;
+        } else if c == sep {
+            v.push(s);
+            s = String::new();
+        } else {
+            s.push(c);
+        }
+    }
+    if quoted {
+        return None;
+    }
+    v.push(s);
+    Some(v)
+}
+
 // /// Abschneiden der ersten 5 Zeichen, falls möglich.
 // fn m0(s: &str, cut: bool) -> Option<&str> {
 //     if !cut || s.is_empty() || s.len() < 5 {
@@ -343,6 +411,31 @@ pub fn ostr_to_ondt(s: Option<&str>) -> Option<NaiveDateTime> {
     None
 }
 
+/// Wandelt einen String in optionales Datum mit Uhrzeit um.
+/// Erlaubt sind RFC 3339, ISO 8601 ohne Zeitzone und Unix-Sekunden.
+/// * s: Zu konvertierender String.
+pub fn to_ondt(s: &str) -> Option<NaiveDateTime> {
+    let s = s.trim();
+    if let Ok(d) = DateTime::parse_from_rfc3339(s) {
+        return Some(d.with_timezone(&Local).naive_local());
+    }
+    for f in ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"].iter() {
+        if let Ok(d) = NaiveDateTime::parse_from_str(s, f) {
+            return Some(d);
+        }
+    }
+    if let Ok(secs) = s.parse::<i64>() {
+        if let Some(d) = NaiveDateTime::from_timestamp_opt(secs, 0) {
+            return Some(
+                DateTime::<Utc>::from_utc(d, Utc)
+                    .with_timezone(&Local)
+                    .naive_local(),
+            );
+        }
+    }
+    None
+}
+
 /// Add days to optional date.
 /// * ond: Affected date.
 pub fn ond_add_days(ond: &Option<NaiveDate>, days: i32) -> Option<NaiveDate> {
@@ -460,25 +553,82 @@ pub fn iif_i64(b: bool, itrue: i64, ifalse: i64) -> i64 {
     ifalse
 }
 
+/// Liefert f64 in Abhängigkeit eines Boolean-Wertes.
+pub fn iif_f64(b: bool, ftrue: f64, ffalse: f64) -> f64 {
+    if b {
+        return ftrue;
+    }
+    ffalse
+}
+
 /// Convert string with graphhopper.com coordinates to tuple
+/// Coordinates separated by semicolons may have a decimal comma.
 pub fn to_coordinates(s: &str) -> Option<(f64, f64, f64)> {
     lazy_static! {
         static ref RE_COORD: Regex =
-            RegexBuilder::new(r#"^(-?\d+(\.\d+)),\s*(-?\d+(\.\d+))(,\s*(-?\d+(\.\d+))z?)?$"#)
+            RegexBuilder::new(r#"^(-?\d+(\.\d+)?),\s*(-?\d+(\.\d+)?)(,\s*(-?\d+(\.\d+)?)z?)?$"#)
                 .case_insensitive(true)
                 .build()
                 .unwrap();
-    }
-    if let Some(c) = RE_COORD.captures(s) {
-        return Some((
-            to_f64(&c[1], false),
-            to_f64(&c[3], false),
-            to_f64(&c.get(6).map_or("", |a| a.as_str()), false),
-        ));
+        static ref RE_COORD_DE: Regex = RegexBuilder::new(
+            r#"^(-?\d+([.,]\d+)?);\s*(-?\d+([.,]\d+)?)(;\s*(-?\d+([.,]\d+)?)z?)?$"#
+        )
+        .case_insensitive(true)
+        .build()
+        .unwrap();
+    }
+    if let Some(c) = RE_COORD.captures(s).or_else(|| RE_COORD_DE.captures(s)) {
+        let f = |i: usize| {
+            to_f64(
+                &c.get(i).map_or("", |a| a.as_str()).replace(',', "."),
+                false,
+            )
+        };
+        return Some((f(1), f(3), f(6)));
     }
     None
 }
 
+/// Get the great-circle distance between two coordinates with the haversine formula.
+/// * lat1: Affected latitude of the first point.
+/// * lon1: Affected longitude of the first point.
+/// * lat2: Affected latitude of the second point.
+/// * lon2: Affected longitude of the second point.
+/// * returns: Distance in km.
+pub fn get_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let r = 6371.0088; // mean earth radius in km
+    let dlat = (lat2 - lat1).to_radians();
+    let dlon = (lon2 - lon1).to_radians();
+    let a = (dlat / 2.0).sin().powi(2)
+        + lat1.to_radians().cos() * lat2.to_radians().cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * r * a.sqrt().asin()
+}
+
+/// Get the similarity of two strings based on the Levenshtein distance.
+/// * s1: Affected first string.
+/// * s2: Affected second string.
+/// * returns: Similarity from 0 (different) to 1 (equal).
+pub fn get_similarity(s1: &str, s2: &str) -> f64 {
+    let a: Vec<char> = s1.chars().collect();
+    let b: Vec<char> = s2.chars().collect();
+    let max = a.len().max(b.len());
+    if max == 0 {
+        return 1.0;
+    }
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut prev = row[0];
+        row[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let d = (row[j] + 1).min(row[j - 1] + 1).min(prev + cost);
+            prev = row[j];
+            row[j] = d;
+        }
+    }
+    1.0 - row[b.len()] as f64 / max as f64
+}
+
 /// Get a file name with optional date and random number.
 pub fn get_file_name(name: &str, date: bool, random: bool, ext: &str) -> String {
     let mut s = String::new();
@@ -549,6 +699,74 @@ mod tests {
     //     assert_eq!(Some("1234567"), super::m0("1234567", false));
     // }
 
+    #[test]
+    fn to_csv() {
+        assert_eq!("", super::to_csv(&[]));
+        assert_eq!("\"a\";\"\"", super::to_csv(&["a", ""]));
+        assert_eq!("\"1;2\";\"x\"\"y\"", super::to_csv(&["1;2", "x\"y"]));
+    }
+
+    #[test]
+    fn from_csv() {
+        assert_eq!(Some(vec!["".to_string()]), super::from_csv("", ';'));
+        assert_eq!(
+            Some(vec!["a".to_string(), "".to_string()]),
+            super::from_csv("\"a\";\"\"", ';')
+        );
+        assert_eq!(
+            Some(vec!["1;2".to_string(), "x\"y".to_string()]),
+            super::from_csv("\"1;2\";\"x\"\"y\"", ';')
+        );
+        assert_eq!(
+            Some(vec!["1".to_string(), "2.5".to_string()]),
+            super::from_csv("1,2.5", ',')
+        );
+        assert_eq!(None, super::from_csv("\"a;b", ';'));
+    }
+
+    #[test]
+    fn to_coordinates() {
+        assert_eq!(
+            Some((52.5, 13.25, 0.0)),
+            super::to_coordinates("52.5,13.25")
+        );
+        assert_eq!(
+            Some((52.5, -13.25, 34.0)),
+            super::to_coordinates("52.5, -13.25, 34z")
+        );
+        assert_eq!(
+            Some((52.0, 13.0, 34.5)),
+            super::to_coordinates("52; 13;34,5")
+        );
+        assert_eq!(
+            Some((52.5, 13.25, 0.0)),
+            super::to_coordinates("52,5;13.25")
+        );
+        assert_eq!(
+            Some((52.0, 5.0, 13.25)),
+            super::to_coordinates("52,5,13.25")
+        );
+        assert_eq!(None, super::to_coordinates("52,5;13.25;1,5;2"));
+        assert_eq!(None, super::to_coordinates("52.5"));
+        assert_eq!(None, super::to_coordinates("52.5,x13"));
+    }
+
+    #[test]
+    fn get_distance() {
+        assert_eq!(0.0, super::get_distance(52.0, 13.0, 52.0, 13.0));
+        let d = super::get_distance(52.5163, 13.3777, 48.1372, 11.5755);
+        assert!((d - 504.3).abs() < 1.0);
+    }
+
+    #[test]
+    fn get_similarity() {
+        assert_eq!(1.0, super::get_similarity("", ""));
+        assert_eq!(0.0, super::get_similarity("abc", ""));
+        assert_eq!(1.0, super::get_similarity("hauptstr", "hauptstr"));
+        assert_eq!(0.75, super::get_similarity("haus", "maus"));
+        assert!((super::get_similarity("kitten", "sitting") - 4.0 / 7.0).abs() < 1e-9);
+    }
+
     #[test]
     fn m5() {
         assert_eq!("", super::m5("", true));
@@ -425,6 +425,19 @@ pub fn set_ad120_days(v: &str) {
     Parameter::set_value("AD120Days", &Some(v.to_string()));
 }
 
+/// Get amount of days ahead for birthday list beyond the days, 0 for the same amount.
+pub fn get_ad120_horizon() -> i32 {
+    if let Some(v) = Parameter::get_value("AD120Horizon") {
+        return functions::to_i32(v.as_str());
+    }
+    0
+}
+
+/// Set amount of days ahead for birthday list.
+pub fn set_ad120_horizon(v: &str) {
+    Parameter::set_value("AD120Horizon", &Some(v.to_string()));
+}
+
 /// Should the birthday list open after login.
 pub fn get_ad120_start() -> bool {
     if let Some(v) = Parameter::get_value("AD120Start") {
@@ -438,6 +451,32 @@ pub fn set_ad120_start(v: bool) {
     Parameter::set_value("AD120Start", &Some(functions::bool_to_str(v)));
 }
 
+/// Get minimum similarity of streets for duplicate addresses.
+pub fn get_ad_street_similarity() -> f64 {
+    if let Some(v) = Parameter::get_value("ADStreetSimilarity") {
+        return functions::to_f64(v.as_str(), false);
+    }
+    0.8
+}
+
+/// Set minimum similarity of streets for duplicate addresses.
+pub fn set_ad_street_similarity(v: f64) {
+    Parameter::set_value("ADStreetSimilarity", &Some(v.to_string()));
+}
+
+/// Get minimum similarity of towns for duplicate addresses.
+pub fn get_ad_town_similarity() -> f64 {
+    if let Some(v) = Parameter::get_value("ADTownSimilarity") {
+        return functions::to_f64(v.as_str(), false);
+    }
+    0.9
+}
+
+/// Set minimum similarity of towns for duplicate addresses.
+pub fn set_ad_town_similarity(v: f64) {
+    Parameter::set_value("ADTownSimilarity", &Some(v.to_string()));
+}
+
 /// Get start dialogs.
 pub fn get_start_dialogs() -> String {
     if let Some(v) = Parameter::get_value(AG_STARTDIALOGE) {
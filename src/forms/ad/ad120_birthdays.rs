@@ -107,7 +107,8 @@ impl<'a> Ad120Birthdays {
             let mut s = String::new();
             let date = bin::get_date_grid(&self.datum).unwrap_or(daten.get_today());
             let days = functions::to_i32(self.tage.text().as_str());
-            let l0 = address_service::get_birthday_list(&daten, &date, days);
+            let horizon = parameter::get_ad120_horizon();
+            let l0 = address_service::get_birthday_file(&daten, &date, days, horizon, false);
             if bin::get2(&l0) {
                 if let Ok(ref l) = l0 {
                     if l.len() <= 1 {
@@ -1,5 +1,7 @@
 use crate::base::functions;
 use chrono::{NaiveDate, NaiveDateTime};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
 use std::{borrow::Cow, fmt, str::FromStr};
 use strum_macros::EnumString;
 
@@ -36,6 +38,24 @@ pub enum Messages {
     AD012,
     AD013_,
     AD014,
+    AD015,
+    AD016,
+    AD017_,
+    AD018_,
+    AD019_,
+    AD020,
+    AD021_,
+    AD022_,
+    AD023_,
+    AD024,
+    AD025_,
+    AD026_,
+    AD027_,
+    AD028,
+    AD029,
+    AD030,
+    AD031,
+    AD032_,
     AG001,
     AG002,
     AM001,
@@ -91,6 +111,15 @@ pub enum Messages {
     FZ043,
     FZ044,
     FZ045,
+    FZ046_,
+    FZ047_,
+    FZ048_,
+    FZ049_,
+    FZ050_,
+    FZ051_,
+    FZ052_,
+    FZ053_,
+    FZ054_,
     HH001,
     HH002,
     HH003,
@@ -201,6 +230,12 @@ pub enum Messages {
     SB033_,
     SB034_,
     SB035_,
+    SB036_,
+    SB037_,
+    SB038,
+    SB039_,
+    SB040,
+    SB041,
     TB001,
     // TB002_,
     // TB003_,
@@ -214,6 +249,11 @@ pub enum Messages {
     // TB011_,
     TB012,
     // TB013_,
+    TB014,
+    TB015_,
+    TB016_,
+    TB017_,
+    TB018_,
     WP001,
     WP002,
     WP003,
@@ -1444,6 +1484,24 @@ impl Messages {
             M::AD012 => r#"AD012Address list"#,
             M::AD013_ => r#"AD013Person {0}"#,
             M::AD014 => r#"AD014Site"#,
+            M::AD015 => r#"AD015Type in a name."#,
+            M::AD016 => r#"AD016Type in a town."#,
+            M::AD017_ => r#"AD017The address is used by {0} sites and cannot be deleted."#,
+            M::AD018_ => r#"AD018New person {0}"#,
+            M::AD019_ => r#"AD019Changed person {0}"#,
+            M::AD020 => r#"AD020Birthdays"#,
+            M::AD021_ => r#"AD021{0} turns {1}"#,
+            M::AD022_ => r#"AD022Dear Mr {0},"#,
+            M::AD023_ => r#"AD023Dear Ms {0},"#,
+            M::AD024 => r#"AD024Dear Sir or Madam,"#,
+            M::AD025_ => r#"AD025Dear {0},"#,
+            M::AD026_ => r#"AD026Dear {0},"#,
+            M::AD027_ => r#"AD027Hello {0},"#,
+            M::AD028 => r#"AD028Mr"#,
+            M::AD029 => r#"AD029Ms"#,
+            M::AD030 => r#"AD030Select at least two addresses."#,
+            M::AD031 => r#"AD031special birthday"#,
+            M::AD032_ => r#"AD032{0} person(s), {1} site(s) and {2} address(es) imported."#,
             M::AG001 => r#"AG001Are you sure to restore?"#,
             M::AG002 => r#"AG002Type in a password."#,
             M::AM001 => r#"AM001Invalid login."#,
@@ -1498,6 +1556,15 @@ impl Messages {
             M::FZ043 => r#"FZ043Select a series."#,
             M::FZ044 => r#"FZ044Mileages per year"#,
             M::FZ045 => r#"FZ045Average per year"#,
+            M::FZ046_ => r#"FZ046The track {0} contains less than two points."#,
+            M::FZ047_ => r#"FZ047Notes report {0}"#,
+            M::FZ048_ => r#"FZ048Series report {0}"#,
+            M::FZ049_ => r#"FZ049{0}: {1} books, issues {2} to {3}"#,
+            M::FZ050_ => r#"FZ050Missing issues: {0}"#,
+            M::FZ051_ => r#"FZ051Owned but unread: {0}"#,
+            M::FZ052_ => r#"FZ052Next issue to read: {0}"#,
+            M::FZ053_ => r#"FZ053Finished reading {0}"#,
+            M::FZ054_ => r#"FZ054Finished listening to {0}"#,
             M::HH001 => r#"HH001Equity capital"#,
             M::HH002 => r#"HH002Profit and loss"#,
             M::HH003 => r#"HH003No periods found."#,
@@ -1612,12 +1679,25 @@ impl Messages {
             M::SB033_ => r#"SB033before {0}"#,
             M::SB034_ => r#"SB034{0} - {1}"#,
             M::SB035_ => r#"SB035{0} or {1}"#,
+            M::SB036_ => r#"SB036Ancestor statistics from {0:yyyy-MM-dd HH:mm:ss}"#,
+            M::SB037_ => {
+                r#"SB037Without date of death: {0}  Without source: {1}  Generations: {2}"#
+            }
+            M::SB038 => r#"SB038Average lifespan per century of birth:"#,
+            M::SB039_ => r#"SB039{0}-{1}: {2} years ({3} persons)"#,
+            M::SB040 => r#"SB040Most common first names:"#,
+            M::SB041 => r#"SB041Most common surnames:"#,
             M::TB001 => r#"TB001The search parameter is invalid."#,
             M::TB005 => r#"TB005Diary"#,
             M::TB007 => r#"TB007Type in a description."#,
             M::TB008 => r#"TB008The latitude must be between -90 and +90."#,
             M::TB009 => r#"TB009The longitude must be between -180 and +180."#,
             M::TB012 => r#"TB012Without position"#,
+            M::TB014 => r#"TB014Type in a search text."#,
+            M::TB015_ => r#"TB015The search index contains {0} entries."#,
+            M::TB016_ => r#"TB016{0} entries, {1} positions and {2} position links were imported."#,
+            M::TB017_ => r#"TB017The file is no valid diary export: {0}"#,
+            M::TB018_ => r#"TB018The search text {0} is invalid: {1}"#,
             M::WP001 => r#"WP001Type in a description."#,
             M::WP002 => r#"WP002Select a state."#,
             M::WP003 => r#"WP003The box size must be greater than 0."#,
@@ -2925,6 +3005,26 @@ impl Messages {
             M::AD012 => r#"AD012Adressenliste"#,
             M::AD013_ => r#"AD013Person {0}"#,
             M::AD014 => r#"AD014Sitz"#,
+            M::AD015 => r#"AD015Der Name darf nicht leer sein."#,
+            M::AD016 => r#"AD016Der Ort darf nicht leer sein."#,
+            M::AD017_ => {
+                r#"AD017Die Adresse wird von {0} Sitzen verwendet und kann nicht gelöscht werden."#
+            }
+            M::AD018_ => r#"AD018Neue Person {0}"#,
+            M::AD019_ => r#"AD019Geänderte Person {0}"#,
+            M::AD020 => r#"AD020Geburtstage"#,
+            M::AD021_ => r#"AD021{0} wird {1}"#,
+            M::AD022_ => r#"AD022Sehr geehrter Herr {0},"#,
+            M::AD023_ => r#"AD023Sehr geehrte Frau {0},"#,
+            M::AD024 => r#"AD024Sehr geehrte Damen und Herren,"#,
+            M::AD025_ => r#"AD025Lieber {0},"#,
+            M::AD026_ => r#"AD026Liebe {0},"#,
+            M::AD027_ => r#"AD027Hallo {0},"#,
+            M::AD028 => r#"AD028Herrn"#,
+            M::AD029 => r#"AD029Frau"#,
+            M::AD030 => r#"AD030Bitte mindestens zwei Adressen auswählen."#,
+            M::AD031 => r#"AD031runder Geburtstag"#,
+            M::AD032_ => r#"AD032Es wurde(n) {0} Person(en), {1} Sitz(e) und {2} Adresse(n) importiert."#,
             M::AM001 => r#"AM001Die Anmeldedaten sind ungültig."#,
             M::AM002 => r#"AM002Das neue Kennwort darf nicht leer sein."#,
             M::AM004 => r#"AM004Der aktuelle Mandant kann nicht gelöscht werden."#,
@@ -2981,6 +3081,15 @@ impl Messages {
             M::FZ043 => r#"FZ043Bitte eine Serie auswählen."#,
             M::FZ044 => r#"FZ044km pro Jahr"#,
             M::FZ045 => r#"FZ045Jahresdurchschnitt"#,
+            M::FZ046_ => r#"FZ046Die Strecke {0} enthält weniger als zwei Punkte."#,
+            M::FZ047_ => r#"FZ047Notizen-Bericht vom {0}"#,
+            M::FZ048_ => r#"FZ048Serien-Bericht vom {0}"#,
+            M::FZ049_ => r#"FZ049{0}: {1} Bücher, Nummern {2} bis {3}"#,
+            M::FZ050_ => r#"FZ050Fehlende Nummern: {0}"#,
+            M::FZ051_ => r#"FZ051Im Besitz, aber ungelesen: {0}"#,
+            M::FZ052_ => r#"FZ052Als Nächstes lesen: {0}"#,
+            M::FZ053_ => r#"FZ053{0} ausgelesen"#,
+            M::FZ054_ => r#"FZ054{0} fertig gehört"#,
             M::HH001 => r#"HH001Eigenkapital"#,
             M::HH002 => r#"HH002Gewinn/Verlust"#,
             M::HH003 => r#"HH003Keine Perioden gefunden."#,
@@ -3101,12 +3210,23 @@ impl Messages {
             M::SB033_ => r#"SB033vor {0}"#,
             M::SB034_ => r#"SB034{0} - {1}"#,
             M::SB035_ => r#"SB035{0} oder {1}"#,
+            M::SB036_ => r#"SB036Ahnen-Statistik vom {0:yyyy-MM-dd HH:mm:ss}"#,
+            M::SB037_ => r#"SB037Ohne Sterbedatum: {0}  ohne Quelle: {1}  Generationen: {2}"#,
+            M::SB038 => r#"SB038Durchschnittliches Lebensalter je Jahrhundert der Geburt:"#,
+            M::SB039_ => r#"SB039{0}-{1}: {2} Jahre ({3} Personen)"#,
+            M::SB040 => r#"SB040Häufigste Vornamen:"#,
+            M::SB041 => r#"SB041Häufigste Nachnamen:"#,
             M::TB001 => r#"TB001Der Such-Parameter ist ungültig."#,
             M::TB005 => r#"TB005Tagebuch"#,
             M::TB007 => r#"TB007Die Bezeichnung darf nicht leer sein."#,
             M::TB008 => r#"TB008Die Breite muss zwischen -90 und +90 sein."#,
             M::TB009 => r#"TB009Die Länge muss zwischen -180 und +180 sein."#,
             M::TB012 => r#"TB012Ohne Position"#,
+            M::TB014 => r#"TB014Der Suchtext darf nicht leer sein."#,
+            M::TB015_ => r#"TB015Der Suchindex enthält {0} Einträge."#,
+            M::TB016_ => r#"TB016{0} Einträge, {1} Positionen und {2} Positionszuordnungen wurden importiert."#,
+            M::TB017_ => r#"TB017Die Datei ist kein gültiger Tagebuch-Export: {0}"#,
+            M::TB018_ => r#"TB018Der Suchtext {0} ist ungültig: {1}"#,
             M::WP001 => r#"WP001Die Bezeichnung darf nicht leer sein."#,
             M::WP002 => r#"WP002Der Status darf nicht leer sein."#,
             M::WP003 => r#"WP003Die Boxgröße muss größer 0 sein."#,
@@ -4448,6 +4568,21 @@ impl Messages {
         }
     }
 
+    /// Liefert Meldung zu Messages-Enum in gewünschter Sprache mit abgeschnittener Nummer
+    /// und ersetzten Parametern wie {0} oder {1:#,##0}.
+    pub fn mecf(key: Messages, args: &[&str], is_de: bool) -> String {
+        lazy_static! {
+            static ref RE_PARAM: Regex = Regex::new(r#"\{(\d+)(:[^}]*)?\}"#).unwrap();
+        }
+        let m0 = M::mec(key, is_de);
+        RE_PARAM
+            .replace_all(&m0, |c: &Captures| {
+                let i = functions::to_i32(&c[1]) as usize;
+                args.get(i).map_or(String::new(), |a| a.to_string())
+            })
+            .into_owned()
+    }
+
     pub fn ad001(f: &NaiveDate, t: &NaiveDate, is_de: bool) -> String {
         if is_de {
             return format!("Geburtstage zwischen {0} und {1}:", f, t);
@@ -4551,6 +4686,17 @@ mod tests {
     use quick_xml::events::Event;
     use quick_xml::Reader;
 
+    #[test]
+    fn mecf() {
+        use super::M;
+        assert_eq!(
+            "Days of life:          12,345",
+            M::mecf(M::FZ004_, &["12,345"], false)
+        );
+        assert_eq!("Bike 1 (2) km", M::mecf(M::FZ016_, &["Bike ", "1", "2"], false));
+        assert_eq!("Bike  () km", M::mecf(M::FZ016_, &["Bike "], false));
+    }
+
     #[test]
     fn generate_messages() {
         // cargo test generate_messages
@@ -1,7 +1,9 @@
 pub mod address_service;
+pub mod ancestor_service;
 pub mod client_service;
 pub mod diary_service;
 pub mod https_server;
 pub mod login_service;
+pub mod private_service;
 mod reps;
 mod undo;
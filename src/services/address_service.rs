@@ -1,73 +1,2023 @@
-use super::reps::{self, DbContext};
+use super::{
+    diary_service,
+    reps::{self, DbContext},
+};
 use crate::{
-    apis::services::ServiceDaten, base::functions, config::RsbpError, res::messages::M,
-    services::undo::UndoRedoStack, Result,
+    apis::services::ServiceDaten,
+    base::{functions, parameter},
+    config::RsbpError,
+    res::messages::M,
+    services::undo::UndoRedoStack,
+    Result,
 };
-use chrono::{Datelike, NaiveDate};
+use chrono::{Datelike, NaiveDate, Utc};
 use diesel::Connection;
+use rsbp_rep::{
+    models::{AdAdresse, AdPerson, AdSitz},
+    models_ext::{AdBirthday, AdPersonExt, AdSitzExt},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
 
-/// Get list with clients.
+/// Column headers of the birthday CSV.
+pub const BIRTHDAY_CSV_HEADER: [&str; 7] = [
+    "person_uid",
+    "name",
+    "geburt",
+    "datum",
+    "alter",
+    "tage",
+    "meilenstein",
+];
+
+/// Get the birthdays of actual persons around a date.
 /// * daten: Service data for database access.
 /// * date: Affected date.
-/// * days: Number of days for interval.
-/// * returns: List with clients.
+/// * days: Number of days before and after the date.
+/// * horizon: Number of days after the date if greater than days.
+/// * returns: Birthdays ordered by date and name or possibly errors.
 pub fn get_birthday_list<'a>(
     daten: &'a ServiceDaten,
     date: &NaiveDate,
     days: i32,
+    horizon: i32,
+) -> Result<Vec<AdBirthday>> {
+    let (from, to) = get_birthday_period(date, days, horizon);
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    get_birthday_list0(&db, date, &from, &to)
+}
+
+/// Get the birthdays of actual persons around a date as text or CSV lines.
+/// * daten: Service data for database access.
+/// * date: Affected date.
+/// * days: Number of days before and after the date.
+/// * horizon: Number of days after the date if greater than days.
+/// * csv: Should the lines be CSV with the header BIRTHDAY_CSV_HEADER?
+/// * returns: Text lines with a title line or CSV lines or possibly errors.
+pub fn get_birthday_file<'a>(
+    daten: &'a ServiceDaten,
+    date: &NaiveDate,
+    days: i32,
+    horizon: i32,
+    csv: bool,
 ) -> Result<Vec<String>> {
+    let is_de = daten.config.is_de();
+    let (from, to) = get_birthday_period(date, days, horizon);
+    let l = get_birthday_list(daten, date, days, horizon)?;
     let mut v: Vec<String> = vec![];
+    if csv {
+        v.push(functions::to_csv(&BIRTHDAY_CSV_HEADER));
+    } else {
+        v.push(M::ad001(&from, &to, is_de));
+    }
+    for b in l {
+        let g = b.person.geburt.unwrap_or(b.date);
+        if csv {
+            v.push(functions::to_csv(&[
+                b.person.uid.as_str(),
+                b.person.name().as_str(),
+                functions::ond_to_str(&b.person.geburt).as_str(),
+                functions::ond_to_str(&Some(b.date)).as_str(),
+                b.age.to_string().as_str(),
+                b.days.to_string().as_str(),
+                functions::bool_to_str(b.milestone).as_str(),
+            ]));
+        } else {
+            let mut s = M::ad002(&g, &b.person.name(), b.age, is_de);
+            if b.milestone {
+                s.push_str(format!(", {}", M::mec(M::AD031, is_de)).as_str());
+            }
+            v.push(s);
+        }
+    }
+    Ok(v)
+}
+
+/// Is the age a special birthday: 18, every ten years from 20 and every five years from 60.
+pub fn is_milestone(age: i32) -> bool {
+    age == 18 || (age >= 20 && age % 10 == 0) || (age >= 60 && age % 5 == 0)
+}
+
+/// Get the period for birthday lists.
+fn get_birthday_period(date: &NaiveDate, days: i32, horizon: i32) -> (NaiveDate, NaiveDate) {
     let from = functions::ond_add_days(&Some(*date), -days.abs()).unwrap_or(*date);
-    let to = functions::ond_add_days(&Some(*date), days.abs()).unwrap_or(*date);
-    let j = date.year();
-    let f = (from.month() as i32) * 100 + (from.day() as i32);
-    v.push(M::ad001(&from, &to, daten.config.is_de()));
-    let i = functions::iif_i32(
-        j != from.year(),
-        1,
-        functions::iif_i32(j != to.year(), 2, 0),
+    let to = functions::ond_add_days(&Some(*date), days.abs().max(horizon)).unwrap_or(*date);
+    (from, to)
+}
+
+/// Get the birthdays of actual persons within a period.
+fn get_birthday_list0(
+    db: &DbContext,
+    date: &NaiveDate,
+    from: &NaiveDate,
+    to: &NaiveDate,
+) -> Result<Vec<AdBirthday>> {
+    let mut v: Vec<AdBirthday> = vec![];
+    for p in reps::ad_person::get_list_ext2(db, None, true)? {
+        let g = match p.geburt {
+            Some(g) => g,
+            None => continue,
+        };
+        for y in from.year().max(g.year() + 1)..=to.year() {
+            let d = get_birthday_of_year(&g, y);
+            if d >= *from && d <= *to {
+                let age = y - g.year();
+                v.push(AdBirthday {
+                    person: p.clone(),
+                    date: d,
+                    age,
+                    days: d.signed_duration_since(*date).num_days(),
+                    milestone: is_milestone(age),
+                });
+            }
+        }
+    }
+    v.sort_by(|a, b| {
+        a.date
+            .cmp(&b.date)
+            .then_with(|| a.person.name1.cmp(&b.person.name1))
+            .then_with(|| a.person.vorname.cmp(&b.person.vorname))
+            .then_with(|| a.person.uid.cmp(&b.person.uid))
+    });
+    Ok(v)
+}
+
+/// Get the birthday in a year, February 28 for February 29 in non-leap years.
+fn get_birthday_of_year(birth: &NaiveDate, year: i32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, birth.month(), birth.day())
+        .unwrap_or_else(|| NaiveDate::from_ymd(year, 2, 28))
+}
+
+/// Get the birthdays of all actual persons within the next year as iCalendar events.
+/// Each birthday is a single event with the age in the summary, so the events are
+/// correct as long as the calendar is fetched again, e.g. by a subscription.
+/// * daten: Service data for database access.
+/// * returns: iCalendar lines or possibly errors.
+pub fn get_birthday_calendar<'a>(daten: &'a ServiceDaten) -> Result<Vec<String>> {
+    let is_de = daten.config.is_de();
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let today = daten.get_today();
+    let to = functions::nd_add_dmy(&today, -1, 0, 1).unwrap_or(today);
+    let l = get_birthday_list0(&db, &today, &today, &to)?;
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut v: Vec<String> = vec![
+        "BEGIN:VCALENDAR".into(),
+        "VERSION:2.0".into(),
+        format!(
+            "PRODID:-//{0}//{0} {1}//EN",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        ),
+        "CALSCALE:GREGORIAN".into(),
+        format!("X-WR-CALNAME:{}", escape_text(&M::mec(M::AD020, is_de))),
+    ];
+    for b in l.iter() {
+        v.append(&mut get_birthday_event(b, &stamp, is_de));
+    }
+    v.push("END:VCALENDAR".into());
+    Ok(v.iter().flat_map(|l| fold_line(l)).collect())
+}
+
+/// Get a birthday as iCalendar event with the age in the summary.
+fn get_birthday_event(b: &AdBirthday, stamp: &str, is_de: bool) -> Vec<String> {
+    let p = &b.person;
+    let summary = M::mecf(
+        M::AD021_,
+        &[p.name().as_str(), b.age.to_string().as_str()],
+        is_de,
     );
+    vec![
+        "BEGIN:VEVENT".into(),
+        format!(
+            "UID:{}-birthday-{}@{}",
+            p.uid,
+            b.date.year(),
+            env!("CARGO_PKG_NAME")
+        ),
+        format!("DTSTAMP:{}", stamp),
+        format!("DTSTART;VALUE=DATE:{}", b.date.format("%Y%m%d")),
+        format!("DTEND;VALUE=DATE:{}", b.date.succ().format("%Y%m%d")),
+        format!("SUMMARY:{}", escape_text(&summary)),
+        "TRANSP:TRANSPARENT".into(),
+        "END:VEVENT".into(),
+    ]
+}
+
+/// Get a list of persons.
+/// * daten: Service data for database access.
+/// * name: Affected name filter with wildcards.
+/// * actual: Affected only persons with status 0.
+/// * returns: List of persons or possibly errors.
+pub fn get_person_list<'a>(
+    daten: &'a ServiceDaten,
+    name: &Option<String>,
+    actual: bool,
+) -> Result<Vec<AdPerson>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let l = reps::ad_person::get_list_ext2(&db, name.as_ref(), actual)?;
+    Ok(l)
+}
+
+/// Get a person.
+/// * daten: Service data for database access.
+/// * uid: Affected person ID.
+/// * returns: Person or possibly errors.
+pub fn get_person<'a>(daten: &'a ServiceDaten, uid: &String) -> Result<Option<AdPerson>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let e = reps::ad_person::get(&db, &daten.mandant_nr, uid)?;
+    Ok(e)
+}
+
+/// Get the ordered list of sites with addresses of a person.
+/// * daten: Service data for database access.
+/// * person_uid: Affected person ID.
+/// * returns: List of sites or possibly errors.
+pub fn get_site_list<'a>(daten: &'a ServiceDaten, person_uid: &String) -> Result<Vec<AdSitzExt>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let l = get_site_list0(&db, person_uid)?;
+    Ok(l)
+}
+
+/// Get a list of all addresses.
+/// * daten: Service data for database access.
+/// * returns: List of addresses ordered by town and street or possibly errors.
+pub fn get_address_list<'a>(daten: &'a ServiceDaten) -> Result<Vec<AdAdresse>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let mut l = reps::ad_adresse::get_list(&db, daten.mandant_nr)?;
+    l.sort_by(|a, b| {
+        a.ort
+            .cmp(&b.ort)
+            .then(a.strasse.cmp(&b.strasse))
+            .then(a.hausnr.cmp(&b.hausnr))
+    });
+    Ok(l)
+}
+
+/// Save a person with its ordered sites and their addresses.
+/// Sites get the order of the list, sites missing in the list are deleted.
+/// An address with empty ID is created, an existing address is changed for all its sites.
+/// * daten: Service data for database access.
+/// * p: Affected person, empty ID for a new person.
+/// * sites: Affected sites with optional addresses.
+/// * returns: Saved person or possibly errors.
+pub fn save_person<'a>(
+    daten: &'a ServiceDaten,
+    p: &AdPerson,
+    sites: &Vec<AdSitzExt>,
+) -> Result<AdPerson> {
+    let mut r: Vec<String> = vec![];
+    let is_de = daten.config.is_de();
+    if p.name1.trim().is_empty() {
+        r.push(M::mec(M::AD015, is_de).into_owned());
+    }
+    if sites.iter().any(|s| {
+        s.adresse
+            .as_ref()
+            .map_or(false, |a| a.ort.trim().is_empty())
+    }) {
+        r.push(M::mec(M::AD016, is_de).into_owned());
+    }
+    if r.len() > 0 {
+        return Err(RsbpError::error(&r));
+    }
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<AdPerson, RsbpError, _>(|| {
+        let e = save_person0(&mut db, p, sites)?;
+        Ok(e)
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Copy a person with its sites. The copied sites share the addresses.
+/// * daten: Service data for database access.
+/// * uid: Affected person ID.
+/// * returns: Copied person or possibly errors.
+pub fn copy_person<'a>(daten: &'a ServiceDaten, uid: &String) -> Result<AdPerson> {
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<AdPerson, RsbpError, _>(|| {
+        let mut p = match reps::ad_person::get(&db, &daten.mandant_nr, uid)? {
+            Some(p) => p,
+            None => return Err(RsbpError::NotFound),
+        };
+        let mut sites = get_site_list0(&db, uid)?;
+        p.uid = String::new();
+        for s in sites.iter_mut() {
+            s.sitz.uid = String::new();
+        }
+        let e = save_person0(&mut db, &p, &sites)?;
+        Ok(e)
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Delete a person with its sites and the addresses which are no longer used.
+/// * daten: Service data for database access.
+/// * e: Affected Entity.
+/// * returns: Possibly errors.
+pub fn delete_person<'a>(daten: &'a ServiceDaten, e: &AdPerson) -> Result<()> {
     let c = reps::establish_connection(daten);
     let mut db = DbContext::new(daten, &c);
     let tr = c.transaction::<(), RsbpError, _>(|| {
-        let _p = reps::ad_person::save(
-            &mut db,
-            &daten.mandant_nr,
-            &"1".to_string(),
-            &0,
-            &"M".to_string(),
-            &Some(NaiveDate::from_ymd(1970, 9, 1)),
-            &901,
-            &0,
-            &0,
-            &"Name1".to_string(),
-            &Some("Name2".to_string()),
-            &Some("Praedikat".to_string()),
-            &Some("Vorname".to_string()),
-            &Some("Titel".to_string()),
-            &0,
-        )?;
+        let sl = reps::ad_sitz::get_list_ext(&db, Some(&e.uid), None)?;
+        for s in sl.iter() {
+            reps::ad_sitz::delete(&mut db, s)?;
+        }
+        delete_unused_addresses(&mut db, &sl)?;
+        reps::ad_person::delete(&mut db, e)?;
+        Ok(())
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Save an address which may be shared by several sites.
+/// * daten: Service data for database access.
+/// * uid: Affected ID, empty for a new address.
+/// * state: Affected state.
+/// * postcode: Affected postcode.
+/// * town: Affected town.
+/// * street: Affected street.
+/// * no: Affected house number.
+/// * returns: Saved address or possibly errors.
+pub fn save_address<'a>(
+    daten: &'a ServiceDaten,
+    uid: &String,
+    state: &String,
+    postcode: &String,
+    town: &String,
+    street: &String,
+    no: &String,
+) -> Result<AdAdresse> {
+    let a = AdAdresse {
+        mandant_nr: daten.mandant_nr,
+        uid: uid.clone(),
+        staat: functions::to_ostr(state.trim()),
+        plz: functions::to_ostr(postcode.trim()),
+        ort: town.trim().to_string(),
+        strasse: functions::to_ostr(street.trim()),
+        hausnr: functions::to_ostr(no.trim()),
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+    };
+    if a.ort.is_empty() {
+        return Err(RsbpError::error_msg(M::AD016, daten.config.is_de()));
+    }
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<AdAdresse, RsbpError, _>(|| {
+        let e = save_address0(&mut db, &a)?;
+        Ok(e)
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Delete an address if no site references it.
+/// * daten: Service data for database access.
+/// * e: Affected Entity.
+/// * returns: Possibly errors.
+pub fn delete_address<'a>(daten: &'a ServiceDaten, e: &AdAdresse) -> Result<()> {
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<(), RsbpError, _>(|| {
+        let sl = reps::ad_sitz::get_list_ext(&db, None, Some(&e.uid))?;
+        if sl.len() > 0 {
+            return Err(RsbpError::error_string(
+                M::mecf(
+                    M::AD017_,
+                    &[sl.len().to_string().as_str()],
+                    daten.config.is_de(),
+                )
+                .as_str(),
+            ));
+        }
+        diary_service::move_position_addresses(&mut db, None, Some(&e.uid), None)?;
+        reps::ad_adresse::delete(&mut db, e)?;
         Ok(())
     });
     if tr.is_ok() {
         UndoRedoStack::add_undo(&mut db.ul);
     }
+    tr
+}
+
+/// Header of the address list CSV file: one row per site with person and address columns.
+/// A person without sites has one row with empty site and address columns.
+pub const ADDRESS_CSV_HEADER: [&str; 29] = [
+    "person_uid",
+    "typ",
+    "geschlecht",
+    "geburt",
+    "anrede",
+    "fanrede",
+    "name1",
+    "name2",
+    "praedikat",
+    "vorname",
+    "titel",
+    "person_status",
+    "sitz_uid",
+    "sitz_typ",
+    "sitz_name",
+    "telefon",
+    "fax",
+    "mobil",
+    "email",
+    "homepage",
+    "postfach",
+    "bemerkung",
+    "sitz_status",
+    "adresse_uid",
+    "staat",
+    "plz",
+    "ort",
+    "strasse",
+    "hausnr",
+];
+
+/// Get the address list as CSV lines with the header ADDRESS_CSV_HEADER.
+/// * daten: Service data for database access.
+/// * returns: Vector of CSV lines or possibly errors.
+pub fn get_address_csv<'a>(daten: &'a ServiceDaten) -> Result<Vec<String>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let mut v: Vec<String> = vec![];
+    v.push(functions::to_csv(&ADDRESS_CSV_HEADER));
+    for p in get_person_ext_list0(&db, None, false)? {
+        for r in get_csv_rows(&p) {
+            v.push(functions::to_csv(
+                &r.iter().map(|s| s.as_str()).collect::<Vec<&str>>(),
+            ));
+        }
+    }
+    Ok(v)
+}
 
-    let l = reps::ad_person::get_list_ext(&db, &from, &to)?;
-    for p in l {
-        let d = p.geburtk;
-        if let Some(g) = p.geburt {
-            let y = functions::ond_year(&p.geburt);
-            let j1: i32;
-            if i == 0 {
-                j1 = j - y;
-            } else if f <= d {
-                j1 = from.year() - y;
+/// Read an address list CSV file and match the persons by ID or by name and birthday
+/// without saving anything.
+/// * daten: Service data for database access.
+/// * file: Affected CSV file name.
+/// * returns: Persons to import with report of new and changed persons or possibly errors.
+pub fn get_address_import<'a>(
+    daten: &'a ServiceDaten,
+    file: &str,
+) -> Result<(Vec<AdPersonExt>, Vec<String>)> {
+    let is_de = daten.config.is_de();
+    let content = match fs::read_to_string(file) {
+        Ok(c) => c,
+        Err(_) => {
+            return Err(RsbpError::error_string(
+                M::mecf(M::M1034_, &[file], is_de).as_str(),
+            ))
+        }
+    };
+    let mut l: Vec<AdPersonExt> = vec![];
+    let mut keys: HashMap<String, usize> = HashMap::new();
+    let mut header = false;
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let cols = match functions::from_csv(line, ';') {
+            Some(c) => c,
+            None => {
+                return Err(RsbpError::error_string(
+                    M::mecf(M::M1020_, &[line], is_de).as_str(),
+                ))
+            }
+        };
+        if !header {
+            if cols.len() != ADDRESS_CSV_HEADER.len()
+                || cols
+                    .iter()
+                    .zip(ADDRESS_CSV_HEADER.iter())
+                    .any(|(c, h)| !functions::cmp(c.trim(), h))
+            {
+                return Err(RsbpError::error_msg(M::AD005, is_de));
+            }
+            header = true;
+            continue;
+        }
+        if cols.len() != ADDRESS_CSV_HEADER.len() {
+            return Err(RsbpError::error_string(
+                M::mecf(M::M1020_, &[line], is_de).as_str(),
+            ));
+        }
+        let (p, site) = from_csv_row(daten.mandant_nr, &cols);
+        let mut r: Vec<String> = vec![];
+        if p.name1.is_empty() {
+            r.push(M::mec(M::AD015, is_de).into_owned());
+        }
+        if let Some(AdSitzExt {
+            adresse: Some(a), ..
+        }) = &site
+        {
+            if a.ort.is_empty() {
+                r.push(M::mec(M::AD016, is_de).into_owned());
+            }
+        }
+        if r.len() > 0 {
+            r.push(line.to_string());
+            return Err(RsbpError::error(&r));
+        }
+        let key = match p.uid.as_str() {
+            "" => format!(
+                "{}|{}|{}",
+                p.name1.to_lowercase(),
+                functions::ostr_to_str(&p.vorname).to_lowercase(),
+                functions::ond_to_str(&p.geburt)
+            ),
+            uid => uid.to_string(),
+        };
+        let i = *keys.entry(key).or_insert_with(|| {
+            l.push(AdPersonExt {
+                person: p,
+                sitze: vec![],
+            });
+            l.len() - 1
+        });
+        if let Some(s) = site {
+            l[i].sitze.push(s);
+        }
+    }
+    if !header {
+        return Err(RsbpError::error_msg(M::AD005, is_de));
+    }
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let v = match_persons(&db, &mut l, false)?;
+    Ok((l, v))
+}
+
+/// Save the persons of an address list import with their sites and addresses in one transaction.
+/// * daten: Service data for database access.
+/// * list: Affected persons from get_address_import.
+/// * returns: Message with numbers of imported persons, sites and addresses or possibly errors.
+pub fn import_addresses<'a>(daten: &'a ServiceDaten, list: &Vec<AdPersonExt>) -> Result<String> {
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<String, RsbpError, _>(|| {
+        let mut addresses: HashSet<String> = HashSet::new();
+        let mut new_addresses = 0;
+        let mut sites = 0;
+        for p in list {
+            save_person0(&mut db, &p.person, &p.sitze)?;
+            sites += p.sitze.len();
+            for a in p.sitze.iter().filter_map(|s| s.adresse.as_ref()) {
+                if a.uid.is_empty() {
+                    new_addresses += 1;
+                } else {
+                    addresses.insert(a.uid.clone());
+                }
+            }
+        }
+        let n = [
+            list.len().to_string(),
+            sites.to_string(),
+            (addresses.len() + new_addresses).to_string(),
+        ];
+        let args = n.iter().map(|s| s.as_str()).collect::<Vec<&str>>();
+        Ok(M::mecf(M::AD032_, &args, daten.config.is_de()))
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Match imported persons with existing persons by ID or by name, first name and birthday.
+/// * merge: Should columns and sites missing in the import be taken from the existing person?
+///   IDs of new persons are not kept then.
+/// * returns: Report of new and changed persons.
+fn match_persons(db: &DbContext, l: &mut Vec<AdPersonExt>, merge: bool) -> Result<Vec<String>> {
+    let is_de = db.daten.config.is_de();
+    let existing = get_person_ext_list0(db, None, false)?;
+    let mut v: Vec<String> = vec![];
+    for p in l.iter_mut() {
+        let e = existing
+            .iter()
+            .find(|e| e.person.uid == p.person.uid)
+            .or_else(|| {
+                existing.iter().find(|e| {
+                    (merge || p.person.uid.is_empty())
+                        && functions::cmp(&e.person.name1, &p.person.name1)
+                        && functions::cmp(
+                            &functions::ostr_to_str(&e.person.vorname),
+                            &functions::ostr_to_str(&p.person.vorname),
+                        )
+                        && e.person.geburt == p.person.geburt
+                })
+            });
+        match e {
+            Some(e) => {
+                p.person.uid = e.person.uid.clone();
+                if merge {
+                    merge_person(e, p);
+                }
+                for s in p.sitze.iter_mut() {
+                    s.sitz.person_uid = e.person.uid.clone();
+                }
+                if get_csv_rows(e) != get_csv_rows(p) {
+                    v.push(M::mecf(M::AD019_, &[p.person.name().as_str()], is_de));
+                }
+            }
+            None => {
+                if merge {
+                    // Fremde IDs nicht übernehmen.
+                    p.person.uid = String::new();
+                }
+                v.push(M::mecf(M::AD018_, &[p.person.name().as_str()], is_de));
+            }
+        }
+    }
+    Ok(v)
+}
+
+/// Take columns and sites missing in an imported person from the existing person.
+/// Sites are matched by their order.
+fn merge_person(e: &AdPersonExt, p: &mut AdPersonExt) {
+    let (ep, pp) = (&e.person, &mut p.person);
+    pp.typ = ep.typ;
+    pp.anrede = ep.anrede;
+    pp.fanrede = ep.fanrede;
+    pp.name2 = ep.name2.clone();
+    pp.person_status = ep.person_status;
+    if pp.geschlecht.is_empty() {
+        pp.geschlecht = ep.geschlecht.clone();
+    }
+    for (s, es) in p.sitze.iter_mut().zip(e.sitze.iter()) {
+        s.sitz.uid = es.sitz.uid.clone();
+        s.sitz.typ = es.sitz.typ;
+        s.sitz.bemerkung = es.sitz.bemerkung.clone();
+        s.sitz.sitz_status = es.sitz.sitz_status;
+        if s.sitz.name.is_empty() {
+            s.sitz.name = es.sitz.name.clone();
+        }
+        if let (Some(a), Some(ea)) = (&mut s.adresse, &es.adresse) {
+            if a.staat.is_none() {
+                a.staat = ea.staat.clone();
+            }
+            // Addresses are shared with other sites, a changed address becomes a new one.
+            if get_address_key(a) == get_address_key(ea) {
+                *a = ea.clone();
             } else {
-                j1 = to.year() - y;
+                a.uid = String::new();
             }
-            v.push(M::ad002(&g, &p.name(), j1, daten.config.is_de()));
         }
+        s.sitz.adresse_uid = s.adresse.as_ref().map(|a| a.uid.clone());
+    }
+    if e.sitze.len() > p.sitze.len() {
+        p.sitze.extend(e.sitze[p.sitze.len()..].iter().cloned());
+    }
+}
+
+/// Get groups of similar addresses. Street and town are compared with a minimum similarity
+/// after normalizing, house number, postcode and state have to be equal after normalizing.
+/// * daten: Service data for database access.
+/// * street: Affected minimum similarity of streets from 0 to 1 or None for the parameter.
+/// * town: Affected minimum similarity of towns from 0 to 1 or None for the parameter.
+/// * returns: Groups of duplicate addresses, the most used address first, or possibly errors.
+pub fn get_duplicate_addresses<'a>(
+    daten: &'a ServiceDaten,
+    street: Option<f64>,
+    town: Option<f64>,
+) -> Result<Vec<Vec<AdAdresse>>> {
+    let street = street.unwrap_or_else(parameter::get_ad_street_similarity);
+    let town = town.unwrap_or_else(parameter::get_ad_town_similarity);
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let l = reps::ad_adresse::get_list(&db, daten.mandant_nr)?;
+    let mut used: HashMap<String, usize> = HashMap::new();
+    for s in reps::ad_sitz::get_list_ext(&db, None, None)? {
+        if let Some(a) = s.adresse_uid {
+            *used.entry(a).or_insert(0) += 1;
+        }
+    }
+    let mut v: Vec<Vec<AdAdresse>> = vec![];
+    for g in get_duplicate_groups(&l, street, town) {
+        let mut group: Vec<AdAdresse> = g.iter().map(|i| l[*i].clone()).collect();
+        group.sort_by_key(|a| std::cmp::Reverse(used.get(&a.uid).cloned().unwrap_or(0)));
+        v.push(group);
     }
     Ok(v)
 }
+
+/// Merge addresses into one address. All sites of the merged addresses get the remaining address
+/// and the merged addresses are deleted.
+/// * daten: Service data for database access.
+/// * uid: Affected ID of the remaining address.
+/// * uids: Affected IDs of the merged addresses.
+/// * returns: Number of changed sites or possibly errors.
+pub fn merge_addresses<'a>(
+    daten: &'a ServiceDaten,
+    uid: &String,
+    uids: &Vec<String>,
+) -> Result<usize> {
+    let is_de = daten.config.is_de();
+    let merged: Vec<&String> = uids.iter().filter(|u| *u != uid).collect();
+    if merged.is_empty() {
+        return Err(RsbpError::error_msg(M::AD030, is_de));
+    }
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<usize, RsbpError, _>(|| {
+        if reps::ad_adresse::get(&db, &daten.mandant_nr, uid)?.is_none() {
+            return Err(RsbpError::error_msg(M::M1013, is_de));
+        }
+        let mut count = 0;
+        for u in merged {
+            for s in reps::ad_sitz::get_list_ext(&db, None, Some(u))? {
+                reps::ad_sitz::save(
+                    &mut db,
+                    &s.mandant_nr,
+                    &s.person_uid,
+                    &s.reihenfolge,
+                    &s.uid,
+                    &s.typ,
+                    &s.name,
+                    &Some(uid.clone()),
+                    &s.telefon,
+                    &s.fax,
+                    &s.mobil,
+                    &s.email,
+                    &s.homepage,
+                    &s.postfach,
+                    &s.bemerkung,
+                    &s.sitz_status,
+                )?;
+                count += 1;
+            }
+            diary_service::move_position_addresses(&mut db, None, Some(u), Some(uid))?;
+            if let Some(a) = reps::ad_adresse::get(&db, &daten.mandant_nr, u)? {
+                reps::ad_adresse::delete(&mut db, &a)?;
+            }
+        }
+        Ok(count)
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Get groups of indices of similar addresses.
+fn get_duplicate_groups(l: &Vec<AdAdresse>, street: f64, town: f64) -> Vec<Vec<usize>> {
+    let n: Vec<[String; 5]> = l
+        .iter()
+        .map(|a| {
+            [
+                normalize_street(&functions::ostr_to_str(&a.strasse)),
+                normalize_text(&functions::ostr_to_str(&a.hausnr)),
+                normalize_text(&functions::ostr_to_str(&a.plz)),
+                normalize_text(&a.ort),
+                normalize_text(&functions::ostr_to_str(&a.staat)),
+            ]
+        })
+        .collect();
+    // Index der ersten Adresse der Gruppe
+    let mut group: Vec<usize> = (0..l.len()).collect();
+    for i in 0..l.len() {
+        for j in (i + 1)..l.len() {
+            if group[j] != j {
+                continue;
+            }
+            let (a, b) = (&n[i], &n[j]);
+            if a[1] == b[1]
+                && a[2] == b[2]
+                && a[4] == b[4]
+                && functions::get_similarity(&a[0], &b[0]) >= street
+                && functions::get_similarity(&a[3], &b[3]) >= town
+            {
+                group[j] = group[i];
+            }
+        }
+    }
+    let mut v: Vec<Vec<usize>> = vec![];
+    for i in 0..l.len() {
+        if group[i] == i {
+            let g: Vec<usize> = (i..l.len()).filter(|j| group[*j] == i).collect();
+            if g.len() > 1 {
+                v.push(g);
+            }
+        }
+    }
+    v
+}
+
+/// Normalize a part of an address: lower case, umlauts replaced, only letters and digits.
+fn normalize_text(s: &str) -> String {
+    s.to_lowercase()
+        .replace('ä', "ae")
+        .replace('ö', "oe")
+        .replace('ü', "ue")
+        .replace('ß', "ss")
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+/// Get a normalized address for comparison independent of the split of street and house number.
+fn get_address_key(a: &AdAdresse) -> [String; 4] {
+    [
+        normalize_text(&format!(
+            "{}{}",
+            functions::ostr_to_str(&a.strasse),
+            functions::ostr_to_str(&a.hausnr)
+        )),
+        normalize_text(&functions::ostr_to_str(&a.plz)),
+        normalize_text(&a.ort),
+        normalize_text(&functions::ostr_to_str(&a.staat)),
+    ]
+}
+
+/// Normalize a street with abbreviations, e.g. Hauptstraße and Haupt-Str. are equal.
+fn normalize_street(s: &str) -> String {
+    let mut n = normalize_text(s);
+    for (long, short) in [("strasse", "str"), ("street", "st")].iter() {
+        if n.ends_with(long) {
+            n.truncate(n.len() - long.len());
+            n.push_str(short);
+        }
+    }
+    n
+}
+
+/// Column headers of the label CSV.
+pub const LABEL_CSV_HEADER: [&str; 8] = [
+    "fanrede", "name", "name2", "strasse", "postfach", "plz", "ort", "staat",
+];
+
+/// Get serial letters from a template for the filtered persons.
+/// Placeholders are {anrede}, {fanrede}, {titel}, {vorname}, {praedikat}, {name}, {name1}, {name2},
+/// {strasse}, {hausnr}, {postfach}, {plz}, {ort}, {staat}, {telefon}, {email}, {geburt} and {datum}.
+/// The address is taken from the first site with address.
+/// * daten: Service data for database access.
+/// * template: Affected template file name.
+/// * name: Affected name filter with wildcards.
+/// * actual: Affected only persons with status 0.
+/// * combined: Should all letters be combined in one file separated by form feeds?
+/// * returns: File names with lines of the letters or possibly errors.
+pub fn get_serial_letters<'a>(
+    daten: &'a ServiceDaten,
+    template: &str,
+    name: &Option<String>,
+    actual: bool,
+    combined: bool,
+) -> Result<Vec<(String, Vec<String>)>> {
+    let is_de = daten.config.is_de();
+    let t = match fs::read_to_string(template) {
+        Ok(t) => t,
+        Err(_) => {
+            return Err(RsbpError::error_string(
+                M::mecf(M::M1034_, &[template], is_de).as_str(),
+            ))
+        }
+    };
+    let ext = std::path::Path::new(template)
+        .extension()
+        .map_or("txt".to_string(), |e| e.to_string_lossy().to_string());
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let today = daten.get_today();
+    let mut v: Vec<(String, Vec<String>)> = vec![];
+    let mut all: Vec<String> = vec![];
+    let mut files: HashSet<String> = HashSet::new();
+    for p in get_person_ext_list0(&db, name.as_ref(), actual)? {
+        let values = get_letter_values(&p, &today, is_de);
+        let mut lines: Vec<String> = t.lines().map(|l| replace_values(l, &values)).collect();
+        if combined {
+            if !all.is_empty() {
+                all.push("\u{c}".into());
+            }
+            all.append(&mut lines);
+        } else {
+            let f = format!(
+                "{}_{}",
+                p.person.name1,
+                functions::ostr_to_str(&p.person.vorname)
+            );
+            let f = f
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect::<String>();
+            // Persons with equal names get a running number.
+            let mut file = functions::get_file_name(&f, false, false, &ext);
+            let mut nr = 1;
+            while !files.insert(file.clone()) {
+                nr += 1;
+                file = functions::get_file_name(&format!("{}_{}", f, nr), false, false, &ext);
+            }
+            v.push((file, lines));
+        }
+    }
+    if combined {
+        v.push((functions::get_file_name("letters", true, false, &ext), all));
+    }
+    Ok(v)
+}
+
+/// Get the addresses of the filtered persons as CSV lines with the header LABEL_CSV_HEADER for label printers.
+/// Persons without address are skipped.
+/// * daten: Service data for database access.
+/// * name: Affected name filter with wildcards.
+/// * actual: Affected only persons with status 0.
+/// * returns: Vector of CSV lines or possibly errors.
+pub fn get_label_csv<'a>(
+    daten: &'a ServiceDaten,
+    name: &Option<String>,
+    actual: bool,
+) -> Result<Vec<String>> {
+    let is_de = daten.config.is_de();
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let today = daten.get_today();
+    let mut v: Vec<String> = vec![functions::to_csv(&LABEL_CSV_HEADER)];
+    for p in get_person_ext_list0(&db, name.as_ref(), actual)? {
+        if !p.sitze.iter().any(|s| s.adresse.is_some()) {
+            continue;
+        }
+        let values = get_letter_values(&p, &today, is_de);
+        let street = [values["strasse"].as_str(), values["hausnr"].as_str()]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .cloned()
+            .collect::<Vec<&str>>()
+            .join(" ");
+        v.push(functions::to_csv(&[
+            values["fanrede"].as_str(),
+            values["name"].as_str(),
+            values["name2"].as_str(),
+            street.as_str(),
+            values["postfach"].as_str(),
+            values["plz"].as_str(),
+            values["ort"].as_str(),
+            values["staat"].as_str(),
+        ]));
+    }
+    Ok(v)
+}
+
+/// Get the placeholder values of a person for serial letters.
+/// anrede 0 is the formal salutation, 1 the informal salutation with first name.
+/// fanrede 0 is the form of address by gender, e.g. Mr, other values are without form of address.
+fn get_letter_values(
+    p: &AdPersonExt,
+    date: &NaiveDate,
+    is_de: bool,
+) -> HashMap<&'static str, String> {
+    let e = &p.person;
+    let o = |s: &Option<String>| functions::ostr_to_str(s);
+    let join = |l: &[&str]| {
+        l.iter()
+            .filter(|s| !s.is_empty())
+            .cloned()
+            .collect::<Vec<&str>>()
+            .join(" ")
+    };
+    let (titel, vorname, praedikat) = (o(&e.titel), o(&e.vorname), o(&e.praedikat));
+    let formal = join(&[&titel, &praedikat, &e.name1]);
+    let first = if vorname.is_empty() {
+        formal.clone()
+    } else {
+        vorname.clone()
+    };
+    let anrede = match (e.anrede, e.geschlecht.as_str()) {
+        (1, "M") => M::mecf(M::AD025_, &[first.as_str()], is_de),
+        (1, "F") => M::mecf(M::AD026_, &[first.as_str()], is_de),
+        (1, _) => M::mecf(M::AD027_, &[first.as_str()], is_de),
+        (_, "M") => M::mecf(M::AD022_, &[formal.as_str()], is_de),
+        (_, "F") => M::mecf(M::AD023_, &[formal.as_str()], is_de),
+        _ => M::mec(M::AD024, is_de).into_owned(),
+    };
+    let fanrede = match (e.fanrede, e.geschlecht.as_str()) {
+        (0, "M") => M::mec(M::AD028, is_de).into_owned(),
+        (0, "F") => M::mec(M::AD029, is_de).into_owned(),
+        _ => String::new(),
+    };
+    let s = p
+        .sitze
+        .iter()
+        .find(|s| s.adresse.is_some())
+        .or_else(|| p.sitze.first());
+    let si = s.map(|s| &s.sitz);
+    let a = s.and_then(|s| s.adresse.as_ref());
+    let mut v: HashMap<&'static str, String> = HashMap::new();
+    v.insert("anrede", anrede);
+    v.insert("fanrede", fanrede);
+    v.insert("titel", titel.clone());
+    v.insert("vorname", vorname.clone());
+    v.insert("praedikat", praedikat.clone());
+    v.insert("name", join(&[&titel, &vorname, &praedikat, &e.name1]));
+    v.insert("name1", e.name1.clone());
+    v.insert("name2", o(&e.name2));
+    v.insert("strasse", a.map_or(String::new(), |a| o(&a.strasse)));
+    v.insert("hausnr", a.map_or(String::new(), |a| o(&a.hausnr)));
+    v.insert("postfach", si.map_or(String::new(), |s| o(&s.postfach)));
+    v.insert("plz", a.map_or(String::new(), |a| o(&a.plz)));
+    v.insert("ort", a.map_or(String::new(), |a| a.ort.clone()));
+    v.insert("staat", a.map_or(String::new(), |a| o(&a.staat)));
+    v.insert("telefon", si.map_or(String::new(), |s| o(&s.telefon)));
+    v.insert("email", si.map_or(String::new(), |s| o(&s.email)));
+    let df = if is_de { "%d.%m.%Y" } else { "%Y-%m-%d" };
+    v.insert(
+        "geburt",
+        e.geburt.map_or(String::new(), |d| d.format(df).to_string()),
+    );
+    v.insert("datum", date.format(df).to_string());
+    v
+}
+
+/// Replace the placeholders of a template line. Unknown placeholders are kept.
+/// Values are not searched for placeholders again.
+fn replace_values(line: &str, values: &HashMap<&'static str, String>) -> String {
+    let mut s = String::new();
+    let mut rest = line;
+    while let Some(i) = rest.find('{') {
+        s.push_str(&rest[..i]);
+        rest = &rest[i..];
+        match rest
+            .find('}')
+            .and_then(|j| values.get(&rest[1..j]).map(|v| (j, v)))
+        {
+            Some((j, v)) => {
+                s.push_str(v);
+                rest = &rest[j + 1..];
+            }
+            None => {
+                s.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    s.push_str(rest);
+    s
+}
+
+/// Get persons with all their sites as vCard 4.0 lines.
+/// * daten: Service data for database access.
+/// * uid: Affected person ID or None for all persons.
+/// * returns: Vector of vCard lines or possibly errors.
+pub fn get_vcard_file<'a>(daten: &'a ServiceDaten, uid: &Option<String>) -> Result<Vec<String>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let mut v: Vec<String> = vec![];
+    for p in get_person_ext_list0(&db, None, false)? {
+        if uid.as_ref().map_or(false, |u| *u != p.person.uid) {
+            continue;
+        }
+        for l in get_vcard(&p) {
+            v.extend(fold_line(&l));
+        }
+    }
+    Ok(v)
+}
+
+/// Read a vCard file and match the persons by ID or by name and birthday without saving anything.
+/// Columns and sites which are not part of a vCard are taken from existing persons.
+/// * daten: Service data for database access.
+/// * file: Affected vCard file name.
+/// * returns: Persons to import with report of new and changed persons or possibly errors.
+pub fn get_vcard_import<'a>(
+    daten: &'a ServiceDaten,
+    file: &str,
+) -> Result<(Vec<AdPersonExt>, Vec<String>)> {
+    let is_de = daten.config.is_de();
+    let content = match fs::read_to_string(file) {
+        Ok(c) => c,
+        Err(_) => {
+            return Err(RsbpError::error_string(
+                M::mecf(M::M1034_, &[file], is_de).as_str(),
+            ))
+        }
+    };
+    let mut l = from_vcard(daten.mandant_nr, content.as_str());
+    if l.iter().any(|p| p.person.name1.is_empty()) {
+        return Err(RsbpError::error_msg(M::AD015, is_de));
+    }
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let v = match_persons(&db, &mut l, true)?;
+    for p in l.iter_mut() {
+        if p.person.geschlecht.is_empty() {
+            p.person.geschlecht = "N".into();
+        }
+    }
+    Ok((l, v))
+}
+
+/// Get the vCard 4.0 lines of a person. Sites are property groups site1, site2, ...
+fn get_vcard(p: &AdPersonExt) -> Vec<String> {
+    let e = &p.person;
+    let o = |s: &Option<String>| escape_text(&functions::ostr_to_str(s));
+    let mut v: Vec<String> = vec!["BEGIN:VCARD".into(), "VERSION:4.0".into()];
+    v.push(format!("UID:urn:uuid:{}", e.uid));
+    let fname = [&e.titel, &e.vorname, &e.praedikat, &Some(e.name1.clone())]
+        .iter()
+        .filter_map(|n| n.as_ref().filter(|n| !n.is_empty()).cloned())
+        .collect::<Vec<String>>()
+        .join(" ");
+    v.push(format!("FN:{}", escape_text(&fname)));
+    v.push(format!(
+        "N:{};{};{};{};",
+        escape_text(&e.name1),
+        o(&e.vorname),
+        o(&e.praedikat),
+        o(&e.titel)
+    ));
+    if !e.geschlecht.is_empty() {
+        let g = match e.geschlecht.as_str() {
+            "M" | "F" => e.geschlecht.as_str(),
+            _ => "N",
+        };
+        v.push(format!("GENDER:{}", g));
+    }
+    if let Some(g) = e.geburt {
+        v.push(format!("BDAY:{}", g.format("%Y%m%d")));
+    }
+    for (i, s) in p.sitze.iter().enumerate() {
+        let g = format!("site{}.", i + 1);
+        let si = &s.sitz;
+        v.push(format!("{}X-RSBP-SITE:{}", g, escape_text(&si.name)));
+        let props = [
+            ("TEL;TYPE=voice", &si.telefon),
+            ("TEL;TYPE=cell", &si.mobil),
+            ("TEL;TYPE=fax", &si.fax),
+            ("EMAIL", &si.email),
+            ("URL", &si.homepage),
+        ];
+        for (k, val) in props.iter() {
+            if let Some(val) = val.as_ref().filter(|val| !val.is_empty()) {
+                v.push(format!("{}{}:{}", g, k, escape_text(val)));
+            }
+        }
+        if let Some(a) = &s.adresse {
+            let street = [&a.strasse, &a.hausnr]
+                .iter()
+                .filter_map(|n| n.as_ref().filter(|n| !n.is_empty()).cloned())
+                .collect::<Vec<String>>()
+                .join(" ");
+            v.push(format!(
+                "{}ADR:{};;{};{};;{};{}",
+                g,
+                o(&si.postfach),
+                escape_text(&street),
+                escape_text(&a.ort),
+                o(&a.plz),
+                o(&a.staat)
+            ));
+        }
+    }
+    v.push("END:VCARD".into());
+    v
+}
+
+/// Get the persons of vCard content.
+fn from_vcard(mandant_nr: i32, content: &str) -> Vec<AdPersonExt> {
+    let mut lines: Vec<String> = vec![];
+    for l in content.lines() {
+        if l.starts_with(' ') || l.starts_with('\t') {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(&l[1..]);
+            }
+        } else {
+            lines.push(l.to_string());
+        }
+    }
+    let mut v: Vec<AdPersonExt> = vec![];
+    let mut op: Option<AdPersonExt> = None;
+    let mut groups: HashMap<String, usize> = HashMap::new();
+    for l in lines {
+        let (np, value) = match split_vcard_line(&l) {
+            Some(x) => x,
+            None => continue,
+        };
+        let mut params = np.split(';');
+        let gname = params.next().unwrap_or_default().to_uppercase();
+        let (group, name) = match gname.find('.') {
+            Some(i) => (gname[..i].to_string(), gname[i + 1..].to_string()),
+            None => (String::new(), gname),
+        };
+        let types = params
+            .filter(|p| p.to_uppercase().starts_with("TYPE="))
+            .map(|p| p[5..].replace('"', "").to_lowercase())
+            .collect::<Vec<String>>()
+            .join(",");
+        if name == "BEGIN" {
+            groups.clear();
+            op = Some(AdPersonExt {
+                person: new_person(mandant_nr),
+                sitze: vec![],
+            });
+            continue;
+        }
+        let p = match op.as_mut() {
+            Some(p) => p,
+            None => continue,
+        };
+        let c = split_vcard_value(value, ';');
+        let c0 = c.get(0).cloned().unwrap_or_default();
+        match name.as_str() {
+            "END" => {
+                if let Some(mut p) = op.take() {
+                    if p.person.name1.is_empty() {
+                        p.person.name1 = p.person.name2.take().unwrap_or_default();
+                    }
+                    p.person.name2 = None;
+                    v.push(p);
+                }
+            }
+            "UID" => {
+                let u = c0.to_lowercase();
+                p.person.uid = u.trim_start_matches("urn:uuid:").to_string();
+            }
+            // Formatierter Name nur, falls N fehlt.
+            "FN" => p.person.name2 = functions::to_ostr(c0.trim()),
+            "N" => {
+                let n = |i: usize| c.get(i).and_then(|s| functions::to_ostr(s.trim()));
+                p.person.name1 = c0.trim().to_string();
+                p.person.vorname = n(1);
+                p.person.praedikat = n(2);
+                p.person.titel = n(3);
+            }
+            "GENDER" => {
+                p.person.geschlecht = match c0.to_uppercase().chars().next() {
+                    Some('M') => "M",
+                    Some('F') => "F",
+                    _ => "N",
+                }
+                .into();
+            }
+            "BDAY" => {
+                let b = c0.replace('-', "");
+                p.person.geburt = NaiveDate::parse_from_str(b.as_str(), "%Y%m%d").ok();
+                p.person.geburtk = p
+                    .person
+                    .geburt
+                    .map_or(0, |g| (g.month() * 100 + g.day()) as i32);
+            }
+            "X-RSBP-SITE" | "TEL" | "EMAIL" | "URL" | "ADR" => {
+                let i = *groups.entry(group).or_insert_with(|| {
+                    p.sitze.push(new_site(mandant_nr));
+                    p.sitze.len() - 1
+                });
+                let s = &mut p.sitze[i];
+                let val = functions::to_ostr(c0.trim());
+                match name.as_str() {
+                    "X-RSBP-SITE" => s.sitz.name = c0.trim().to_string(),
+                    "TEL" if types.contains("cell") => s.sitz.mobil = val,
+                    "TEL" if types.contains("fax") => s.sitz.fax = val,
+                    "TEL" if s.sitz.telefon.is_none() => s.sitz.telefon = val,
+                    "EMAIL" if s.sitz.email.is_none() => s.sitz.email = val,
+                    "URL" if s.sitz.homepage.is_none() => s.sitz.homepage = val,
+                    "ADR" => {
+                        let n = |i: usize| c.get(i).and_then(|s| functions::to_ostr(s.trim()));
+                        s.sitz.postfach = n(0);
+                        let (strasse, hausnr) = split_street(&n(2).unwrap_or_default());
+                        s.adresse = Some(AdAdresse {
+                            mandant_nr,
+                            uid: String::new(),
+                            staat: n(6),
+                            plz: n(5),
+                            ort: n(3).unwrap_or_default(),
+                            strasse,
+                            hausnr,
+                            angelegt_von: None,
+                            angelegt_am: None,
+                            geaendert_von: None,
+                            geaendert_am: None,
+                        });
+                    }
+                    _ => (),
+                };
+            }
+            _ => (),
+        };
+    }
+    v
+}
+
+/// Get a new person without values.
+fn new_person(mandant_nr: i32) -> AdPerson {
+    AdPerson {
+        mandant_nr,
+        uid: String::new(),
+        typ: 1,
+        geschlecht: String::new(),
+        geburt: None,
+        geburtk: 0,
+        anrede: 0,
+        fanrede: 0,
+        name1: String::new(),
+        name2: None,
+        praedikat: None,
+        vorname: None,
+        titel: None,
+        person_status: 0,
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+    }
+}
+
+/// Get a new site without values.
+fn new_site(mandant_nr: i32) -> AdSitzExt {
+    AdSitzExt {
+        sitz: AdSitz {
+            mandant_nr,
+            person_uid: String::new(),
+            reihenfolge: 0,
+            uid: String::new(),
+            typ: 1,
+            name: String::new(),
+            adresse_uid: None,
+            telefon: None,
+            fax: None,
+            mobil: None,
+            email: None,
+            homepage: None,
+            postfach: None,
+            bemerkung: None,
+            sitz_status: 0,
+            angelegt_von: None,
+            angelegt_am: None,
+            geaendert_von: None,
+            geaendert_am: None,
+        },
+        adresse: None,
+    }
+}
+
+/// Split a street with trailing house number, e.g. Main Street 12a or Hauptstraße 12 a.
+fn split_street(s: &str) -> (Option<String>, Option<String>) {
+    let t: Vec<&str> = s.split_whitespace().collect();
+    // The house number starts with a digit and may be followed by short parts, e.g. 12 a or 3 - 5.
+    for (i, x) in t.iter().enumerate().skip(1) {
+        if x.starts_with(|c: char| c.is_ascii_digit())
+            && t[i + 1..]
+                .iter()
+                .all(|x| x.chars().count() <= 2 || x.starts_with(|c: char| c.is_ascii_digit()))
+        {
+            return (
+                functions::to_ostr(t[..i].join(" ").as_str()),
+                functions::to_ostr(t[i..].join(" ").as_str()),
+            );
+        }
+    }
+    (functions::to_ostr(s.trim()), None)
+}
+
+/// Escape a vCard or iCalendar text value.
+fn escape_text(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace(',', "\\,")
+        .replace(';', "\\;")
+}
+
+/// Split a vCard line into name with parameters and value at the first colon outside quotes.
+fn split_vcard_line(l: &str) -> Option<(&str, &str)> {
+    let mut quoted = false;
+    for (i, c) in l.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => return Some((&l[..i], &l[i + 1..])),
+            _ => (),
+        };
+    }
+    None
+}
+
+/// Split an escaped vCard value into unescaped components.
+fn split_vcard_value(value: &str, sep: char) -> Vec<String> {
+    let mut v: Vec<String> = vec![];
+    let mut s = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') | Some('N') => s.push('\n'),
+                Some(c2) => s.push(c2),
+                None => (),
+            };
+        } else if c == sep {
+            v.push(s);
+            s = String::new();
+        } else {
+            s.push(c);
+        }
+    }
+    v.push(s);
+    v
+}
+
+/// Fold a vCard or iCalendar line into lines of at most 75 octets.
+fn fold_line(l: &str) -> Vec<String> {
+    let mut v: Vec<String> = vec![];
+    let mut s = String::new();
+    for c in l.chars() {
+        let max = if v.is_empty() { 75 } else { 74 };
+        if s.len() + c.len_utf8() > max {
+            v.push(s);
+            s = String::new();
+        }
+        s.push(c);
+    }
+    v.push(s);
+    v.iter()
+        .enumerate()
+        .map(|(i, s)| if i == 0 { s.clone() } else { format!(" {}", s) })
+        .collect()
+}
+
+/// Get all persons with their sites and addresses ordered by name.
+fn get_person_ext_list0(
+    db: &DbContext,
+    name: Option<&String>,
+    actual: bool,
+) -> Result<Vec<AdPersonExt>> {
+    let mandant_nr = db.daten.mandant_nr;
+    let addresses: HashMap<String, AdAdresse> = reps::ad_adresse::get_list(db, mandant_nr)?
+        .into_iter()
+        .map(|a| (a.uid.clone(), a))
+        .collect();
+    let mut sites: HashMap<String, Vec<AdSitzExt>> = HashMap::new();
+    for s in reps::ad_sitz::get_list_ext(db, None, None)? {
+        let adresse = s
+            .adresse_uid
+            .as_ref()
+            .and_then(|a| addresses.get(a))
+            .cloned();
+        sites
+            .entry(s.person_uid.clone())
+            .or_insert_with(Vec::new)
+            .push(AdSitzExt { sitz: s, adresse });
+    }
+    let l = reps::ad_person::get_list_ext2(db, name, actual)?
+        .into_iter()
+        .map(|p| AdPersonExt {
+            sitze: sites.remove(&p.uid).unwrap_or_default(),
+            person: p,
+        })
+        .collect();
+    Ok(l)
+}
+
+/// Get the CSV values of a person with one row per site.
+fn get_csv_rows(p: &AdPersonExt) -> Vec<Vec<String>> {
+    let e = &p.person;
+    let o = |s: &Option<String>| functions::ostr_to_str(s);
+    let pv = vec![
+        e.uid.clone(),
+        e.typ.to_string(),
+        e.geschlecht.clone(),
+        functions::ond_to_str(&e.geburt),
+        e.anrede.to_string(),
+        e.fanrede.to_string(),
+        e.name1.clone(),
+        o(&e.name2),
+        o(&e.praedikat),
+        o(&e.vorname),
+        o(&e.titel),
+        e.person_status.to_string(),
+    ];
+    let mut v: Vec<Vec<String>> = vec![];
+    for s in p.sitze.iter() {
+        let mut r = pv.clone();
+        let si = &s.sitz;
+        r.extend(vec![
+            si.uid.clone(),
+            si.typ.to_string(),
+            si.name.clone(),
+            o(&si.telefon),
+            o(&si.fax),
+            o(&si.mobil),
+            o(&si.email),
+            o(&si.homepage),
+            o(&si.postfach),
+            o(&si.bemerkung),
+            si.sitz_status.to_string(),
+        ]);
+        match &s.adresse {
+            Some(a) => r.extend(vec![
+                a.uid.clone(),
+                o(&a.staat),
+                o(&a.plz),
+                a.ort.clone(),
+                o(&a.strasse),
+                o(&a.hausnr),
+            ]),
+            None => r.extend(vec![String::new(); 6]),
+        }
+        v.push(r);
+    }
+    if v.is_empty() {
+        let mut r = pv;
+        r.extend(vec![String::new(); ADDRESS_CSV_HEADER.len() - 12]);
+        v.push(r);
+    }
+    v
+}
+
+/// Get person and optional site with address of a CSV row.
+fn from_csv_row(mandant_nr: i32, c: &Vec<String>) -> (AdPerson, Option<AdSitzExt>) {
+    let t = |i: usize| c[i].trim().to_string();
+    let o = |i: usize| functions::to_ostr(c[i].trim());
+    let n = |i: usize| functions::to_i32(c[i].trim());
+    let geburt = functions::to_ond(c[3].trim());
+    let p = AdPerson {
+        mandant_nr,
+        uid: t(0),
+        typ: n(1),
+        geschlecht: t(2),
+        geburtk: geburt.map_or(0, |g| (g.month() * 100 + g.day()) as i32),
+        geburt,
+        anrede: n(4),
+        fanrede: n(5),
+        name1: t(6),
+        name2: o(7),
+        praedikat: o(8),
+        vorname: o(9),
+        titel: o(10),
+        person_status: n(11),
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+    };
+    if c[12..].iter().all(|v| v.trim().is_empty()) {
+        return (p, None);
+    }
+    let adresse = if c[23..].iter().all(|v| v.trim().is_empty()) {
+        None
+    } else {
+        Some(AdAdresse {
+            mandant_nr,
+            uid: t(23),
+            staat: o(24),
+            plz: o(25),
+            ort: t(26),
+            strasse: o(27),
+            hausnr: o(28),
+            angelegt_von: None,
+            angelegt_am: None,
+            geaendert_von: None,
+            geaendert_am: None,
+        })
+    };
+    let sitz = AdSitz {
+        mandant_nr,
+        person_uid: p.uid.clone(),
+        reihenfolge: 0,
+        uid: t(12),
+        typ: n(13),
+        name: t(14),
+        adresse_uid: adresse.as_ref().map(|a| a.uid.clone()),
+        telefon: o(15),
+        fax: o(16),
+        mobil: o(17),
+        email: o(18),
+        homepage: o(19),
+        postfach: o(20),
+        bemerkung: o(21),
+        sitz_status: n(22),
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+    };
+    (p, Some(AdSitzExt { sitz, adresse }))
+}
+
+/// Get the ordered list of sites with addresses of a person.
+fn get_site_list0(db: &DbContext, person_uid: &String) -> Result<Vec<AdSitzExt>> {
+    let mut l: Vec<AdSitzExt> = vec![];
+    for s in reps::ad_sitz::get_list_ext(db, Some(person_uid), None)? {
+        let adresse = match &s.adresse_uid {
+            Some(a) => reps::ad_adresse::get(db, &db.daten.mandant_nr, a)?,
+            None => None,
+        };
+        l.push(AdSitzExt { sitz: s, adresse });
+    }
+    Ok(l)
+}
+
+/// Save a person with its ordered sites and their addresses.
+fn save_person0(db: &mut DbContext, p: &AdPerson, sites: &Vec<AdSitzExt>) -> Result<AdPerson> {
+    let mandant_nr = db.daten.mandant_nr;
+    let is_de = db.daten.config.is_de();
+    let geburtk = p.geburt.map_or(0, |g| (g.month() * 100 + g.day()) as i32);
+    let e = reps::ad_person::save(
+        db,
+        &mandant_nr,
+        &get_uid(&p.uid),
+        &p.typ,
+        &p.geschlecht,
+        &p.geburt,
+        &geburtk,
+        &p.anrede,
+        &p.fanrede,
+        &p.name1.trim().to_string(),
+        &trim(&p.name2),
+        &trim(&p.praedikat),
+        &trim(&p.vorname),
+        &trim(&p.titel),
+        &p.person_status,
+    )?;
+    let old = reps::ad_sitz::get_list_ext(db, Some(&e.uid), None)?;
+    let mut uids: Vec<String> = vec![];
+    for (i, s) in sites.iter().enumerate() {
+        let adresse_uid = match &s.adresse {
+            Some(a) => Some(save_address0(db, a)?.uid),
+            None => None,
+        };
+        let reihenfolge = i as i32 + 1;
+        let uid = get_uid(&s.sitz.uid);
+        let mut angelegt = (None, None);
+        if let Some(o) = old.iter().find(|o| o.uid == uid) {
+            if o.reihenfolge != reihenfolge {
+                // Primärschlüssel geändert
+                reps::ad_sitz::delete(db, o)?;
+                angelegt = (o.angelegt_von.clone(), o.angelegt_am);
+            }
+        }
+        let name = match s.sitz.name.trim() {
+            "" => M::mec(M::AD014, is_de).to_string(),
+            n => n.to_string(),
+        };
+        reps::ad_sitz::save0(
+            db,
+            &mandant_nr,
+            &e.uid,
+            &reihenfolge,
+            &uid,
+            &s.sitz.typ,
+            &name,
+            &adresse_uid,
+            &trim(&s.sitz.telefon),
+            &trim(&s.sitz.fax),
+            &trim(&s.sitz.mobil),
+            &trim(&s.sitz.email),
+            &trim(&s.sitz.homepage),
+            &trim(&s.sitz.postfach),
+            &trim(&s.sitz.bemerkung),
+            &s.sitz.sitz_status,
+            &angelegt.0,
+            &angelegt.1,
+            &None,
+            &None,
+        )?;
+        uids.push(uid);
+    }
+    let removed: Vec<AdSitz> = old.into_iter().filter(|o| !uids.contains(&o.uid)).collect();
+    for o in removed.iter() {
+        reps::ad_sitz::delete(db, o)?;
+    }
+    delete_unused_addresses(db, &removed)?;
+    Ok(e)
+}
+
+/// Save an address, empty ID for a new address.
+fn save_address0(db: &mut DbContext, a: &AdAdresse) -> Result<AdAdresse> {
+    let e = reps::ad_adresse::save(
+        db,
+        &db.daten.mandant_nr,
+        &get_uid(&a.uid),
+        &trim(&a.staat),
+        &trim(&a.plz),
+        &a.ort.trim().to_string(),
+        &trim(&a.strasse),
+        &trim(&a.hausnr),
+    )?;
+    Ok(e)
+}
+
+/// Delete the addresses of removed sites which are no longer used.
+fn delete_unused_addresses(db: &mut DbContext, removed: &Vec<AdSitz>) -> Result<()> {
+    let mandant_nr = db.daten.mandant_nr;
+    for s in removed.iter() {
+        if let Some(auid) = &s.adresse_uid {
+            if reps::ad_sitz::get_list_ext(db, None, Some(auid))?.is_empty() {
+                if let Some(a) = reps::ad_adresse::get(db, &mandant_nr, auid)? {
+                    diary_service::move_position_addresses(db, None, Some(auid), None)?;
+                    reps::ad_adresse::delete(db, &a)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Get a trimmed optional string, None if empty.
+fn trim(s: &Option<String>) -> Option<String> {
+    s.as_ref().and_then(|s| functions::to_ostr(s.trim()))
+}
+
+/// Get the ID or a new ID if empty.
+fn get_uid(uid: &String) -> String {
+    if uid.is_empty() {
+        return functions::get_uid();
+    }
+    uid.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_csv_row, get_csv_rows, ADDRESS_CSV_HEADER};
+    use chrono::NaiveDate;
+    use rsbp_rep::{
+        models::AdAdresse,
+        models_ext::{AdBirthday, AdPersonExt},
+    };
+
+    #[test]
+    fn csv_rows() {
+        let mut c = vec![String::new(); ADDRESS_CSV_HEADER.len()];
+        c[0] = "p1".into();
+        c[2] = "F".into();
+        c[3] = "1970-09-01".into();
+        c[6] = "Name".into();
+        c[9] = "Vorname".into();
+        let (p, s) = from_csv_row(0, &c);
+        assert_eq!(901, p.geburtk);
+        assert!(s.is_none());
+        let e = AdPersonExt {
+            person: p,
+            sitze: vec![],
+        };
+        let mut c0 = c.clone();
+        for i in [1, 4, 5, 11].iter() {
+            c0[*i] = "0".into();
+        }
+        assert_eq!(vec![c0], get_csv_rows(&e));
+        c[12] = "s1".into();
+        c[14] = "Home".into();
+        c[18] = "a@b.c".into();
+        c[23] = "a1".into();
+        c[26] = "Town".into();
+        let (p, s) = from_csv_row(0, &c);
+        let s = s.unwrap();
+        assert_eq!(Some("a1".to_string()), s.sitz.adresse_uid);
+        assert_eq!("Town", s.adresse.as_ref().unwrap().ort);
+        let e = AdPersonExt {
+            person: p,
+            sitze: vec![s],
+        };
+        let r = get_csv_rows(&e);
+        assert_eq!(1, r.len());
+        assert_eq!("a@b.c", r[0][18]);
+        assert_eq!("Town", r[0][26]);
+    }
+
+    #[test]
+    fn vcard() {
+        let (p, s) = from_csv_row(0, &{
+            let mut c = vec![String::new(); ADDRESS_CSV_HEADER.len()];
+            c[0] = "p1".into();
+            c[2] = "F".into();
+            c[3] = "1970-09-01".into();
+            c[6] = "Name; Semi".into();
+            c[9] = "Vorname".into();
+            c[12] = "s1".into();
+            c[14] = "Home".into();
+            c[15] = "0123".into();
+            c[17] = "0170".into();
+            c[18] = "a@b.c".into();
+            c[23] = "a1".into();
+            c[26] = "Town".into();
+            c[27] = "Main Street".into();
+            c[28] = "12a".into();
+            c
+        });
+        let e = AdPersonExt {
+            person: p,
+            sitze: vec![s.unwrap()],
+        };
+        let lines = super::get_vcard(&e);
+        assert!(lines.contains(&"N:Name\\; Semi;Vorname;;;".to_string()));
+        assert!(lines.contains(&"site1.ADR:;;Main Street 12a;Town;;;".to_string()));
+        let l = super::from_vcard(0, lines.join("\r\n").as_str());
+        assert_eq!(1, l.len());
+        let p = &l[0];
+        assert_eq!("p1", p.person.uid);
+        assert_eq!("Name; Semi", p.person.name1);
+        assert_eq!(901, p.person.geburtk);
+        assert_eq!(1, p.sitze.len());
+        assert_eq!("Home", p.sitze[0].sitz.name);
+        assert_eq!(Some("0170".to_string()), p.sitze[0].sitz.mobil);
+        let a = p.sitze[0].adresse.as_ref().unwrap();
+        assert_eq!(Some("Main Street".to_string()), a.strasse);
+        assert_eq!(Some("12a".to_string()), a.hausnr);
+        let f = super::fold_line(&"x".repeat(200));
+        assert_eq!(3, f.len());
+        assert_eq!(75, f[0].len());
+        assert!(f[1].starts_with(' '));
+    }
+
+    #[test]
+    fn birthday_of_year() {
+        let n = |y, m, d| NaiveDate::from_ymd(y, m, d);
+        assert_eq!(
+            n(2023, 3, 1),
+            super::get_birthday_of_year(&n(1970, 3, 1), 2023)
+        );
+        assert_eq!(
+            n(2024, 2, 29),
+            super::get_birthday_of_year(&n(1972, 2, 29), 2024)
+        );
+        assert_eq!(
+            n(2023, 2, 28),
+            super::get_birthday_of_year(&n(1972, 2, 29), 2023)
+        );
+        assert!(super::is_milestone(18));
+        assert!(super::is_milestone(50));
+        assert!(super::is_milestone(65));
+        assert!(!super::is_milestone(19));
+        assert!(!super::is_milestone(45));
+    }
+
+    #[test]
+    fn birthday_event() {
+        let (p, _) = from_csv_row(0, &{
+            let mut c = vec![String::new(); ADDRESS_CSV_HEADER.len()];
+            c[0] = "p1".into();
+            c[3] = "1972-02-29".into();
+            c[6] = "Name".into();
+            c[9] = "Vorname".into();
+            c
+        });
+        let b = AdBirthday {
+            person: p,
+            date: NaiveDate::from_ymd(2023, 2, 28),
+            age: 51,
+            days: 3,
+            milestone: false,
+        };
+        let v = super::get_birthday_event(&b, "20230225T120000Z", false);
+        assert!(v.contains(&"DTSTAMP:20230225T120000Z".to_string()));
+        assert!(v.contains(&"DTSTART;VALUE=DATE:20230228".to_string()));
+        assert!(v.contains(&"DTEND;VALUE=DATE:20230301".to_string()));
+        assert!(v.contains(&"SUMMARY:Name\\, Vorname turns 51".to_string()));
+        assert!(v.iter().any(|l| l.starts_with("UID:p1-birthday-2023@")));
+        assert!(!v.iter().any(|l| l.starts_with("RRULE")));
+    }
+
+    #[test]
+    fn letter_values() {
+        let (mut p, s) = from_csv_row(0, &{
+            let mut c = vec![String::new(); ADDRESS_CSV_HEADER.len()];
+            c[2] = "F".into();
+            c[6] = "Name".into();
+            c[9] = "Vorname".into();
+            c[10] = "Dr.".into();
+            c[26] = "Town".into();
+            c[27] = "Main Street".into();
+            c
+        });
+        let d = NaiveDate::from_ymd(2023, 3, 1);
+        let mut e = AdPersonExt {
+            person: p.clone(),
+            sitze: vec![s.unwrap()],
+        };
+        let v = super::get_letter_values(&e, &d, false);
+        assert_eq!("Dear Ms Dr. Name,", v["anrede"]);
+        assert_eq!("Ms", v["fanrede"]);
+        assert_eq!(
+            "Dr. Vorname Name, Main Street, Town {unknown}",
+            super::replace_values("{name}, {strasse}{hausnr}, {ort} {unknown}", &v)
+        );
+        let mut v0 = v.clone();
+        v0.insert("name", "{ort}".into());
+        assert_eq!(
+            "{{ort}} {Town",
+            super::replace_values("{{name}} {{ort}", &v0)
+        );
+        p.anrede = 1;
+        p.fanrede = 1;
+        e.person = p;
+        let v = super::get_letter_values(&e, &d, true);
+        assert_eq!("Liebe Vorname,", v["anrede"]);
+        assert_eq!("", v["fanrede"]);
+        assert_eq!("01.03.2023", v["datum"]);
+    }
+
+    #[test]
+    fn duplicate_groups() {
+        let a = |strasse: &str, hausnr: &str, plz: &str, ort: &str| AdAdresse {
+            mandant_nr: 0,
+            uid: String::new(),
+            staat: None,
+            plz: Some(plz.into()),
+            ort: ort.into(),
+            strasse: Some(strasse.into()),
+            hausnr: Some(hausnr.into()),
+            angelegt_von: None,
+            angelegt_am: None,
+            geaendert_von: None,
+            geaendert_am: None,
+        };
+        let l = vec![
+            a("Hauptstraße", "12a", "12345", "Neustadt"),
+            a("Bahnhofstr.", "1", "12345", "Neustadt"),
+            a("Haupt-Str.", "12 A", "12345", "Neustadt"),
+            a("Haubtstr.", "12a", "12345", "Neustadt "),
+            a("Hauptstraße", "12", "12345", "Neustadt"),
+        ];
+        assert_eq!("hauptstr", super::normalize_street("Haupt-Straße"));
+        assert_eq!(
+            vec![vec![0, 2, 3]],
+            super::get_duplicate_groups(&l, 0.8, 0.9)
+        );
+        assert_eq!(vec![vec![0, 2]], super::get_duplicate_groups(&l, 1.0, 1.0));
+    }
+
+    #[test]
+    fn street() {
+        let s = |x: &str| super::split_street(x);
+        let o = |x: &str| Some(x.to_string());
+        assert_eq!((o("Main Street"), o("12a")), s("Main Street 12a"));
+        assert_eq!((o("Hauptstraße"), o("12 a")), s(" Hauptstraße  12 a"));
+        assert_eq!((o("Am Markt"), o("3 - 5")), s("Am Markt 3 - 5"));
+        assert_eq!(
+            (o("Straße des 17. Juni"), o("5")),
+            s("Straße des 17. Juni 5")
+        );
+        assert_eq!((o("Straße des 17. Juni"), None), s("Straße des 17. Juni"));
+        assert_eq!((o("12"), None), s("12"));
+    }
+
+    #[test]
+    fn merge_person() {
+        let row = |strasse: &str, hausnr: &str| {
+            let mut c = vec![String::new(); ADDRESS_CSV_HEADER.len()];
+            c[0] = "p1".into();
+            c[6] = "Name".into();
+            c[12] = "s1".into();
+            c[14] = "Home".into();
+            c[23] = "a1".into();
+            c[24] = "D".into();
+            c[26] = "Town".into();
+            c[27] = strasse.into();
+            c[28] = hausnr.into();
+            c
+        };
+        let (p, s) = from_csv_row(0, &row("Hauptstraße", "12 a"));
+        let e = AdPersonExt {
+            person: p,
+            sitze: vec![s.unwrap()],
+        };
+        let imported = |strasse: &str| {
+            let (mut p, s) = from_csv_row(0, &row("", ""));
+            let mut s = s.unwrap();
+            p.uid = String::new();
+            s.sitz.uid = String::new();
+            let a = s.adresse.as_mut().unwrap();
+            a.uid = String::new();
+            a.staat = None;
+            let (st, no) = super::split_street(strasse);
+            a.strasse = st;
+            a.hausnr = no;
+            AdPersonExt {
+                person: p,
+                sitze: vec![s],
+            }
+        };
+        // The same address with another split keeps the shared address.
+        let mut p = imported("Hauptstraße 12a");
+        super::merge_person(&e, &mut p);
+        assert_eq!("s1", p.sitze[0].sitz.uid);
+        let a = p.sitze[0].adresse.as_ref().unwrap();
+        assert_eq!("a1", a.uid);
+        assert_eq!(Some("12 a".to_string()), a.hausnr);
+        assert_eq!(Some("a1".to_string()), p.sitze[0].sitz.adresse_uid);
+        // A changed address becomes a new address.
+        let mut p = imported("Hauptstraße 14");
+        super::merge_person(&e, &mut p);
+        let a = p.sitze[0].adresse.as_ref().unwrap();
+        assert_eq!("", a.uid);
+        assert_eq!(Some("14".to_string()), a.hausnr);
+        assert_eq!(Some("D".to_string()), a.staat);
+    }
+}
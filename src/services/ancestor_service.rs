@@ -0,0 +1,1615 @@
+use super::{
+    reps::{self, DbContext},
+    undo::UndoRedoStack,
+};
+use crate::{
+    apis::{enums::GedcomEventEnum, services::ServiceDaten},
+    base::functions,
+    config::RsbpError,
+    res::messages::M,
+    Result,
+};
+use diesel::Connection;
+use rsbp_rep::{
+    models::{SbEreignis, SbFamilie, SbPerson, SbQuelle},
+    models_ext::{SbPersonDuplicate, SbRelationship},
+};
+use std::collections::{HashMap, HashSet};
+
+/// Get a list of sources.
+/// * daten: Service data for database access.
+/// * returns: List of sources or possibly errors.
+pub fn get_source_list<'a>(daten: &'a ServiceDaten) -> Result<Vec<SbQuelle>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let l = reps::sb_quelle::get_list_ext(&db)?;
+    Ok(l)
+}
+
+/// Get a source.
+/// * daten: Service data for database access.
+/// * uid: Affected source ID.
+/// * returns: Source or possibly errors.
+pub fn get_source<'a>(daten: &'a ServiceDaten, uid: &String) -> Result<Option<SbQuelle>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let e = reps::sb_quelle::get(&db, &daten.mandant_nr, uid)?;
+    Ok(e)
+}
+
+/// Get the ancestors and events citing a source.
+/// * daten: Service data for database access.
+/// * uid: Affected source ID.
+/// * returns: Lists of ancestors and events or possibly errors.
+pub fn get_source_references<'a>(
+    daten: &'a ServiceDaten,
+    uid: &String,
+) -> Result<(Vec<SbPerson>, Vec<SbEreignis>)> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let pl = reps::sb_person::get_list_source(&db, uid)?;
+    let el = reps::sb_ereignis::get_list_source(&db, uid)?;
+    Ok((pl, el))
+}
+
+/// Save a source.
+/// * daten: Service data for database access.
+/// * uid: Affected ID, empty for a new source.
+/// * desc: Affected description.
+/// * quote: Affected quote.
+/// * memo: Affected memo.
+/// * author: Affected author.
+/// * returns: Saved source or possibly errors.
+pub fn save_source<'a>(
+    daten: &'a ServiceDaten,
+    uid: &String,
+    desc: &String,
+    quote: &String,
+    memo: &String,
+    author: &String,
+) -> Result<SbQuelle> {
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<SbQuelle, RsbpError, _>(|| {
+        save_source0(&mut db, uid, desc, quote, memo, author)
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Save a source without transaction.
+/// * db: Database context.
+/// * uid: Affected ID, empty for a new source.
+/// * desc: Affected description.
+/// * quote: Affected quote.
+/// * memo: Affected memo.
+/// * author: Affected author.
+/// * returns: Saved source or possibly errors.
+fn save_source0(
+    db: &mut DbContext,
+    uid: &String,
+    desc: &String,
+    quote: &String,
+    memo: &String,
+    author: &String,
+) -> Result<SbQuelle> {
+    let daten = db.daten;
+    let mut r: Vec<String> = vec![];
+    let is_de = daten.config.is_de();
+    let a = author.trim().to_string();
+    let d = desc.trim().to_string();
+    if a.len() <= 0 {
+        r.push(M::mec(M::SB013, is_de).into_owned());
+    }
+    if d.len() <= 0 {
+        r.push(M::mec(M::SB014, is_de).into_owned());
+    }
+    if r.len() > 0 {
+        return Err(RsbpError::error(&r));
+    }
+    let mut u = uid.clone();
+    let mut s1 = 0;
+    let mut s2 = 0;
+    let mut s3 = 0;
+    if u.is_empty() {
+        u = functions::get_uid();
+    } else if let Some(q) = reps::sb_quelle::get(db, &daten.mandant_nr, &u)? {
+        s1 = q.status1;
+        s2 = q.status2;
+        s3 = q.status3;
+    }
+    let e = reps::sb_quelle::save(
+        db,
+        &daten.mandant_nr,
+        &u,
+        &d,
+        &functions::to_ostr(quote.trim()),
+        &functions::to_ostr(memo.trim()),
+        &a,
+        &s1,
+        &s2,
+        &s3,
+    )?;
+    Ok(e)
+}
+
+/// Delete a source if it is not used by ancestors or events.
+/// * daten: Service data for database access.
+/// * e: Affected Entity.
+/// * returns: Possibly errors.
+pub fn delete_source<'a>(daten: &'a ServiceDaten, e: &SbQuelle) -> Result<()> {
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<(), RsbpError, _>(|| delete_source0(&mut db, e));
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Delete a source if it is not used by ancestors or events without transaction.
+/// * db: Database context.
+/// * e: Affected Entity.
+/// * returns: Possibly errors.
+fn delete_source0(db: &mut DbContext, e: &SbQuelle) -> Result<()> {
+    let is_de = db.daten.config.is_de();
+    if reps::sb_person::get_list_source(db, &e.uid)?.len() > 0 {
+        return Err(RsbpError::error_msg(M::SB015, is_de));
+    }
+    if reps::sb_ereignis::get_list_source(db, &e.uid)?.len() > 0 {
+        return Err(RsbpError::error_msg(M::SB016, is_de));
+    }
+    reps::sb_quelle::delete(db, e)?;
+    Ok(())
+}
+
+/// Reassign all citations of a source to another source in one step.
+/// * daten: Service data for database access.
+/// * from: Affected source ID which is replaced.
+/// * to: Affected source ID which is cited afterwards.
+/// * returns: Number of changed ancestors and events or possibly errors.
+pub fn reassign_source<'a>(daten: &'a ServiceDaten, from: &String, to: &String) -> Result<usize> {
+    let is_de = daten.config.is_de();
+    if from.is_empty() || to.is_empty() || from == to {
+        return Err(RsbpError::error_msg(M::M1013, is_de));
+    }
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<usize, RsbpError, _>(|| reassign_source0(&mut db, from, to));
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Reassign all citations of a source to another source without transaction.
+/// * db: Database context.
+/// * from: Affected source ID which is replaced.
+/// * to: Affected source ID which is cited afterwards.
+/// * returns: Number of changed ancestors and events or possibly errors.
+fn reassign_source0(db: &mut DbContext, from: &String, to: &String) -> Result<usize> {
+    let daten = db.daten;
+    let is_de = daten.config.is_de();
+    if reps::sb_quelle::get(db, &daten.mandant_nr, to)?.is_none() {
+        return Err(RsbpError::error_msg(M::M1013, is_de));
+    }
+    let mut anzahl = 0;
+    for p in reps::sb_person::get_list_source(db, from)? {
+        reps::sb_person::save(
+            db,
+            &p.mandant_nr,
+            &p.uid,
+            &p.name,
+            &p.vorname,
+            &p.geburtsname,
+            &p.geschlecht,
+            &p.titel,
+            &p.konfession,
+            &p.bemerkung,
+            &Some(to.clone()),
+            &p.status1,
+            &p.status2,
+            &p.status3,
+        )?;
+        anzahl += 1;
+    }
+    for e in reps::sb_ereignis::get_list_source(db, from)? {
+        reps::sb_ereignis::save0(
+            db,
+            &e.mandant_nr,
+            &e.person_uid,
+            &e.familie_uid,
+            &e.typ,
+            &e.tag1,
+            &e.monat1,
+            &e.jahr1,
+            &e.tag2,
+            &e.monat2,
+            &e.jahr2,
+            &e.datum_typ,
+            &e.ort,
+            &e.bemerkung,
+            &Some(to.clone()),
+            &None,
+            &None,
+            &None,
+            &None,
+            &e.replikation_uid,
+        )?;
+        anzahl += 1;
+    }
+    Ok(anzahl)
+}
+
+/// Get pairs of possibly duplicate ancestors, e.g. after several GEDCOM imports.
+/// Pairs are only compared if they share a name or birth name.
+/// * daten: Service data for database access.
+/// * min_score: Affected minimal score of a pair (max. 120).
+/// * returns: List of pairs ordered by descending score or possibly errors.
+pub fn get_person_duplicates<'a>(
+    daten: &'a ServiceDaten,
+    min_score: i32,
+) -> Result<Vec<SbPersonDuplicate>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    get_person_duplicates0(&db, min_score)
+}
+
+/// Get pairs of possibly duplicate ancestors.
+/// * db: Database context.
+/// * min_score: Affected minimal score of a pair (max. 120).
+/// * returns: List of pairs ordered by descending score or possibly errors.
+fn get_person_duplicates0(db: &DbContext, min_score: i32) -> Result<Vec<SbPersonDuplicate>> {
+    let pl = reps::sb_person::get_list(db, db.daten.mandant_nr)?;
+    let el = reps::sb_ereignis::get_list(db, db.daten.mandant_nr)?;
+    let birth = GedcomEventEnum::to_str(GedcomEventEnum::Birth);
+    let death = GedcomEventEnum::to_str(GedcomEventEnum::Death);
+    let mut births: HashMap<&str, &SbEreignis> = HashMap::new();
+    let mut deaths: HashMap<&str, &SbEreignis> = HashMap::new();
+    for e in el.iter() {
+        if e.typ == birth {
+            births.insert(e.person_uid.as_str(), e);
+        } else if e.typ == death {
+            deaths.insert(e.person_uid.as_str(), e);
+        }
+    }
+    let mut keys: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, p) in pl.iter().enumerate() {
+        let k = normalize_name(&Some(p.name.clone()));
+        if !k.is_empty() {
+            keys.entry(k.clone()).or_insert(vec![]).push(i);
+        }
+        let kg = normalize_name(&p.geburtsname);
+        if !kg.is_empty() && kg != k {
+            keys.entry(kg).or_insert(vec![]).push(i);
+        }
+    }
+    let mut done: HashSet<(usize, usize)> = HashSet::new();
+    let mut l: Vec<SbPersonDuplicate> = vec![];
+    for v in keys.values() {
+        for (x, i) in v.iter().enumerate() {
+            for j in v.iter().skip(x + 1) {
+                let pair = (*i.min(j), *i.max(j));
+                if !done.insert(pair) {
+                    continue;
+                }
+                let (p1, p2) = (&pl[pair.0], &pl[pair.1]);
+                let score = get_person_score(
+                    p1,
+                    births.get(p1.uid.as_str()).map(|e| *e),
+                    deaths.get(p1.uid.as_str()).map(|e| *e),
+                    p2,
+                    births.get(p2.uid.as_str()).map(|e| *e),
+                    deaths.get(p2.uid.as_str()).map(|e| *e),
+                );
+                if score >= min_score {
+                    l.push(SbPersonDuplicate {
+                        person1: p1.clone(),
+                        person2: p2.clone(),
+                        score,
+                    });
+                }
+            }
+        }
+    }
+    l.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(a.person1.name.cmp(&b.person1.name))
+            .then(a.person1.uid.cmp(&b.person1.uid))
+    });
+    Ok(l)
+}
+
+/// Merge a duplicate ancestor into the surviving ancestor.
+/// Child relations, families and events are re-pointed, empty attributes are taken over
+/// and the duplicate is deleted. Everything can be undone in one step.
+/// * daten: Service data for database access.
+/// * uid: Affected ID of the surviving ancestor.
+/// * duid: Affected ID of the duplicate ancestor.
+/// * returns: Possibly errors.
+pub fn merge_persons<'a>(daten: &'a ServiceDaten, uid: &String, duid: &String) -> Result<()> {
+    let is_de = daten.config.is_de();
+    if uid.is_empty() || duid.is_empty() || uid == duid {
+        return Err(RsbpError::error_msg(M::M1013, is_de));
+    }
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<(), RsbpError, _>(|| merge_persons0(&mut db, uid, duid));
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Merge a duplicate ancestor into the surviving ancestor without transaction.
+/// * db: Database context.
+/// * uid: Affected ID of the surviving ancestor.
+/// * duid: Affected ID of the duplicate ancestor.
+/// * returns: Possibly errors.
+fn merge_persons0(db: &mut DbContext, uid: &String, duid: &String) -> Result<()> {
+    let daten = db.daten;
+    let is_de = daten.config.is_de();
+    let p = match reps::sb_person::get(db, &daten.mandant_nr, uid)? {
+        Some(p) => p,
+        None => {
+            return Err(RsbpError::error_string(
+                M::mecf(M::SB017_, &[uid], is_de).as_str(),
+            ))
+        }
+    };
+    let d = match reps::sb_person::get(db, &daten.mandant_nr, duid)? {
+        Some(d) => d,
+        None => {
+            return Err(RsbpError::error_string(
+                M::mecf(M::SB017_, &[duid], is_de).as_str(),
+            ))
+        }
+    };
+    // Child relations
+    for k in reps::sb_kind::get_list_ext(db, None, Some(duid))? {
+        if reps::sb_kind::get(db, &daten.mandant_nr, &k.familie_uid, uid)?.is_none() {
+            reps::sb_kind::save0(
+                db,
+                &k.mandant_nr,
+                &k.familie_uid,
+                uid,
+                &k.angelegt_von,
+                &k.angelegt_am,
+                &None,
+                &None,
+                &k.replikation_uid,
+            )?;
+        }
+        reps::sb_kind::delete(db, &k)?;
+    }
+    // Families as father or mother
+    for f in reps::sb_familie::get_list_ext(db, duid)? {
+        let mut mann = f.mann_uid.clone();
+        let mut frau = f.frau_uid.clone();
+        if functions::cmpuo(&mann, duid) {
+            mann = Some(uid.clone());
+        }
+        if functions::cmpuo(&frau, duid) {
+            frau = Some(uid.clone());
+        }
+        reps::sb_familie::save(
+            db,
+            &f.mandant_nr,
+            &f.uid,
+            &mann,
+            &frau,
+            &f.status1,
+            &f.status2,
+            &f.status3,
+        )?;
+    }
+    // Events
+    for e in reps::sb_ereignis::get_list_ext(db, Some(duid), None)? {
+        let ep = reps::sb_ereignis::get(db, &daten.mandant_nr, uid, &e.familie_uid, &e.typ)?;
+        let n = match &ep {
+            Some(ep) if ep.jahr1 != 0 || e.jahr1 == 0 => None,
+            Some(ep) => Some((
+                ep.angelegt_von.clone(),
+                ep.angelegt_am.clone(),
+                ep.replikation_uid.clone(),
+            )),
+            None => Some((
+                e.angelegt_von.clone(),
+                e.angelegt_am.clone(),
+                e.replikation_uid.clone(),
+            )),
+        };
+        reps::sb_ereignis::delete(db, &e)?;
+        if let Some((av, aa, ru)) = n {
+            reps::sb_ereignis::save0(
+                db,
+                &e.mandant_nr,
+                uid,
+                &e.familie_uid,
+                &e.typ,
+                &e.tag1,
+                &e.monat1,
+                &e.jahr1,
+                &e.tag2,
+                &e.monat2,
+                &e.jahr2,
+                &e.datum_typ,
+                &e.ort,
+                &e.bemerkung,
+                &e.quelle_uid,
+                &av,
+                &aa,
+                &None,
+                &None,
+                &ru,
+            )?;
+        }
+    }
+    // Ancestor
+    reps::sb_person::save(
+        db,
+        &p.mandant_nr,
+        &p.uid,
+        &p.name,
+        &p.vorname.clone().or(d.vorname.clone()),
+        &p.geburtsname.clone().or(d.geburtsname.clone()),
+        &p.geschlecht.clone().or(d.geschlecht.clone()),
+        &p.titel.clone().or(d.titel.clone()),
+        &p.konfession.clone().or(d.konfession.clone()),
+        &p.bemerkung.clone().or(d.bemerkung.clone()),
+        &p.quelle_uid.clone().or(d.quelle_uid.clone()),
+        &p.status1,
+        &p.status2,
+        &p.status3,
+    )?;
+    reps::sb_person::delete(db, &d)?;
+    Ok(())
+}
+
+/// Get the relationship of two ancestors via their closest common ancestors.
+/// * daten: Service data for database access.
+/// * uid1: Affected ID of the first ancestor.
+/// * uid2: Affected ID of the second ancestor whose relationship is named.
+/// * returns: Relationship, None if they are not related, or possibly errors.
+pub fn get_relationship<'a>(
+    daten: &'a ServiceDaten,
+    uid1: &String,
+    uid2: &String,
+) -> Result<Option<SbRelationship>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    get_relationship0(&db, uid1, uid2)
+}
+
+/// Get the relationship of two ancestors with a database context.
+fn get_relationship0(
+    db: &DbContext,
+    uid1: &String,
+    uid2: &String,
+) -> Result<Option<SbRelationship>> {
+    let daten = db.daten;
+    let is_de = daten.config.is_de();
+    let pl = reps::sb_person::get_list(db, daten.mandant_nr)?;
+    let persons: HashMap<&str, &SbPerson> = pl.iter().map(|p| (p.uid.as_str(), p)).collect();
+    if !persons.contains_key(uid1.as_str()) {
+        return Err(RsbpError::error_string(
+            M::mecf(M::SB017_, &[uid1], is_de).as_str(),
+        ));
+    }
+    let p2 = match persons.get(uid2.as_str()) {
+        Some(p) => *p,
+        None => {
+            return Err(RsbpError::error_string(
+                M::mecf(M::SB017_, &[uid2], is_de).as_str(),
+            ))
+        }
+    };
+    let fl = reps::sb_familie::get_list(db, daten.mandant_nr)?;
+    let kl = reps::sb_kind::get_list(db, daten.mandant_nr)?;
+    let families: HashMap<&str, &SbFamilie> = fl.iter().map(|f| (f.uid.as_str(), f)).collect();
+    // Parents of each child with the connecting family
+    let mut parents: HashMap<&str, Vec<(&str, &str)>> = HashMap::new();
+    for k in kl.iter() {
+        if let Some(f) = families.get(k.familie_uid.as_str()) {
+            let v = parents.entry(k.kind_uid.as_str()).or_insert(vec![]);
+            for p in [&f.mann_uid, &f.frau_uid].iter() {
+                if let Some(p) = p {
+                    if !p.is_empty() && !v.iter().any(|(u, _)| u == p) {
+                        v.push((p.as_str(), f.uid.as_str()));
+                    }
+                }
+            }
+        }
+    }
+    let a1 = get_forbears(&parents, uid1.as_str());
+    let a2 = get_forbears(&parents, uid2.as_str());
+    let mut best: Option<(i32, i32)> = None;
+    for (uid, (d1, _)) in a1.iter() {
+        if let Some((d2, _)) = a2.get(uid) {
+            let rank = (d1 + d2, (d1 - d2).abs());
+            if best.map_or(true, |(b1, b2)| rank < (b1 + b2, (b1 - b2).abs())) {
+                best = Some((*d1, *d2));
+            }
+        }
+    }
+    let (g1, g2) = match best {
+        Some(b) => b,
+        None => return Ok(None),
+    };
+    let mut cl: Vec<&str> = a1
+        .iter()
+        .filter(|(uid, (d1, _))| *d1 == g1 && a2.get(*uid).map_or(false, |(d2, _)| *d2 == g2))
+        .map(|(uid, _)| *uid)
+        .collect();
+    cl.sort();
+    // Full relation if both lines descend from the same family of a common ancestor
+    let half = g1 > 0
+        && g2 > 0
+        && !cl.iter().any(|uid| {
+            let f1: HashSet<&str> = a1[uid].1.iter().map(|(_, f)| *f).collect();
+            a2[uid].1.iter().any(|(_, f)| f1.contains(f))
+        });
+    let mut paths: Vec<Vec<SbPerson>> = vec![];
+    for uid in cl.iter() {
+        for up in get_forbear_paths(&a1, uid).iter() {
+            for down in get_forbear_paths(&a2, uid).iter() {
+                let path = up
+                    .iter()
+                    .chain(down.iter().rev().skip(1))
+                    .filter_map(|u| persons.get(u).map(|p| (*p).clone()))
+                    .collect();
+                paths.push(path);
+            }
+        }
+    }
+    Ok(Some(SbRelationship {
+        generations1: g1,
+        generations2: g2,
+        half,
+        name: get_relationship_name(g1, g2, half, &p2.geschlecht, is_de),
+        ancestors: cl
+            .iter()
+            .filter_map(|u| persons.get(u).map(|p| (*p).clone()))
+            .collect(),
+        paths,
+    }))
+}
+
+/// Get all forbears of an ancestor with generation distance and children on the shortest paths.
+fn get_forbears<'b>(
+    parents: &HashMap<&'b str, Vec<(&'b str, &'b str)>>,
+    uid: &'b str,
+) -> HashMap<&'b str, (i32, Vec<(&'b str, &'b str)>)> {
+    let mut m: HashMap<&str, (i32, Vec<(&str, &str)>)> = HashMap::new();
+    m.insert(uid, (0, vec![]));
+    let mut level = vec![uid];
+    let mut d = 0;
+    while !level.is_empty() {
+        d += 1;
+        let mut next = vec![];
+        for c in level.iter() {
+            if let Some(pl) = parents.get(c) {
+                for (p, f) in pl.iter() {
+                    if let Some((dp, children)) = m.get_mut(p) {
+                        if *dp == d {
+                            children.push((*c, *f));
+                        }
+                    } else {
+                        m.insert(*p, (d, vec![(*c, *f)]));
+                        next.push(*p);
+                    }
+                }
+            }
+        }
+        level = next;
+    }
+    m
+}
+
+/// Get all shortest paths from the ancestor of the forbears map up to a forbear.
+fn get_forbear_paths<'b>(
+    m: &HashMap<&'b str, (i32, Vec<(&'b str, &'b str)>)>,
+    uid: &'b str,
+) -> Vec<Vec<&'b str>> {
+    let mut l: Vec<Vec<&str>> = vec![];
+    if let Some((d, children)) = m.get(uid) {
+        if *d == 0 {
+            l.push(vec![uid]);
+        }
+        for (c, _) in children.iter() {
+            for mut p in get_forbear_paths(m, c) {
+                p.push(uid);
+                l.push(p);
+            }
+        }
+    }
+    l
+}
+
+/// Get the name of a relationship.
+/// * g1: Affected generations from the first ancestor up to the common ancestor.
+/// * g2: Affected generations from the second ancestor up to the common ancestor.
+/// * half: Affected half-relation.
+/// * gender: Affected gender of the second ancestor.
+/// * returns: Name of the relationship of the second ancestor to the first one.
+fn get_relationship_name(
+    g1: i32,
+    g2: i32,
+    half: bool,
+    gender: &Option<String>,
+    is_de: bool,
+) -> String {
+    let gi = match functions::ostr_to_str(gender).to_uppercase().as_str() {
+        "M" => 0,
+        "F" | "W" => 1,
+        _ => 2,
+    };
+    if g1 == 0 && g2 == 0 {
+        return functions::iif(is_de, "dieselbe Person", "same person").to_string();
+    }
+    // Words for male, female and unknown gender with number of prefixes
+    let (de, en, ur, great) = if g1 == 0 && g2 == 1 {
+        (
+            ["Sohn", "Tochter", "Kind"],
+            ["son", "daughter", "child"],
+            0,
+            0,
+        )
+    } else if g1 == 0 {
+        (
+            ["Enkel", "Enkelin", "Enkelkind"],
+            ["grandson", "granddaughter", "grandchild"],
+            g2 - 2,
+            g2 - 2,
+        )
+    } else if g1 == 1 && g2 == 0 {
+        (
+            ["Vater", "Mutter", "Elternteil"],
+            ["father", "mother", "parent"],
+            0,
+            0,
+        )
+    } else if g2 == 0 {
+        (
+            ["Großvater", "Großmutter", "Großelternteil"],
+            ["grandfather", "grandmother", "grandparent"],
+            g1 - 2,
+            g1 - 2,
+        )
+    } else if g1 == 1 && g2 == 1 {
+        (
+            ["Bruder", "Schwester", "Geschwister"],
+            ["brother", "sister", "sibling"],
+            0,
+            0,
+        )
+    } else if g1 == 1 && g2 == 2 {
+        (
+            ["Neffe", "Nichte", "Neffe/Nichte"],
+            ["nephew", "niece", "nephew/niece"],
+            0,
+            0,
+        )
+    } else if g1 == 1 {
+        (
+            ["Großneffe", "Großnichte", "Großneffe/-nichte"],
+            ["nephew", "niece", "nephew/niece"],
+            g2 - 3,
+            g2 - 2,
+        )
+    } else if g2 == 1 && g1 == 2 {
+        (
+            ["Onkel", "Tante", "Onkel/Tante"],
+            ["uncle", "aunt", "uncle/aunt"],
+            0,
+            0,
+        )
+    } else if g2 == 1 {
+        (
+            ["Großonkel", "Großtante", "Großonkel/-tante"],
+            ["uncle", "aunt", "uncle/aunt"],
+            g1 - 3,
+            g1 - 2,
+        )
+    } else {
+        let degree = g1.min(g2) - 1;
+        let removed = (g1 - g2).abs();
+        let mut s = String::new();
+        if is_de {
+            s.push_str(["Cousin", "Cousine", "Cousin/Cousine"][gi]);
+            if half {
+                s = format!("Halb{}", to_first_lower(s.as_str()));
+            }
+            if degree > 1 {
+                s.push_str(format!(" {}. Grades", degree).as_str());
+            }
+            if removed > 0 {
+                s.push_str(
+                    format!(
+                        ", um {} Generation{} versetzt",
+                        removed,
+                        functions::iif(removed > 1, "en", "")
+                    )
+                    .as_str(),
+                );
+            }
+        } else {
+            if half {
+                s.push_str("half-");
+            }
+            s.push_str(get_ordinal(degree).as_str());
+            s.push_str(" cousin");
+            match removed {
+                0 => (),
+                1 => s.push_str(" once removed"),
+                2 => s.push_str(" twice removed"),
+                _ => s.push_str(format!(" {} times removed", removed).as_str()),
+            }
+        }
+        return s;
+    };
+    if is_de {
+        let mut s = de[gi].to_string();
+        if ur > 0 {
+            s = format!("{}{}", "Ur".repeat(ur as usize), to_first_lower(s.as_str()));
+        }
+        if half {
+            s = format!("Halb{}", to_first_lower(s.as_str()));
+        }
+        return s;
+    }
+    let s = format!("{}{}", "great-".repeat(great as usize), en[gi]);
+    if half {
+        return format!("half-{}", s);
+    }
+    s
+}
+
+/// Get an English ordinal number.
+fn get_ordinal(n: i32) -> String {
+    match n {
+        1 => "first".into(),
+        2 => "second".into(),
+        3 => "third".into(),
+        4 => "fourth".into(),
+        5 => "fifth".into(),
+        _ => format!("{}th", n),
+    }
+}
+
+/// Convert the first character to lower case.
+fn to_first_lower(s: &str) -> String {
+    let mut c = s.chars();
+    match c.next() {
+        Some(f) => f.to_lowercase().collect::<String>() + c.as_str(),
+        None => String::new(),
+    }
+}
+
+/// Get a statistics report of the family tree as text or CSV lines.
+/// * daten: Service data for database access.
+/// * csv: Affected format: CSV lines or text lines.
+/// * returns: Vector of report lines or possibly errors.
+pub fn get_statistics_file<'a>(daten: &'a ServiceDaten, csv: bool) -> Result<Vec<String>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let is_de = daten.config.is_de();
+    let pl = reps::sb_person::get_list(&db, daten.mandant_nr)?;
+    let el = reps::sb_ereignis::get_list(&db, daten.mandant_nr)?;
+    let fl = reps::sb_familie::get_list(&db, daten.mandant_nr)?;
+    let kl = reps::sb_kind::get_list(&db, daten.mandant_nr)?;
+    let birth = [
+        GedcomEventEnum::to_str(GedcomEventEnum::Birth),
+        GedcomEventEnum::to_str(GedcomEventEnum::Christening),
+    ];
+    let death = [
+        GedcomEventEnum::to_str(GedcomEventEnum::Death),
+        GedcomEventEnum::to_str(GedcomEventEnum::Burial),
+    ];
+    // Birth before christening and death before burial
+    let mut births: HashMap<&str, &SbEreignis> = HashMap::new();
+    let mut deaths: HashMap<&str, &SbEreignis> = HashMap::new();
+    let mut sources: HashSet<&str> = HashSet::new();
+    for e in el.iter() {
+        if !functions::is_empty(&e.quelle_uid) {
+            sources.insert(e.person_uid.as_str());
+        }
+        if e.jahr1 == 0 {
+            continue;
+        }
+        for (types, m) in [(&birth, &mut births), (&death, &mut deaths)].iter_mut() {
+            if let Some(i) = types.iter().position(|t| *t == e.typ.as_str()) {
+                let better = m.get(e.person_uid.as_str()).map_or(true, |o| {
+                    types.iter().position(|t| *t == o.typ.as_str()) > Some(i)
+                });
+                if better {
+                    m.insert(e.person_uid.as_str(), e);
+                }
+            }
+        }
+    }
+    let mut lifespans: HashMap<i32, (i32, usize)> = HashMap::new();
+    let mut first_names: HashMap<&str, usize> = HashMap::new();
+    let mut surnames: HashMap<&str, usize> = HashMap::new();
+    let mut without_source = 0;
+    for p in pl.iter() {
+        if functions::is_empty(&p.quelle_uid) && !sources.contains(p.uid.as_str()) {
+            without_source += 1;
+        }
+        if let (Some(b), Some(d)) = (births.get(p.uid.as_str()), deaths.get(p.uid.as_str())) {
+            let mut age = d.jahr1 - b.jahr1;
+            if b.monat1 > 0 && d.monat1 > 0 && (d.monat1, d.tag1) < (b.monat1, b.tag1) {
+                age -= 1;
+            }
+            if age >= 0 {
+                let l = lifespans.entry(b.jahr1 / 100 * 100).or_insert((0, 0));
+                l.0 += age;
+                l.1 += 1;
+            }
+        }
+        if let Some(v) = &p.vorname {
+            for n in v.split_whitespace() {
+                *first_names.entry(n).or_insert(0) += 1;
+            }
+        }
+        if !p.name.trim().is_empty() {
+            *surnames.entry(p.name.trim()).or_insert(0) += 1;
+        }
+    }
+    // Generation depth
+    let families: HashMap<&str, &SbFamilie> = fl.iter().map(|f| (f.uid.as_str(), f)).collect();
+    let mut parents: HashMap<&str, Vec<&str>> = HashMap::new();
+    for k in kl.iter() {
+        if let Some(f) = families.get(k.familie_uid.as_str()) {
+            let v = parents.entry(k.kind_uid.as_str()).or_insert(vec![]);
+            for p in [&f.mann_uid, &f.frau_uid].iter() {
+                if let Some(p) = p {
+                    if !p.is_empty() {
+                        v.push(p.as_str());
+                    }
+                }
+            }
+        }
+    }
+    let mut depths: HashMap<&str, usize> = HashMap::new();
+    let mut generations = 0;
+    for p in pl.iter() {
+        generations = generations.max(get_depth(&parents, &mut depths, p.uid.as_str()));
+    }
+    let mut centuries: Vec<(&i32, &(i32, usize))> = lifespans.iter().collect();
+    centuries.sort();
+    let top = |m: &HashMap<&str, usize>| -> Vec<(String, usize)> {
+        let mut l: Vec<(String, usize)> = m.iter().map(|(k, v)| (k.to_string(), *v)).collect();
+        l.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        l.truncate(10);
+        l
+    };
+    let without_birth = pl
+        .iter()
+        .filter(|p| !births.contains_key(p.uid.as_str()))
+        .count();
+    let without_death = pl
+        .iter()
+        .filter(|p| !deaths.contains_key(p.uid.as_str()))
+        .count();
+    let mut v: Vec<String> = vec![];
+    if csv {
+        v.push(functions::to_csv(&["category", "key", "value", "count"]));
+        for (k, n) in [
+            ("records", pl.len()),
+            ("without_birth", without_birth),
+            ("without_death", without_death),
+            ("without_source", without_source),
+            ("generations", generations),
+        ]
+        .iter()
+        {
+            v.push(functions::to_csv(&[k, "", "", n.to_string().as_str()]));
+        }
+        for (c, (sum, n)) in centuries.iter() {
+            let avg = *sum as f64 / *n as f64;
+            v.push(functions::to_csv(&[
+                "lifespan",
+                c.to_string().as_str(),
+                format!("{:.2}", avg).as_str(),
+                n.to_string().as_str(),
+            ]));
+        }
+        for (k, m) in [("first_name", &first_names), ("surname", &surnames)].iter() {
+            for (name, n) in top(m).iter() {
+                v.push(functions::to_csv(&[k, name, "", n.to_string().as_str()]));
+            }
+        }
+        return Ok(v);
+    }
+    v.push(M::mecf(
+        M::SB036_,
+        &[daten
+            .get_now()
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+            .as_str()],
+        is_de,
+    ));
+    v.push(M::mecf(
+        M::SB028_,
+        &[
+            pl.len().to_string().as_str(),
+            without_birth.to_string().as_str(),
+        ],
+        is_de,
+    ));
+    v.push(M::mecf(
+        M::SB037_,
+        &[
+            without_death.to_string().as_str(),
+            without_source.to_string().as_str(),
+            generations.to_string().as_str(),
+        ],
+        is_de,
+    ));
+    v.push("".into());
+    v.push(M::mec(M::SB038, is_de).into_owned());
+    for (c, (sum, n)) in centuries.iter() {
+        let avg = *sum as f64 / *n as f64;
+        v.push(M::mecf(
+            M::SB039_,
+            &[
+                c.to_string().as_str(),
+                (**c + 99).to_string().as_str(),
+                functions::f64_to_str_2(&avg, is_de).as_str(),
+                n.to_string().as_str(),
+            ],
+            is_de,
+        ));
+    }
+    for (title, m) in [
+        (M::mec(M::SB040, is_de), &first_names),
+        (M::mec(M::SB041, is_de), &surnames),
+    ]
+    .iter()
+    {
+        v.push("".into());
+        v.push(title.to_string());
+        for (name, n) in top(m).iter() {
+            v.push(format!("{}: {}", name, n));
+        }
+    }
+    Ok(v)
+}
+
+/// Get the number of generations of an ancestor and his forbears.
+fn get_depth<'b>(
+    parents: &HashMap<&'b str, Vec<&'b str>>,
+    depths: &mut HashMap<&'b str, usize>,
+    uid: &'b str,
+) -> usize {
+    if let Some(d) = depths.get(uid) {
+        return *d;
+    }
+    // Mark as visited against cycles in the family tree
+    depths.insert(uid, 1);
+    let mut d = 1;
+    if let Some(pl) = parents.get(uid) {
+        for p in pl.iter() {
+            d = d.max(get_depth(parents, depths, p) + 1);
+        }
+    }
+    depths.insert(uid, d);
+    d
+}
+
+/// Get a Graphviz DOT graph of the forbears or descendants of an ancestor.
+/// Ancestors and families are nodes, edges lead from parents via family to children.
+/// * daten: Service data for database access.
+/// * uid: Affected ancestor ID.
+/// * generations: Affected maximal number of generations.
+/// * descendants: Affected direction: descendants or forbears.
+/// * returns: Vector of DOT lines or possibly errors.
+pub fn get_dot_file<'a>(
+    daten: &'a ServiceDaten,
+    uid: &String,
+    generations: i32,
+    descendants: bool,
+) -> Result<Vec<String>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    get_dot_file0(&db, uid, generations, descendants)
+}
+
+/// Get a Graphviz DOT graph of the forbears or descendants of an ancestor.
+/// * db: Database context.
+/// * uid: Affected ancestor ID.
+/// * generations: Affected maximal number of generations.
+/// * descendants: Affected direction: descendants or forbears.
+/// * returns: Vector of DOT lines or possibly errors.
+fn get_dot_file0(
+    db: &DbContext,
+    uid: &String,
+    generations: i32,
+    descendants: bool,
+) -> Result<Vec<String>> {
+    let daten = db.daten;
+    let is_de = daten.config.is_de();
+    let p = match reps::sb_person::get(db, &daten.mandant_nr, uid)? {
+        Some(p) => p,
+        None => {
+            return Err(RsbpError::error_string(
+                M::mecf(M::SB017_, &[uid], is_de).as_str(),
+            ))
+        }
+    };
+    let name = p.full_name();
+    let g = generations.to_string();
+    let title = if descendants {
+        M::mecf(M::SB019_, &[name.as_str(), g.as_str()], is_de)
+    } else {
+        M::mecf(M::SB021_, &[name.as_str(), g.as_str(), ""], is_de)
+    };
+    let mut persons: Vec<String> = vec![p.uid.clone()];
+    let mut families: Vec<SbFamilie> = vec![];
+    let mut edges: Vec<(String, String)> = vec![];
+    let mut level = vec![p.uid.clone()];
+    for _ in 0..generations.max(0) {
+        let mut next: Vec<String> = vec![];
+        for puid in level.iter() {
+            let fl = if descendants {
+                reps::sb_familie::get_list_ext(db, puid)?
+            } else {
+                let mut fl = vec![];
+                for k in reps::sb_kind::get_list_ext(db, None, Some(puid))? {
+                    if let Some(f) = reps::sb_familie::get(db, &daten.mandant_nr, &k.familie_uid)? {
+                        fl.push(f);
+                    }
+                }
+                fl
+            };
+            for f in fl {
+                let fnode = format!("F{}", f.uid);
+                if families.iter().any(|f0| f0.uid == f.uid) {
+                    // Pedigree collapse: parents are known, only the edge to the child is missing.
+                    let edge = (fnode, format!("P{}", puid));
+                    if !descendants && !edges.contains(&edge) {
+                        edges.push(edge);
+                    }
+                    continue;
+                }
+                for parent in [&f.mann_uid, &f.frau_uid].iter() {
+                    if let Some(parent) = parent {
+                        if parent.is_empty() {
+                            continue;
+                        }
+                        edges.push((format!("P{}", parent), fnode.clone()));
+                        if !persons.contains(parent) {
+                            persons.push(parent.clone());
+                            if !descendants {
+                                next.push(parent.clone());
+                            }
+                        }
+                    }
+                }
+                for k in reps::sb_kind::get_list_ext(db, Some(&f.uid), None)? {
+                    if !descendants && &k.kind_uid != puid {
+                        continue;
+                    }
+                    edges.push((fnode.clone(), format!("P{}", k.kind_uid)));
+                    if !persons.contains(&k.kind_uid) {
+                        persons.push(k.kind_uid.clone());
+                        next.push(k.kind_uid.clone());
+                    }
+                }
+                families.push(f);
+            }
+        }
+        level = next;
+    }
+    let birth = GedcomEventEnum::to_str(GedcomEventEnum::Birth);
+    let death = GedcomEventEnum::to_str(GedcomEventEnum::Death);
+    let marriage = GedcomEventEnum::to_str(GedcomEventEnum::Marriage);
+    let mut v: Vec<String> = vec![];
+    v.push(format!("digraph \"{}\" {{", to_dot(title.as_str())));
+    v.push(format!("  label=\"{}\";", to_dot(title.as_str())));
+    v.push("  node [shape=box];".into());
+    for puid in persons.iter() {
+        if let Some(p) = reps::sb_person::get(db, &daten.mandant_nr, puid)? {
+            let mut label = p.full_name();
+            for e in reps::sb_ereignis::get_list_ext(db, Some(puid), None)? {
+                if e.typ == birth {
+                    label.push_str(format!("\n* {}", get_event_date(&e, is_de)).as_str());
+                } else if e.typ == death {
+                    label.push_str(format!("\n† {}", get_event_date(&e, is_de)).as_str());
+                }
+            }
+            v.push(format!(
+                "  \"P{}\" [label=\"{}\"];",
+                puid,
+                to_dot(label.as_str())
+            ));
+        }
+    }
+    for f in families.iter() {
+        let mut label = String::new();
+        for e in reps::sb_ereignis::get_list_ext(db, None, Some(&f.uid))? {
+            if e.typ == marriage {
+                label = format!("⚭ {}", get_event_date(&e, is_de));
+            }
+        }
+        v.push(format!(
+            "  \"F{}\" [shape=ellipse, label=\"{}\"];",
+            f.uid,
+            to_dot(label.as_str())
+        ));
+    }
+    for (from, to) in edges.iter() {
+        v.push(format!("  \"{}\" -> \"{}\";", from, to));
+    }
+    v.push("}".into());
+    Ok(v)
+}
+
+/// Get the date of an event as string, e.g. about 1850-03.
+fn get_event_date(e: &SbEreignis, is_de: bool) -> String {
+    let d = |t: i32, m: i32, y: i32| -> String {
+        if y == 0 {
+            return "".into();
+        } else if m == 0 {
+            return format!("{}", y);
+        } else if t == 0 {
+            return format!("{}-{:02}", y, m);
+        }
+        format!("{}-{:02}-{:02}", y, m, t)
+    };
+    let d1 = d(e.tag1, e.monat1, e.jahr1);
+    let d2 = d(e.tag2, e.monat2, e.jahr2);
+    let args = [d1.as_str(), d2.as_str()];
+    match e.datum_typ.as_str() {
+        "ABT" => M::mecf(M::SB031_, &args, is_de),
+        "AFT" => M::mecf(M::SB032_, &args, is_de),
+        "BEF" => M::mecf(M::SB033_, &args, is_de),
+        "BET" => M::mecf(M::SB034_, &args, is_de),
+        "OR" => M::mecf(M::SB035_, &args, is_de),
+        _ => d1,
+    }
+}
+
+/// Escape a string for a quoted DOT label.
+fn to_dot(s: &str) -> String {
+    s.replace("\\", "\\\\")
+        .replace("\"", "\\\"")
+        .replace("\n", "\\n")
+}
+
+/// Normalize a name for comparison.
+fn normalize_name(s: &Option<String>) -> String {
+    functions::ostr_to_str(s).trim().to_lowercase()
+}
+
+/// Compare the dates of two events.
+fn get_event_score(e1: Option<&SbEreignis>, e2: Option<&SbEreignis>) -> i32 {
+    if let (Some(a), Some(b)) = (e1, e2) {
+        if a.jahr1 == 0 || b.jahr1 == 0 {
+            return 0;
+        }
+        if a.jahr1 != b.jahr1 {
+            return functions::iif_i32((a.jahr1 - b.jahr1).abs() <= 2, 5, -25);
+        }
+        let mut score = 15;
+        if a.monat1 != 0 && a.monat1 == b.monat1 {
+            score += 5;
+            if a.tag1 != 0 && a.tag1 == b.tag1 {
+                score += 5;
+            }
+        }
+        return score;
+    }
+    0
+}
+
+/// Calculate the matching score of two ancestors with their birth and death events.
+/// Different genders never match.
+fn get_person_score(
+    p1: &SbPerson,
+    b1: Option<&SbEreignis>,
+    d1: Option<&SbEreignis>,
+    p2: &SbPerson,
+    b2: Option<&SbEreignis>,
+    d2: Option<&SbEreignis>,
+) -> i32 {
+    let g1 = normalize_name(&p1.geschlecht);
+    let g2 = normalize_name(&p2.geschlecht);
+    if !g1.is_empty() && !g2.is_empty() && g1 != g2 {
+        return 0;
+    }
+    let mut score = functions::iif_i32(!g1.is_empty() && g1 == g2, 5, 0);
+    let n1 = normalize_name(&Some(p1.name.clone()));
+    let n2 = normalize_name(&Some(p2.name.clone()));
+    let gn1 = normalize_name(&p1.geburtsname);
+    let gn2 = normalize_name(&p2.geburtsname);
+    if n1 == n2 {
+        score += 30;
+    } else if (!gn1.is_empty() && gn1 == n2) || (!gn2.is_empty() && gn2 == n1) {
+        score += 15;
+    }
+    if !gn1.is_empty() && !gn2.is_empty() {
+        score += functions::iif_i32(gn1 == gn2, 10, -10);
+    }
+    let v1 = normalize_name(&p1.vorname);
+    let v2 = normalize_name(&p2.vorname);
+    if !v1.is_empty() && v1 == v2 {
+        score += 25;
+    } else if v1.split_whitespace().next().is_some()
+        && v1.split_whitespace().next() == v2.split_whitespace().next()
+    {
+        score += 15;
+    }
+    score += get_event_score(b1, b2);
+    score += get_event_score(d1, d2);
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        delete_source0, get_dot_file0, get_person_duplicates0, get_relationship0,
+        get_relationship_name, merge_persons0, reassign_source0, save_source0,
+    };
+    use crate::{
+        apis::{enums::GedcomEventEnum, services::ServiceDaten},
+        res::messages::M,
+        services::reps::{self, DbContext},
+    };
+
+    fn person(db: &mut DbContext, uid: &str, gender: &str) {
+        reps::sb_person::save(
+            db,
+            &0,
+            &uid.to_string(),
+            &uid.to_string(),
+            &None,
+            &None,
+            &Some(gender.to_string()),
+            &None,
+            &None,
+            &None,
+            &None,
+            &0,
+            &0,
+            &0,
+        )
+        .unwrap();
+    }
+
+    fn family(db: &mut DbContext, uid: &str, father: &str, mother: &str, children: &[&str]) {
+        reps::sb_familie::save(
+            db,
+            &0,
+            &uid.to_string(),
+            &Some(father.to_string()),
+            &Some(mother.to_string()),
+            &0,
+            &0,
+            &0,
+        )
+        .unwrap();
+        for c in children.iter() {
+            reps::sb_kind::save(db, &0, &uid.to_string(), &c.to_string()).unwrap();
+        }
+    }
+
+    #[test]
+    fn source() {
+        let daten = ServiceDaten::init();
+        let is_de = daten.config.is_de();
+        let c = reps::establish_test_connection();
+        let mut db = DbContext::new(&daten, &c);
+        let mut save = |desc: &str, author: &str| {
+            save_source0(
+                &mut db,
+                &"".into(),
+                &desc.into(),
+                &" quote ".into(),
+                &"".into(),
+                &author.into(),
+            )
+        };
+        let e = save(" ", "").unwrap_err().to_string();
+        let m = format!("{}\n{}", M::mec(M::SB013, is_de), M::mec(M::SB014, is_de));
+        assert_eq!(m, e);
+        let e = save("", "Parish").unwrap_err().to_string();
+        assert_eq!(M::mec(M::SB014, is_de), e);
+        let q1 = save(" Church book ", "Parish").unwrap();
+        assert_eq!("Church book", q1.beschreibung);
+        assert_eq!(Some("quote".to_string()), q1.zitat);
+        assert_eq!(None, q1.bemerkung);
+        let q2 = save("Census", "State").unwrap();
+        let q3 = save("Letter", "Family").unwrap();
+        reps::sb_person::save(
+            &mut db,
+            &0,
+            &"p1".into(),
+            &"p1".into(),
+            &None,
+            &None,
+            &None,
+            &None,
+            &None,
+            &None,
+            &Some(q1.uid.clone()),
+            &0,
+            &0,
+            &0,
+        )
+        .unwrap();
+        for (puid, quid) in [("p1", &q1.uid), ("p2", &q1.uid), ("p3", &q3.uid)].iter() {
+            reps::sb_ereignis::save(
+                &mut db,
+                &0,
+                &puid.to_string(),
+                &"".into(),
+                &"BIRT".into(),
+                &0,
+                &0,
+                &1900,
+                &0,
+                &0,
+                &0,
+                &"".into(),
+                &None,
+                &None,
+                &Some(quid.to_string()),
+            )
+            .unwrap();
+        }
+
+        // Delete guard
+        let e = delete_source0(&mut db, &q1).unwrap_err().to_string();
+        assert_eq!(M::mec(M::SB015, is_de), e);
+        let e = delete_source0(&mut db, &q3).unwrap_err().to_string();
+        assert_eq!(M::mec(M::SB016, is_de), e);
+
+        // Reassign ancestors and events
+        assert!(reassign_source0(&mut db, &q1.uid, &"q0".into()).is_err());
+        assert_eq!(3, reassign_source0(&mut db, &q1.uid, &q2.uid).unwrap());
+        assert_eq!(0, reassign_source0(&mut db, &q1.uid, &q2.uid).unwrap());
+        let p = reps::sb_person::get(&db, &0, &"p1".into())
+            .unwrap()
+            .unwrap();
+        assert_eq!(Some(q2.uid.clone()), p.quelle_uid);
+        assert_eq!(
+            1,
+            reps::sb_person::get_list_source(&db, &q2.uid)
+                .unwrap()
+                .len()
+        );
+        assert_eq!(
+            2,
+            reps::sb_ereignis::get_list_source(&db, &q2.uid)
+                .unwrap()
+                .len()
+        );
+        delete_source0(&mut db, &q1).unwrap();
+        assert_eq!(None, reps::sb_quelle::get(&db, &0, &q1.uid).unwrap());
+    }
+
+    #[test]
+    fn relationship_names() {
+        let m = Some("M".to_string());
+        let f = Some("F".to_string());
+        assert_eq!(
+            "Cousin 2. Grades",
+            get_relationship_name(3, 3, false, &m, true)
+        );
+        assert_eq!(
+            "second cousin",
+            get_relationship_name(3, 3, false, &m, false)
+        );
+        assert_eq!("Großonkel", get_relationship_name(3, 1, false, &m, true));
+        assert_eq!("great-uncle", get_relationship_name(3, 1, false, &m, false));
+        assert_eq!("Urgroßtante", get_relationship_name(4, 1, false, &f, true));
+        assert_eq!(
+            "great-great-aunt",
+            get_relationship_name(4, 1, false, &f, false)
+        );
+        assert_eq!("Urgroßmutter", get_relationship_name(3, 0, false, &f, true));
+        assert_eq!("Halbschwester", get_relationship_name(1, 1, true, &f, true));
+        assert_eq!(
+            "Cousine, um 1 Generation versetzt",
+            get_relationship_name(2, 3, false, &f, true)
+        );
+        assert_eq!(
+            "first cousin twice removed",
+            get_relationship_name(4, 2, false, &m, false)
+        );
+    }
+
+    #[test]
+    fn relationship() {
+        let daten = ServiceDaten::init();
+        let c = reps::establish_test_connection();
+        let mut db = DbContext::new(&daten, &c);
+        for (uid, g) in [
+            ("gf", "M"),
+            ("gm", "F"),
+            ("father", "M"),
+            ("mother", "F"),
+            ("other", "F"),
+            ("uncle", "M"),
+            ("aunt", "F"),
+            ("me", "M"),
+            ("sister", "F"),
+            ("halfbrother", "M"),
+            ("cousin", "M"),
+            ("cousinchild", "F"),
+        ]
+        .iter()
+        {
+            person(&mut db, uid, g);
+        }
+        family(&mut db, "f1", "gf", "gm", &["father", "uncle"]);
+        family(&mut db, "f2", "father", "mother", &["me", "sister"]);
+        family(&mut db, "f3", "father", "other", &["halfbrother"]);
+        family(&mut db, "f4", "uncle", "aunt", &["cousin"]);
+        family(&mut db, "f5", "cousin", "", &["cousinchild"]);
+        let rel = |uid: &str| get_relationship0(&db, &"me".into(), &uid.into()).unwrap();
+
+        let r = rel("sister").unwrap();
+        assert_eq!((1, 1, false), (r.generations1, r.generations2, r.half));
+        assert_eq!("sister", r.name);
+        assert_eq!(2, r.ancestors.len());
+        let r = rel("halfbrother").unwrap();
+        assert_eq!((true, "half-brother"), (r.half, r.name.as_str()));
+        assert_eq!(1, r.ancestors.len());
+        assert_eq!("uncle", rel("uncle").unwrap().name);
+        assert_eq!("grandfather", rel("gf").unwrap().name);
+        let r = rel("cousin").unwrap();
+        assert_eq!("first cousin", r.name);
+        assert_eq!(2, r.paths.len());
+        let path: Vec<&str> = r.paths[0].iter().map(|p| p.uid.as_str()).collect();
+        assert_eq!(vec!["me", "father", "gf", "uncle", "cousin"], path);
+        assert_eq!(
+            "first cousin once removed",
+            rel("cousinchild").unwrap().name
+        );
+        assert!(rel("aunt").is_none());
+    }
+
+    #[test]
+    fn merge() {
+        let daten = ServiceDaten::init();
+        let c = reps::establish_test_connection();
+        let mut db = DbContext::new(&daten, &c);
+        let person =
+            |db: &mut DbContext, uid: &str, name: &str, vorname: &str, g: &str, b: &str| {
+                reps::sb_person::save(
+                    db,
+                    &0,
+                    &uid.to_string(),
+                    &name.to_string(),
+                    &Some(vorname.to_string()),
+                    &None,
+                    &Some(g.to_string()),
+                    &None,
+                    &None,
+                    &Some(b.to_string()).filter(|x| !x.is_empty()),
+                    &Some(b.to_string()).filter(|x| !x.is_empty()),
+                    &0,
+                    &0,
+                    &0,
+                )
+                .unwrap();
+            };
+        let birth = |db: &mut DbContext, uid: &str, year: i32| {
+            reps::sb_ereignis::save(
+                db,
+                &0,
+                &uid.to_string(),
+                &"".to_string(),
+                &GedcomEventEnum::to_str(GedcomEventEnum::Birth).to_string(),
+                &0,
+                &0,
+                &year,
+                &0,
+                &0,
+                &0,
+                &"".to_string(),
+                &None,
+                &None,
+                &None,
+            )
+            .unwrap();
+        };
+        person(&mut db, "p1", "Muster", "Hans", "M", "");
+        person(&mut db, "p2", "muster ", "Hans Peter", "M", "q1");
+        person(&mut db, "p3", "Muster", "Hans", "F", "");
+        person(&mut db, "gf", "Muster", "Karl", "M", "");
+        person(&mut db, "gm", "Alt", "Anna", "F", "");
+        person(&mut db, "wife", "Neu", "Eva", "F", "");
+        person(&mut db, "child", "Muster", "Paul", "M", "");
+        family(&mut db, "f1", "gf", "gm", &["p2"]);
+        family(&mut db, "f2", "p2", "wife", &["child"]);
+        birth(&mut db, "p1", 1900);
+        birth(&mut db, "p2", 1901);
+
+        let l = get_person_duplicates0(&db, 50).unwrap();
+        assert_eq!(1, l.len());
+        assert_eq!(
+            ("p1", "p2"),
+            (l[0].person1.uid.as_str(), l[0].person2.uid.as_str())
+        );
+        assert_eq!(5 + 30 + 15 + 5, l[0].score);
+
+        merge_persons0(&mut db, &"p1".into(), &"p2".into()).unwrap();
+        assert!(reps::sb_person::get(&db, &0, &"p2".into())
+            .unwrap()
+            .is_none());
+        let p = reps::sb_person::get(&db, &0, &"p1".into())
+            .unwrap()
+            .unwrap();
+        assert_eq!(Some("Hans".to_string()), p.vorname);
+        assert_eq!(Some("q1".to_string()), p.quelle_uid);
+        assert_eq!(Some("q1".to_string()), p.bemerkung);
+        assert!(reps::sb_kind::get(&db, &0, &"f1".into(), &"p1".into())
+            .unwrap()
+            .is_some());
+        assert!(reps::sb_kind::get(&db, &0, &"f1".into(), &"p2".into())
+            .unwrap()
+            .is_none());
+        let f = reps::sb_familie::get(&db, &0, &"f2".into())
+            .unwrap()
+            .unwrap();
+        assert_eq!(Some("p1".to_string()), f.mann_uid);
+        let el = reps::sb_ereignis::get_list_ext(&db, Some(&"p1".into()), None).unwrap();
+        assert_eq!(vec![1900], el.iter().map(|e| e.jahr1).collect::<Vec<i32>>());
+        assert!(
+            reps::sb_ereignis::get_list_ext(&db, Some(&"p2".into()), None)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(merge_persons0(&mut db, &"p1".into(), &"p2".into()).is_err());
+    }
+
+    #[test]
+    fn dot_file() {
+        let daten = ServiceDaten::init();
+        let c = reps::establish_test_connection();
+        let mut db = DbContext::new(&daten, &c);
+        for (uid, g) in [
+            ("gf", "M"),
+            ("gm", "F"),
+            ("son", "M"),
+            ("daughter", "F"),
+            ("x", "F"),
+            ("y", "M"),
+            ("c", "M"),
+            ("d", "F"),
+            ("me", "M"),
+        ]
+        .iter()
+        {
+            person(&mut db, uid, g);
+        }
+        // Pedigree collapse: the parents of me are cousins.
+        family(&mut db, "f0", "gf", "gm", &["son", "daughter"]);
+        family(&mut db, "f1", "son", "x", &["c"]);
+        family(&mut db, "f2", "y", "daughter", &["d"]);
+        family(&mut db, "f3", "c", "d", &["me"]);
+        let edges = |uid: &str, descendants: bool| {
+            get_dot_file0(&db, &uid.into(), 5, descendants)
+                .unwrap()
+                .into_iter()
+                .filter(|l| l.contains("->"))
+                .collect::<Vec<String>>()
+        };
+        let l = edges("me", false);
+        assert_eq!(4 * 2 + 5, l.len());
+        assert!(l.contains(&"  \"Ff0\" -> \"Pson\";".to_string()));
+        assert!(l.contains(&"  \"Ff0\" -> \"Pdaughter\";".to_string()));
+        assert_eq!(1, l.iter().filter(|e| e.starts_with("  \"Pgf\"")).count());
+        let l = edges("gf", true);
+        assert_eq!(4 * 2 + 5, l.len());
+        assert_eq!(1, l.iter().filter(|e| e.ends_with("\"Pme\";")).count());
+    }
+}
@@ -13,7 +13,7 @@ use chrono::{NaiveDate, NaiveDateTime};
 use diesel::{sql_query, Connection, RunQueryDsl};
 use lazy_static::lazy_static;
 use regex::{Regex, RegexBuilder};
-use rsbp_rep::models::{Benutzer, MaMandant, TbEintrag};
+use rsbp_rep::models::{Benutzer, FzFahrrad, FzFahrradstand, FzNotiz, MaMandant, TbEintrag};
 use std::cmp;
 
 /// Initialize the database.
@@ -37,6 +37,16 @@ pub fn init_db<'a>(daten: &'a ServiceDaten) -> Result<()> {
     //     "update tb_eintrag_ort set datum_bis=date(datum_bis) where not datum_bis is null",
     // )
     // .execute(&c)?;
+    let _q = sql_query(
+        "CREATE TABLE IF NOT EXISTS TB_ORT_ADRESSE (MANDANT_NR INTEGER NOT NULL, ORT_UID VARCHAR(36) NOT NULL, ADRESSE_UID VARCHAR(35) NOT NULL, ANGELEGT_VON VARCHAR(20), ANGELEGT_AM TIMESTAMP, GEAENDERT_VON VARCHAR(20), GEAENDERT_AM TIMESTAMP, PRIMARY KEY (MANDANT_NR, ORT_UID))",
+    )
+    .execute(&c)?;
+    // Move links between positions and addresses from BYTE_DATEN.
+    let _q = sql_query(
+        "INSERT OR IGNORE INTO TB_ORT_ADRESSE SELECT mandant_nr, uid, metadaten, angelegt_von, angelegt_am, geaendert_von, geaendert_am FROM BYTE_DATEN WHERE typ='TB_Ort_Adresse' AND NOT metadaten IS NULL",
+    )
+    .execute(&c)?;
+    let _q = sql_query("DELETE FROM BYTE_DATEN WHERE typ='TB_Ort_Adresse'").execute(&c)?;
     // TODO init_db
     Ok(())
 }
@@ -314,41 +324,12 @@ pub fn replicate_table<'a>(
                     };
                     if let Some(mut es) = reps::tb_eintrag::get(&db, &e.mandant_nr, &e.datum)? {
                         if es.eintrag != e.eintrag {
-                            // Wenn es.angelegtAm != e.angelegtAm, Einträge zusammenkopieren
-                            // Wenn es.angelegtAm == e.angelegtAm und (e.geaendertAm == null oder es.geaendertAm > e.geaendertAm), Eintrag lassen
-                            // Wenn es.angelegtAm == e.angelegtAm und es.geaendertAm <= e.geaendertAm, Eintrag überschreiben
-                            let mut zusammenkopieren = false;
-                            let mut lassen = false;
-                            // if (e.Angelegt_Am.HasValue
-                            //     && (!es.Angelegt_Am.HasValue || es.Angelegt_Am != e.Angelegt_Am))
-                            if let Some(eaa) = e.angelegt_am {
-                                if let Some(esaa) = es.angelegt_am {
-                                    if eaa != esaa {
-                                        zusammenkopieren = true;
-                                    }
-                                } else {
-                                    zusammenkopieren = true;
-                                }
-                            }
-                            if !zusammenkopieren {
-                                // else if (es.Angelegt_Am.HasValue && e.Angelegt_Am.HasValue && es.Angelegt_Am == e.Angelegt_Am
-                                //   && es.Geaendert_Am.HasValue && (!e.Geaendert_Am.HasValue || es.Geaendert_Am > e.Geaendert_Am))
-                                if let Some(esaa) = es.angelegt_am {
-                                    if let Some(eaa) = e.angelegt_am {
-                                        if esaa == eaa {
-                                            if let Some(esga) = es.geaendert_am {
-                                                if let Some(ega) = e.geaendert_am {
-                                                    if esga > ega {
-                                                        lassen = true;
-                                                    }
-                                                } else {
-                                                    lassen = true;
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
+                            let (zusammenkopieren, lassen) = get_merge_mode(
+                                &es.angelegt_am,
+                                &es.geaendert_am,
+                                &e.angelegt_am,
+                                &e.geaendert_am,
+                            );
                             if zusammenkopieren {
                                 // Zusammenkopieren
                                 es.eintrag = format!(
@@ -411,7 +392,57 @@ Lokal: {}",
                 }
             }
         } else if table == "FZ_Notiz" {
-            // TODO Replikationen ergänzen
+            let jo: serde_json::Value = serde_json::from_str(json.as_str())
+                .map_err(|err| RsbpError::error_string(err.to_string().as_str()))?;
+            if let Some(jarr) = jo[table].as_array() {
+                for a in jarr {
+                    let e = FzNotiz {
+                        mandant_nr: daten.mandant_nr,
+                        uid: json_str(a, "uid")?,
+                        thema: json_str(a, "thema")?,
+                        notiz: json_ostr(a, "notiz")?,
+                        angelegt_von: json_ostr(a, "angelegtVon")?,
+                        angelegt_am: json_ondt(a, "angelegtAm")?,
+                        geaendert_von: json_ostr(a, "geaendertVon")?,
+                        geaendert_am: json_ondt(a, "geaendertAm")?,
+                    };
+                    let mut n = e.clone();
+                    if let Some(es) = reps::fz_notiz::get(&db, &e.mandant_nr, &e.uid)? {
+                        if es.thema == e.thema && es.notiz == e.notiz {
+                            continue;
+                        }
+                        let (zusammenkopieren, lassen) = get_merge_mode(
+                            &es.angelegt_am,
+                            &es.geaendert_am,
+                            &e.angelegt_am,
+                            &e.geaendert_am,
+                        );
+                        if zusammenkopieren {
+                            // Zusammenkopieren
+                            n.thema = es.thema;
+                            n.notiz = Some(format!(
+                                "Server: {}
+Lokal: {}",
+                                functions::ostr_to_str(&es.notiz),
+                                functions::ostr_to_str(&e.notiz)
+                            ));
+                        } else if lassen {
+                            continue;
+                        }
+                    }
+                    reps::fz_notiz::save0(
+                        &mut db,
+                        &n.mandant_nr,
+                        &n.uid,
+                        &n.thema,
+                        &n.notiz,
+                        &n.angelegt_von,
+                        &n.angelegt_am,
+                        &n.geaendert_von,
+                        &n.geaendert_am,
+                    )?;
+                }
+            }
         } else if table == "HH_Buchung" {
             //
         } else if table == "HH_Ereignis" {
@@ -419,9 +450,68 @@ Lokal: {}",
         } else if table == "HH_Konto" {
             //
         } else if table == "FZ_Fahrrad" {
-            //
+            let jo: serde_json::Value = serde_json::from_str(json.as_str())
+                .map_err(|err| RsbpError::error_string(err.to_string().as_str()))?;
+            if let Some(jarr) = jo[table].as_array() {
+                for a in jarr {
+                    let e = FzFahrrad {
+                        mandant_nr: daten.mandant_nr,
+                        uid: json_str(a, "uid")?,
+                        bezeichnung: json_str(a, "bezeichnung")?,
+                        typ: json_i32(a, "typ")?,
+                        angelegt_von: json_ostr(a, "angelegtVon")?,
+                        angelegt_am: json_ondt(a, "angelegtAm")?,
+                        geaendert_von: json_ostr(a, "geaendertVon")?,
+                        geaendert_am: json_ondt(a, "geaendertAm")?,
+                    };
+                    if let Some(es) = reps::fz_fahrrad::get(&db, &e.mandant_nr, &e.uid)? {
+                        if (es.bezeichnung == e.bezeichnung && es.typ == e.typ)
+                            || is_kept(
+                                &es.angelegt_am,
+                                &es.geaendert_am,
+                                &e.angelegt_am,
+                                &e.geaendert_am,
+                            )
+                        {
+                            continue;
+                        }
+                    }
+                    reps::fz_fahrrad::save0(
+                        &mut db,
+                        &e.mandant_nr,
+                        &e.uid,
+                        &e.bezeichnung,
+                        &e.typ,
+                        &e.angelegt_von,
+                        &e.angelegt_am,
+                        &e.geaendert_von,
+                        &e.geaendert_am,
+                    )?;
+                }
+            }
         } else if table == "FZ_Fahrradstand" {
-            //
+            let jo: serde_json::Value = serde_json::from_str(json.as_str())
+                .map_err(|err| RsbpError::error_string(err.to_string().as_str()))?;
+            if let Some(jarr) = jo[table].as_array() {
+                for a in jarr {
+                    let e = FzFahrradstand {
+                        mandant_nr: daten.mandant_nr,
+                        fahrrad_uid: json_str(a, "fahrradUid")?,
+                        datum: json_nd(a, "datum")?,
+                        nr: json_i32(a, "nr")?,
+                        zaehler_km: json_f64(a, "zaehlerKm")?,
+                        periode_km: json_f64(a, "periodeKm")?,
+                        periode_schnitt: json_f64(a, "periodeSchnitt")?,
+                        beschreibung: json_ostr(a, "beschreibung")?,
+                        angelegt_von: json_ostr(a, "angelegtVon")?,
+                        angelegt_am: json_ondt(a, "angelegtAm")?,
+                        geaendert_von: json_ostr(a, "geaendertVon")?,
+                        geaendert_am: json_ondt(a, "geaendertAm")?,
+                        replikation_uid: json_ostr(a, "replikationUid")?,
+                    };
+                    replicate_mileage0(&mut db, &e)?;
+                }
+            }
         } else {
             return Err(RsbpError::error_string(
                 format!("Unknown table {}", table).as_str(),
@@ -435,6 +525,128 @@ Lokal: {}",
     tr
 }
 
+/// Replicate a mileage of a bike.
+/// An existing mileage is found by the replication ID or the primary key.
+/// * db: Database context.
+/// * e: Replicated mileage.
+/// * returns: Possibly errors.
+fn replicate_mileage0(db: &mut DbContext, e: &FzFahrradstand) -> Result<()> {
+    // Bestehenden Stand über die Replikations-ID oder den Primärschlüssel suchen.
+    let mut oes = None;
+    if let Some(ruid) = &e.replikation_uid {
+        oes = reps::fz_fahrradstand::get_by_replication_uid(db, ruid)?;
+    }
+    if oes.is_none() {
+        oes = reps::fz_fahrradstand::get(db, &e.mandant_nr, &e.fahrrad_uid, &e.datum, &e.nr)?;
+    }
+    let mut ruid = e.replikation_uid.clone();
+    if let Some(es) = oes {
+        if is_kept(
+            &es.angelegt_am,
+            &es.geaendert_am,
+            &e.angelegt_am,
+            &e.geaendert_am,
+        ) {
+            return Ok(());
+        }
+        if ruid.is_none() {
+            ruid = es.replikation_uid.clone();
+        }
+        if es.fahrrad_uid != e.fahrrad_uid || es.datum != e.datum || es.nr != e.nr {
+            // Primärschlüssel geändert
+            if let Some(other) =
+                reps::fz_fahrradstand::get(db, &e.mandant_nr, &e.fahrrad_uid, &e.datum, &e.nr)?
+            {
+                if other.replikation_uid != es.replikation_uid {
+                    // Anderen Stand mit dem neuen Primärschlüssel nicht überschreiben.
+                    return Ok(());
+                }
+            }
+            reps::fz_fahrradstand::delete(db, &es)?;
+        }
+    }
+    if ruid.is_none() {
+        ruid = Some(functions::get_uid());
+    }
+    reps::fz_fahrradstand::save0(
+        db,
+        &e.mandant_nr,
+        &e.fahrrad_uid,
+        &e.datum,
+        &e.nr,
+        &e.zaehler_km,
+        &e.periode_km,
+        &e.periode_schnitt,
+        &e.beschreibung,
+        &e.angelegt_von,
+        &e.angelegt_am,
+        &e.geaendert_von,
+        &e.geaendert_am,
+        &ruid,
+    )?;
+    Ok(())
+}
+
+/// Decide how to merge a replicated dataset with an existing one.
+/// * Different creation times: Copy both together.
+/// * Same creation times and existing dataset changed later or new one unchanged: Keep existing dataset.
+/// * Otherwise: Overwrite existing dataset.
+/// * returns: Copy together and keep existing dataset.
+fn get_merge_mode(
+    es_angelegt_am: &Option<NaiveDateTime>,
+    es_geaendert_am: &Option<NaiveDateTime>,
+    e_angelegt_am: &Option<NaiveDateTime>,
+    e_geaendert_am: &Option<NaiveDateTime>,
+) -> (bool, bool) {
+    let mut zusammenkopieren = false;
+    let mut lassen = false;
+    if let Some(eaa) = e_angelegt_am {
+        if let Some(esaa) = es_angelegt_am {
+            if eaa != esaa {
+                zusammenkopieren = true;
+            }
+        } else {
+            zusammenkopieren = true;
+        }
+    }
+    if !zusammenkopieren {
+        if let (Some(esaa), Some(eaa)) = (es_angelegt_am, e_angelegt_am) {
+            if esaa == eaa {
+                if let Some(esga) = es_geaendert_am {
+                    if let Some(ega) = e_geaendert_am {
+                        if esga > ega {
+                            lassen = true;
+                        }
+                    } else {
+                        lassen = true;
+                    }
+                }
+            }
+        }
+    }
+    (zusammenkopieren, lassen)
+}
+
+/// Decide whether an existing dataset without text to copy together is kept.
+/// With different creation times the later creation or change wins.
+fn is_kept(
+    es_angelegt_am: &Option<NaiveDateTime>,
+    es_geaendert_am: &Option<NaiveDateTime>,
+    e_angelegt_am: &Option<NaiveDateTime>,
+    e_geaendert_am: &Option<NaiveDateTime>,
+) -> bool {
+    let (zusammenkopieren, lassen) = get_merge_mode(
+        es_angelegt_am,
+        es_geaendert_am,
+        e_angelegt_am,
+        e_geaendert_am,
+    );
+    if zusammenkopieren {
+        return es_geaendert_am.or(*es_angelegt_am) > e_geaendert_am.or(*e_angelegt_am);
+    }
+    lassen
+}
+
 fn json_ostr(v: &serde_json::Value, key: &str) -> Result<Option<String>> {
     let data = v[key].as_str();
     if let Some(s) = data {
@@ -457,6 +669,34 @@ fn json_str(v: &serde_json::Value, key: &str) -> Result<String> {
     ))
 }
 
+fn json_i32(v: &serde_json::Value, key: &str) -> Result<i32> {
+    let data = v[key].as_i64();
+    if let Some(i) = data {
+        return Ok(i as i32);
+    }
+    Err(RsbpError::error_string(
+        format!(
+            "Missing or wrong json i32 value for key {}: '{:?}'",
+            key, data
+        )
+        .as_str(),
+    ))
+}
+
+fn json_f64(v: &serde_json::Value, key: &str) -> Result<f64> {
+    let data = v[key].as_f64();
+    if let Some(f) = data {
+        return Ok(f);
+    }
+    Err(RsbpError::error_string(
+        format!(
+            "Missing or wrong json f64 value for key {}: '{:?}'",
+            key, data
+        )
+        .as_str(),
+    ))
+}
+
 fn json_nd(v: &serde_json::Value, key: &str) -> Result<NaiveDate> {
     let data = v[key].as_str();
     let ond = functions::ostr_to_ond(data);
@@ -842,3 +1082,106 @@ struct ReplicationTable<'a> {
     #[allow(dead_code)]
     pub copy: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        apis::services::ServiceDaten,
+        services::reps::{self, DbContext},
+    };
+    use chrono::{NaiveDate, NaiveDateTime};
+    use diesel::{sql_query, RunQueryDsl};
+    use rsbp_rep::models::FzFahrradstand;
+
+    fn t(s: &str) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").ok()
+    }
+
+    #[test]
+    fn merge_mode() {
+        let a1 = t("2023-01-01 10:00:00");
+        let a2 = t("2023-01-02 10:00:00");
+        let g1 = t("2023-02-01 10:00:00");
+        let g2 = t("2023-02-02 10:00:00");
+        // Different creation times: copy together.
+        assert_eq!((true, false), super::get_merge_mode(&a1, &None, &a2, &None));
+        assert_eq!(
+            (true, false),
+            super::get_merge_mode(&None, &None, &a2, &None)
+        );
+        // Same creation time and existing dataset changed later: keep.
+        assert_eq!((false, true), super::get_merge_mode(&a1, &g2, &a1, &g1));
+        assert_eq!((false, true), super::get_merge_mode(&a1, &g1, &a1, &None));
+        // Same creation time and new dataset changed later: overwrite.
+        assert_eq!((false, false), super::get_merge_mode(&a1, &g1, &a1, &g2));
+        assert_eq!((false, false), super::get_merge_mode(&a1, &None, &a1, &g1));
+    }
+
+    #[test]
+    fn kept() {
+        let a1 = t("2023-01-01 10:00:00");
+        let a2 = t("2023-01-02 10:00:00");
+        let g1 = t("2023-02-01 10:00:00");
+        let g2 = t("2023-02-02 10:00:00");
+        // Different creation times: the later change or creation wins.
+        assert!(super::is_kept(&a1, &g2, &a2, &g1));
+        assert!(!super::is_kept(&a1, &g1, &a2, &g2));
+        assert!(super::is_kept(&a2, &None, &a1, &None));
+        assert!(!super::is_kept(&a1, &None, &a2, &None));
+        // Same creation time and existing dataset changed later: keep.
+        assert!(super::is_kept(&a1, &g2, &a1, &g1));
+        // Same creation time and new dataset changed later: overwrite.
+        assert!(!super::is_kept(&a1, &g1, &a1, &g2));
+    }
+
+    #[test]
+    fn replicate_mileage() {
+        let daten = ServiceDaten::init();
+        let c = reps::establish_test_connection();
+        let m = daten.mandant_nr;
+        for sql in [
+            format!("INSERT INTO FZ_FAHRRADSTAND VALUES ({}, 'f1', '2023-01-01', 0, 100, 100, 0, NULL, 'x', '2023-01-01 10:00:00', NULL, NULL, 'r1')", m),
+            format!("INSERT INTO FZ_FAHRRADSTAND VALUES ({}, 'f1', '2023-01-02', 0, 200, 100, 0, NULL, 'x', '2023-01-02 10:00:00', NULL, NULL, 'r2')", m),
+        ]
+        .iter()
+        {
+            sql_query(sql.as_str()).execute(&c).unwrap();
+        }
+        let mut db = DbContext::new(&daten, &c);
+        let mut e = FzFahrradstand {
+            mandant_nr: m,
+            fahrrad_uid: "f1".into(),
+            datum: NaiveDate::from_ymd_opt(2023, 1, 2).unwrap(),
+            nr: 0,
+            zaehler_km: 150.0,
+            periode_km: 50.0,
+            periode_schnitt: 0.0,
+            beschreibung: None,
+            angelegt_von: Some("x".into()),
+            angelegt_am: t("2023-01-01 10:00:00"),
+            geaendert_von: Some("y".into()),
+            geaendert_am: t("2023-02-01 10:00:00"),
+            replikation_uid: Some("r1".into()),
+        };
+        let km = |db: &DbContext| {
+            let mut l = reps::fz_fahrradstand::get_list(db, m).unwrap();
+            l.sort_by(|a, b| a.datum.cmp(&b.datum));
+            l.iter()
+                .map(|a| format!("{} {} {:?}", a.datum, a.zaehler_km, a.replikation_uid))
+                .collect::<Vec<String>>()
+        };
+        // Changed key collides with another mileage: keep both.
+        super::replicate_mileage0(&mut db, &e).unwrap();
+        assert_eq!(
+            vec!["2023-01-01 100 Some(\"r1\")", "2023-01-02 200 Some(\"r2\")"],
+            km(&db)
+        );
+        // Changed key without collision: move the mileage.
+        e.datum = NaiveDate::from_ymd_opt(2023, 1, 3).unwrap();
+        super::replicate_mileage0(&mut db, &e).unwrap();
+        assert_eq!(
+            vec!["2023-01-02 200 Some(\"r2\")", "2023-01-03 150 Some(\"r1\")"],
+            km(&db)
+        );
+    }
+}
@@ -3,7 +3,10 @@ use super::{
     undo::UndoRedoStack,
 };
 use crate::{
-    apis::{enums::SearchDirectionEnum, services::ServiceDaten},
+    apis::{
+        enums::{DiaryFormatEnum, SearchDirectionEnum},
+        services::ServiceDaten,
+    },
     base::functions,
     config::RsbpError,
     res::messages::M,
@@ -13,9 +16,10 @@ use chrono::{Datelike, NaiveDate, NaiveDateTime};
 use diesel::Connection;
 use regex::{Regex, RegexBuilder};
 use rsbp_rep::{
-    models::{TbEintrag, TbOrt},
-    models_ext::TbEintragOrtExt,
+    models::{AdAdresse, TbEintrag, TbEintragOrt, TbOrt},
+    models_ext::{TbEintragExport, TbEintragFts, TbEintragOrtExt},
 };
+use std::fs;
 
 /// Get a diary entry.
 /// * daten: Service data for database access.
@@ -276,6 +280,40 @@ pub fn save_entry<'a>(
     tr
 }
 
+/// Append a line to a diary entry if the entry does not contain it yet.
+/// * db: Database context within a transaction.
+/// * date: Affected date.
+/// * line: Affected line.
+/// * returns: Possibly errors.
+pub(super) fn append_entry<'a>(db: &'a mut DbContext, date: &NaiveDate, line: &str) -> Result<()> {
+    let daten = db.daten;
+    if let Some(ref mut tb) = reps::tb_eintrag::get(db, &daten.mandant_nr, date)? {
+        if tb.eintrag.lines().any(|l| l.trim() == line) {
+            return Ok(());
+        }
+        if tb.replikation_uid.is_none() {
+            tb.replikation_uid = Some(functions::get_uid());
+        }
+        tb.eintrag = format!("{}\n{}", tb.eintrag.trim_end(), line);
+        tb.geaendert_am = Some(daten.get_now());
+        tb.geaendert_von = Some(daten.benutzer_id.to_string());
+        reps::tb_eintrag::update(db, tb)?;
+    } else {
+        let tb = TbEintrag {
+            mandant_nr: daten.mandant_nr,
+            datum: date.clone(),
+            eintrag: line.to_string(),
+            angelegt_am: Some(daten.get_now()),
+            angelegt_von: Some(daten.benutzer_id.to_string()),
+            geaendert_am: None,
+            geaendert_von: None,
+            replikation_uid: Some(functions::get_uid()),
+        };
+        reps::tb_eintrag::insert(db, &tb)?;
+    }
+    Ok(())
+}
+
 /// Optimieren der Positionen, d.h. verlängern oder Lücke füllen.
 /// * daten: Service data for database access.
 /// * puid: Affected position ID.
@@ -283,7 +321,7 @@ pub fn save_entry<'a>(
 /// * to: Affected to date.
 /// * created_by: Affected creation user id.
 /// * created_at: Affection creation time.
-fn optimize_positions<'a>(
+pub(super) fn optimize_positions<'a>(
     db: &'a mut DbContext,
     puid: &String,
     from: &NaiveDate,
@@ -369,6 +407,122 @@ pub fn get_position<'a>(daten: &'a ServiceDaten, uid: &String) -> Result<Option<
     Ok(e)
 }
 
+/// Get the address linked to a position.
+/// * daten: Service data for database access.
+/// * uid: Affected position ID.
+/// * returns: Address or possibly errors.
+pub fn get_position_address<'a>(
+    daten: &'a ServiceDaten,
+    uid: &String,
+) -> Result<Option<AdAdresse>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    if let Some(b) = reps::tb_ort_adresse::get(&db, &daten.mandant_nr, uid)? {
+        return reps::ad_adresse::get(&db, &daten.mandant_nr, &b.adresse_uid);
+    }
+    Ok(None)
+}
+
+/// Get the positions linked to an address.
+/// * daten: Service data for database access.
+/// * adresse_uid: Affected address ID.
+/// * returns: Position list or possibly errors.
+pub fn get_address_position_list<'a>(
+    daten: &'a ServiceDaten,
+    adresse_uid: &String,
+) -> Result<Vec<TbOrt>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let l = reps::tb_ort_adresse::get_list_ext(&db, None, Some(adresse_uid))?;
+    let mut v: Vec<TbOrt> = vec![];
+    for b in l {
+        if let Some(o) = reps::tb_ort::get(&db, &daten.mandant_nr, &b.ort_uid)? {
+            v.push(o);
+        }
+    }
+    v.sort_by(|a, b| a.bezeichnung.cmp(&b.bezeichnung));
+    Ok(v)
+}
+
+/// Get the diary entries of days at positions linked to the addresses of a person.
+/// * daten: Service data for database access.
+/// * person_uid: Affected person ID.
+/// * returns: Diary entries or possibly errors.
+pub fn get_person_entry_list<'a>(
+    daten: &'a ServiceDaten,
+    person_uid: &String,
+) -> Result<Vec<TbEintrag>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let s = check_search(&Default::default());
+    let l = reps::tb_eintrag::get_list_search(
+        &db,
+        &SearchDirectionEnum::None,
+        &None,
+        &s,
+        &Some(person_uid.clone()),
+        &None,
+        &None,
+    )?;
+    Ok(l)
+}
+
+/// Link a position to an address or remove the link.
+/// * daten: Service data for database access.
+/// * uid: Affected position ID.
+/// * adresse_uid: Affected address ID or None for removing the link.
+/// * returns: Possibly errors.
+pub fn save_position_address<'a>(
+    daten: &'a ServiceDaten,
+    uid: &String,
+    adresse_uid: &Option<String>,
+) -> Result<()> {
+    let is_de = daten.config.is_de();
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<(), RsbpError, _>(|| {
+        if reps::tb_ort::get(&db, &daten.mandant_nr, uid)?.is_none() {
+            return Err(RsbpError::error_msg(M::M1013, is_de));
+        }
+        match adresse_uid {
+            Some(auid) => {
+                if reps::ad_adresse::get(&db, &daten.mandant_nr, auid)?.is_none() {
+                    return Err(RsbpError::error_msg(M::M1013, is_de));
+                }
+                reps::tb_ort_adresse::save(&mut db, &daten.mandant_nr, uid, auid)?;
+            }
+            None => move_position_addresses(&mut db, Some(uid), None, None)?,
+        };
+        Ok(())
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Move or delete links between positions and addresses.
+/// * db: Database context.
+/// * uid: Affected position ID or None for all positions.
+/// * adresse_uid: Affected address ID or None for all addresses.
+/// * to: Affected new address ID or None for deleting the links.
+pub(super) fn move_position_addresses(
+    db: &mut DbContext,
+    uid: Option<&String>,
+    adresse_uid: Option<&String>,
+    to: Option<&String>,
+) -> Result<()> {
+    for b in reps::tb_ort_adresse::get_list_ext(db, uid, adresse_uid)? {
+        match to {
+            Some(t) => {
+                reps::tb_ort_adresse::save(db, &b.mandant_nr, &b.ort_uid, t)?;
+            }
+            None => reps::tb_ort_adresse::delete(db, &b)?,
+        };
+    }
+    Ok(())
+}
+
 /// Get a list of positions.
 /// * daten: Service data for database access.
 /// * puid: Affected position ID.
@@ -390,7 +544,7 @@ pub fn get_position_list<'a>(
 /// * dir: Affected direction.
 /// * date: Affected base date.
 /// * search: Affected search strings.
-/// * puid: Affected position uid.
+/// * puid: Affected position uid or person uid for positions linked to the person's addresses.
 /// * from: Affected from date.
 /// * to: Affected to date.
 /// * returns: Position list or possibly errors.
@@ -429,7 +583,7 @@ pub fn search_date<'a>(
 /// Get a vector of all fitting diary entries for storing in a file.
 /// * daten: Service data for database access.
 /// * search: Affected search strings.
-/// * puid: Affected position uid.
+/// * puid: Affected position uid or person uid for positions linked to the person's addresses.
 /// * from: Affected from date.
 /// * to: Affected to date.
 /// * returns: Vector of all fitting diary entries.
@@ -471,17 +625,7 @@ pub fn get_file<'a>(
         to,
     )?;
     let is_de = daten.config.is_de();
-    let mut v: Vec<String> = vec![];
-    v.push(M::tb002(&daten.get_now(), is_de));
-    v.push(M::tb003(&s, is_de));
-    if let Some(uid) = puid {
-        if let Some(p) = reps::tb_ort::get(&db, &daten.mandant_nr, uid)? {
-            v.push(M::tb010(&p.bezeichnung, is_de));
-        }
-    }
-    if from.is_some() || to.is_some() {
-        v.push(M::tb011(&from, &to, is_de));
-    }
+    let mut v = get_header(&db, &s, puid, from, to)?;
     v.push("".into());
     if rf {
         // Zähler prüfen.
@@ -516,7 +660,386 @@ pub fn get_file<'a>(
     Ok(v)
 }
 
-fn check_search(search: &[String; 9]) -> [String; 9] {
+/// Get the header lines of a report with date, search strings, position and period.
+fn get_header(
+    db: &DbContext,
+    s: &[String; 9],
+    puid: &Option<String>,
+    from: &Option<NaiveDate>,
+    to: &Option<NaiveDate>,
+) -> Result<Vec<String>> {
+    let daten = db.daten;
+    let is_de = daten.config.is_de();
+    let mut v: Vec<String> = vec![];
+    v.push(M::tb002(&daten.get_now(), is_de));
+    v.push(M::tb003(s, is_de));
+    if let Some(uid) = puid {
+        if let Some(p) = reps::tb_ort::get(db, &daten.mandant_nr, uid)? {
+            v.push(M::tb010(&p.bezeichnung, is_de));
+        } else if let Some(p) = reps::ad_person::get(db, &daten.mandant_nr, uid)? {
+            v.push(M::mecf(M::AD013_, &[p.name().as_str()], is_de));
+        }
+    }
+    if from.is_some() || to.is_some() {
+        v.push(M::tb011(&from, &to, is_de));
+    }
+    Ok(v)
+}
+
+/// Export diary entries of a period or a search result as Markdown, HTML or JSON.
+/// * daten: Service data for database access.
+/// * format: Affected export format.
+/// * query: Affected full-text search text, if empty all entries fitting the search strings are exported.
+/// * search: Affected search strings.
+/// * puid: Affected position uid or person uid for positions linked to the person's addresses.
+/// * from: Affected from date.
+/// * to: Affected to date.
+/// * returns: Lines of the export ordered by date or possibly errors.
+pub fn get_export<'a>(
+    daten: &'a ServiceDaten,
+    format: &DiaryFormatEnum,
+    query: &str,
+    search: &[String; 9],
+    puid: &Option<String>,
+    from: &Option<NaiveDate>,
+    to: &Option<NaiveDate>,
+) -> Result<Vec<String>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    get_export0(&db, format, query, search, puid, from, to)
+}
+
+/// Get the lines of an export of diary entries with positions.
+fn get_export0(
+    db: &DbContext,
+    format: &DiaryFormatEnum,
+    query: &str,
+    search: &[String; 9],
+    puid: &Option<String>,
+    from: &Option<NaiveDate>,
+    to: &Option<NaiveDate>,
+) -> Result<Vec<String>> {
+    let daten = db.daten;
+    let mut s = check_search(search);
+    let q = query.trim();
+    let mut l: Vec<TbEintrag> = Vec::new();
+    if q.is_empty() {
+        l = reps::tb_eintrag::get_list_search(
+            db,
+            &SearchDirectionEnum::None,
+            &None,
+            &s,
+            puid,
+            from,
+            to,
+        )?;
+    } else {
+        if s[0] == "%" {
+            s[0] = String::new();
+        }
+        let mut dates: Vec<NaiveDate> = get_list_fts0(db, q, &s, puid, from, to)?
+            .into_iter()
+            .map(|e| e.datum)
+            .collect();
+        dates.sort();
+        for d in dates.iter() {
+            if let Some(e) = reps::tb_eintrag::get(db, &daten.mandant_nr, d)? {
+                l.push(e);
+            }
+        }
+    }
+    let mut links: Vec<(TbEintragOrt, TbOrt)> = Vec::new();
+    if let (Some(f), Some(t)) = (l.first(), l.last()) {
+        links = reps::tb_eintrag_ort::get_list_join(db, &f.datum, &t.datum)?
+            .into_iter()
+            .filter(|j| {
+                l.iter()
+                    .any(|e| j.0.datum_von <= e.datum && e.datum <= j.0.datum_bis)
+            })
+            .collect();
+    }
+    if *format == DiaryFormatEnum::Json {
+        let mut orte: Vec<TbOrt> = Vec::new();
+        for j in links.iter() {
+            if !orte.iter().any(|o| o.uid == j.1.uid) {
+                orte.push(j.1.clone());
+            }
+        }
+        let export = TbEintragExport {
+            version: 1,
+            eintraege: l,
+            orte,
+            eintrag_orte: links.into_iter().map(|j| j.0).collect(),
+        };
+        let json = serde_json::to_string_pretty(&export)
+            .map_err(|err| RsbpError::error_string(err.to_string().as_str()))?;
+        return Ok(json.lines().map(|x| x.to_string()).collect());
+    }
+    let is_de = daten.config.is_de();
+    let header = get_header(db, &s, puid, from, to)?;
+    let html = *format == DiaryFormatEnum::Html;
+    let mut v: Vec<String> = vec![];
+    if html {
+        let title = escape_html(header[0].as_str());
+        v.push("<!DOCTYPE html>".into());
+        v.push(format!(
+            "<html lang=\"{}\">",
+            if is_de { "de" } else { "en" }
+        ));
+        v.push("<head>".into());
+        v.push("<meta charset=\"utf-8\">".into());
+        v.push(format!("<title>{}</title>", title));
+        v.push("</head>".into());
+        v.push("<body>".into());
+        v.push(format!("<h1>{}</h1>", title));
+        for h in header.iter().skip(1) {
+            v.push(format!("<p>{}</p>", escape_html(h)));
+        }
+    } else {
+        v.push(format!("# {}", header[0]));
+        v.push("".into());
+        for h in header.iter().skip(1) {
+            v.push(format!("- {}", h));
+        }
+    }
+    for e in l.iter() {
+        let day = format!(
+            "{} {}",
+            e.datum.format("%Y-%m-%d"),
+            functions::ond_to_weekday(&Some(e.datum), is_de)
+        );
+        let pos: Vec<String> = links
+            .iter()
+            .filter(|j| j.0.datum_von <= e.datum && e.datum <= j.0.datum_bis)
+            .map(|j| {
+                format!(
+                    "{} ({}, {}, {})",
+                    j.1.bezeichnung,
+                    functions::f64_to_str(j.1.breite),
+                    functions::f64_to_str(j.1.laenge),
+                    functions::f64_to_str(j.1.hoehe)
+                )
+            })
+            .collect();
+        let lines: Vec<&str> = e.eintrag.lines().map(|x| x.trim_end()).collect();
+        if html {
+            v.push(format!("<h2>{}</h2>", day));
+            let text: Vec<String> = lines.iter().map(|x| escape_html(x)).collect();
+            v.push(format!("<p>{}</p>", text.join("<br>")));
+            if !pos.is_empty() {
+                v.push("<ul>".into());
+                for p in pos.iter() {
+                    v.push(format!("<li>{}</li>", escape_html(p)));
+                }
+                v.push("</ul>".into());
+            }
+        } else {
+            v.push("".into());
+            v.push(format!("## {}", day));
+            v.push("".into());
+            for (i, x) in lines.iter().enumerate() {
+                if x.is_empty() || i + 1 == lines.len() {
+                    v.push(x.to_string());
+                } else {
+                    // Line break within the paragraph
+                    v.push(format!("{}  ", x));
+                }
+            }
+            if !pos.is_empty() {
+                v.push("".into());
+                for p in pos.iter() {
+                    v.push(format!("- {}", p));
+                }
+            }
+        }
+    }
+    if html {
+        v.push("</body>".into());
+        v.push("</html>".into());
+    }
+    Ok(v)
+}
+
+/// Import diary entries with positions from a JSON export.
+/// Entries, positions and links with the same keys are replaced.
+/// * daten: Service data for database access.
+/// * file: Affected file name.
+/// * returns: Message with numbers of imported datasets or possibly errors.
+pub fn import_file<'a>(daten: &'a ServiceDaten, file: &str) -> Result<String> {
+    let is_de = daten.config.is_de();
+    let content = match fs::read_to_string(file) {
+        Ok(c) => c,
+        Err(_) => {
+            return Err(RsbpError::error_string(
+                M::mecf(M::M1034_, &[file], is_de).as_str(),
+            ))
+        }
+    };
+    let export = match serde_json::from_str::<TbEintragExport>(content.as_str()) {
+        Ok(e) if e.version == 1 => e,
+        Ok(e) => {
+            return Err(RsbpError::error_string(
+                M::mecf(M::TB017_, &[e.version.to_string().as_str()], is_de).as_str(),
+            ))
+        }
+        Err(err) => {
+            return Err(RsbpError::error_string(
+                M::mecf(M::TB017_, &[err.to_string().as_str()], is_de).as_str(),
+            ))
+        }
+    };
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<String, RsbpError, _>(|| import_export0(&mut db, &export));
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Import diary entries with positions with all revision columns of the export.
+/// Must be called within a transaction.
+fn import_export0(db: &mut DbContext, export: &TbEintragExport) -> Result<String> {
+    let is_de = db.daten.config.is_de();
+    let mandant_nr = db.daten.mandant_nr;
+    for o in export.orte.iter() {
+        let o = TbOrt {
+            mandant_nr,
+            ..o.clone()
+        };
+        match reps::tb_ort::get2(db, &o)? {
+            Some(x) if x == o => (),
+            Some(_) => {
+                reps::tb_ort::update(db, &o)?;
+            }
+            None => {
+                reps::tb_ort::insert(db, &o)?;
+            }
+        }
+    }
+    for eo in export.eintrag_orte.iter() {
+        if !export.orte.iter().any(|o| o.uid == eo.ort_uid)
+            && reps::tb_ort::get(db, &mandant_nr, &eo.ort_uid)?.is_none()
+        {
+            return Err(RsbpError::error_string(
+                M::mecf(M::TB017_, &[eo.ort_uid.as_str()], is_de).as_str(),
+            ));
+        }
+        let eo = TbEintragOrt {
+            mandant_nr,
+            ..eo.clone()
+        };
+        match reps::tb_eintrag_ort::get2(db, &eo)? {
+            Some(x) if x == eo => (),
+            Some(_) => {
+                reps::tb_eintrag_ort::update(db, &eo)?;
+            }
+            None => {
+                reps::tb_eintrag_ort::insert(db, &eo)?;
+            }
+        }
+    }
+    for e in export.eintraege.iter() {
+        let e = TbEintrag {
+            mandant_nr,
+            ..e.clone()
+        };
+        match reps::tb_eintrag::get2(db, &e)? {
+            Some(x) if x == e => (),
+            Some(_) => {
+                reps::tb_eintrag::update(db, &e)?;
+            }
+            None => {
+                reps::tb_eintrag::insert(db, &e)?;
+            }
+        }
+    }
+    Ok(M::mecf(
+        M::TB016_,
+        &[
+            export.eintraege.len().to_string().as_str(),
+            export.orte.len().to_string().as_str(),
+            export.eintrag_orte.len().to_string().as_str(),
+        ],
+        is_de,
+    ))
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace("&", "&amp;")
+        .replace("<", "&lt;")
+        .replace(">", "&gt;")
+        .replace("\"", "&quot;")
+}
+
+/// Search diary entries with the full-text index ordered by relevance.
+/// The index is created at the first search.
+/// * daten: Service data for database access.
+/// * query: Affected search text with phrases in quotes, prefixes with asterisk and AND, OR, NOT.
+/// * search: Affected search strings which are applied additionally.
+/// * puid: Affected position uid or person uid for positions linked to the person's addresses.
+/// * from: Affected from date.
+/// * to: Affected to date.
+/// * returns: Fitting diary entries with highlighted snippets or possibly errors.
+pub fn search_entries<'a>(
+    daten: &'a ServiceDaten,
+    query: &str,
+    search: &[String; 9],
+    puid: &Option<String>,
+    from: &Option<NaiveDate>,
+    to: &Option<NaiveDate>,
+) -> Result<Vec<TbEintragFts>> {
+    let q = query.trim();
+    if q.is_empty() {
+        return Err(RsbpError::error_msg(M::TB014, daten.config.is_de()));
+    }
+    let mut s = check_search(search);
+    if s[0] == "%" {
+        s[0] = String::new();
+    }
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let l = get_list_fts0(&db, q, &s, puid, from, to)?;
+    Ok(l)
+}
+
+/// Get diary entries by full-text search, the index is created if necessary.
+/// Syntax errors of the query, e.g. unbalanced quotes, get a message.
+fn get_list_fts0(
+    db: &DbContext,
+    query: &str,
+    search: &[String; 9],
+    puid: &Option<String>,
+    from: &Option<NaiveDate>,
+    to: &Option<NaiveDate>,
+) -> Result<Vec<TbEintragFts>> {
+    reps::tb_eintrag::create_fts(db, false)?;
+    match reps::tb_eintrag::get_list_fts(db, query, search, puid, from, to) {
+        Err(RsbpError::DieselError {
+            source: diesel::result::Error::DatabaseError(_, info),
+        }) => Err(RsbpError::error_string(
+            M::mecf(M::TB018_, &[query, info.message()], db.daten.config.is_de()).as_str(),
+        )),
+        r => r,
+    }
+}
+
+/// Create or rebuild the full-text index of the diary entries,
+/// e.g. after changes by other programs.
+/// * daten: Service data for database access.
+/// * returns: Message with number of indexed entries or possibly errors.
+pub fn rebuild_search_index<'a>(daten: &'a ServiceDaten) -> Result<String> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let rows = reps::tb_eintrag::create_fts(&db, true)?;
+    Ok(M::mecf(
+        M::TB015_,
+        &[rows.to_string().as_str()],
+        daten.config.is_de(),
+    ))
+}
+
+pub(super) fn check_search(search: &[String; 9]) -> [String; 9] {
     const COLUMNS: usize = 3;
     const ROWS: usize = 3;
     let mut s = search.clone();
@@ -622,6 +1145,7 @@ pub fn delete_position<'a>(daten: &'a ServiceDaten, e: &TbOrt) -> Result<()> {
                 M::tb013(&p.datum_von, daten.config.is_de()).as_str(),
             ));
         }
+        move_position_addresses(&mut db, Some(&e.uid), None, None)?;
         reps::tb_ort::delete(&mut db, e)?;
         Ok(())
     });
@@ -630,3 +1154,177 @@ pub fn delete_position<'a>(daten: &'a ServiceDaten, e: &TbOrt) -> Result<()> {
     }
     tr
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        apis::{
+            enums::{DiaryFormatEnum, SearchDirectionEnum},
+            services::ServiceDaten,
+        },
+        res::messages::M,
+        services::reps::{self, DbContext},
+    };
+    use chrono::NaiveDate;
+    use diesel::{prelude::*, sql_query};
+    use rsbp_rep::models_ext::TbEintragExport;
+
+    #[test]
+    fn search_person() {
+        let daten = ServiceDaten::init();
+        let c = reps::establish_test_connection();
+        let m = daten.mandant_nr;
+        for sql in [
+            format!("INSERT INTO TB_EINTRAG VALUES ({}, '2023-03-01', 'Visit', NULL, NULL, NULL, NULL, NULL)", m),
+            format!("INSERT INTO TB_EINTRAG VALUES ({}, '2023-03-02', 'Home', NULL, NULL, NULL, NULL, NULL)", m),
+            format!("INSERT INTO TB_EINTRAG VALUES ({}, '2023-03-03', 'Visit', NULL, NULL, NULL, NULL, NULL)", m),
+            format!("INSERT INTO TB_EINTRAG_ORT VALUES ({}, 'o1', '2023-03-01', '2023-03-01', NULL, NULL, NULL, NULL)", m),
+            format!("INSERT INTO TB_EINTRAG_ORT VALUES ({}, 'o2', '2023-03-03', '2023-03-03', NULL, NULL, NULL, NULL)", m),
+            format!("INSERT INTO TB_ORT_ADRESSE VALUES ({}, 'o2', 'a1', NULL, NULL, NULL, NULL)", m),
+            format!("INSERT INTO AD_SITZ (mandant_nr, person_uid, reihenfolge, uid, typ, name, adresse_uid, sitz_status) VALUES ({}, 'p1', 1, 's1', 0, 'Home', 'a1', 0)", m),
+        ]
+        .iter()
+        {
+            sql_query(sql.as_str()).execute(&c).unwrap();
+        }
+        let db = DbContext::new(&daten, &c);
+        let s = super::check_search(&Default::default());
+        let dates = |puid: Option<&str>| {
+            reps::tb_eintrag::get_list_search(
+                &db,
+                &SearchDirectionEnum::None,
+                &None,
+                &s,
+                &puid.map(|p| p.to_string()),
+                &None,
+                &None,
+            )
+            .unwrap()
+            .iter()
+            .map(|e| e.datum.format("%d").to_string())
+            .collect::<Vec<String>>()
+        };
+        assert_eq!(vec!["01", "02", "03"], dates(None));
+        assert_eq!(vec!["02"], dates(Some("0")));
+        assert_eq!(vec!["01"], dates(Some("o1")));
+        assert_eq!(vec!["03"], dates(Some("p1")));
+        assert!(dates(Some("p2")).is_empty());
+    }
+
+    #[test]
+    fn search_fts() {
+        let daten = ServiceDaten::init();
+        let c = reps::establish_test_connection();
+        let mut db = DbContext::new(&daten, &c);
+        let m = daten.mandant_nr;
+        let entry = |db: &mut DbContext, d: u32, e: &str| {
+            let date = NaiveDate::from_ymd(2023, 3, d);
+            reps::tb_eintrag::save(db, &m, &date, &e.to_string()).unwrap();
+        };
+        entry(&mut db, 1, "Walk in the park with Anna");
+        assert_eq!(1, reps::tb_eintrag::create_fts(&db, false).unwrap());
+        entry(&mut db, 1, "Walk in the park with Anna and Ben");
+        entry(&mut db, 2, "Garden work, then a walk");
+        entry(&mut db, 3, "Shopping in town");
+        entry(&mut db, 3, "Shopping in town, parking was full");
+        let date = NaiveDate::from_ymd(2023, 3, 2);
+        let e = reps::tb_eintrag::get(&db, &m, &date).unwrap().unwrap();
+        reps::tb_eintrag::delete(&mut db, &e).unwrap();
+        reps::tb_eintrag::insert(&mut db, &e).unwrap();
+        assert_eq!(3, reps::tb_eintrag::create_fts(&db, false).unwrap());
+        let s: [String; 9] = Default::default();
+        let find = |q: &str, s: &[String; 9]| {
+            reps::tb_eintrag::get_list_fts(&db, q, s, &None, &None, &None)
+                .unwrap()
+                .iter()
+                .map(|e| e.datum.format("%d").to_string())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(vec!["02", "01"], find("walk", &s));
+        assert_eq!(vec!["03", "01"], find("park*", &s));
+        assert_eq!(vec!["01"], find("\"in the park\"", &s));
+        let mut s2 = s.clone();
+        s2[6] = "%Anna%".into();
+        assert_eq!(vec!["02"], find("walk", &s2));
+        let l = reps::tb_eintrag::get_list_fts(&db, "garden", &s, &None, &None, &None).unwrap();
+        assert!(l[0].snippet.contains("[Garden]"));
+        assert_eq!(3, reps::tb_eintrag::create_fts(&db, true).unwrap());
+        // Changes by other programs are indexed by the triggers.
+        sql_query("UPDATE TB_EINTRAG SET eintrag='Garden party' WHERE datum='2023-03-03'")
+            .execute(&c)
+            .unwrap();
+        assert_eq!(vec!["03", "02"], find("garden", &s));
+        sql_query("DELETE FROM TB_EINTRAG WHERE datum='2023-03-02'")
+            .execute(&c)
+            .unwrap();
+        assert_eq!(vec!["03"], find("garden", &s));
+        assert_eq!(2, reps::tb_eintrag::create_fts(&db, false).unwrap());
+        for q in ["e-mail", "\"park"].iter() {
+            let r = super::get_list_fts0(&db, q, &s, &None, &None, &None);
+            let m = M::mecf(M::TB018_, &[q, ""], false);
+            assert!(r.unwrap_err().to_string().starts_with(m.as_str()));
+        }
+    }
+
+    #[test]
+    fn export_json() {
+        let daten = ServiceDaten::init();
+        let c = reps::establish_test_connection();
+        let m = daten.mandant_nr;
+        for sql in [
+            format!("INSERT INTO TB_EINTRAG VALUES ({}, '2023-03-01', 'Trip', 'a', '2023-03-01 20:00:00', 'b', '2023-03-02 08:30:00', 'r1')", m),
+            format!("INSERT INTO TB_EINTRAG VALUES ({}, '2023-03-05', 'Home', NULL, NULL, NULL, NULL, NULL)", m),
+            format!("INSERT INTO TB_EINTRAG_ORT VALUES ({}, 'o1', '2023-02-28', '2023-03-02', 'a', '2023-03-01 20:00:00', NULL, NULL)", m),
+            format!("INSERT INTO TB_EINTRAG_ORT VALUES ({}, 'o2', '2023-03-10', '2023-03-10', NULL, NULL, NULL, NULL)", m),
+            format!("INSERT INTO TB_ORT VALUES ({}, 'o1', 'Lake', 52.5, 13.25, 34.5, 'Note', 'a', '2023-03-01 20:00:00', NULL, NULL)", m),
+            format!("INSERT INTO TB_ORT VALUES ({}, 'o2', 'Hill', 1, 2, 3, '', NULL, NULL, NULL, NULL)", m),
+        ]
+        .iter()
+        {
+            sql_query(sql.as_str()).execute(&c).unwrap();
+        }
+        let db = DbContext::new(&daten, &c);
+        let from = NaiveDate::from_ymd(2023, 3, 1);
+        let to = NaiveDate::from_ymd(2023, 3, 5);
+        let links = reps::tb_eintrag_ort::get_list_join(&db, &from, &to).unwrap();
+        assert_eq!(1, links.len());
+        assert_eq!("Lake", links[0].1.bezeichnung);
+        let s: [String; 9] = Default::default();
+        let json = super::get_export0(
+            &db,
+            &DiaryFormatEnum::Json,
+            "",
+            &s,
+            &None,
+            &Some(from),
+            &Some(to),
+        )
+        .unwrap()
+        .join("\n");
+        assert!(json.contains("\"breite\": 52.5"));
+        assert!(json.contains("\"geaendert_am\": \"2023-03-02T08:30:00\""));
+        let export: TbEintragExport = serde_json::from_str(json.as_str()).unwrap();
+        assert_eq!(2, export.eintraege.len());
+        assert_eq!(1, export.orte.len());
+        assert_eq!(1, export.eintrag_orte.len());
+
+        // Import into another database with a changed entry without revision columns.
+        let c2 = reps::establish_test_connection();
+        sql_query(format!("INSERT INTO TB_EINTRAG VALUES ({}, '2023-03-05', 'Old', NULL, NULL, NULL, NULL, NULL)", m).as_str())
+            .execute(&c2)
+            .unwrap();
+        let mut db2 = DbContext::new(&daten, &c2);
+        let msg = super::import_export0(&mut db2, &export).unwrap();
+        assert_eq!(M::mecf(M::TB016_, &["2", "1", "1"], false), msg);
+        assert!(export.eintraege == reps::tb_eintrag::get_list(&db2, m).unwrap());
+        assert!(export.orte == reps::tb_ort::get_list(&db2, m).unwrap());
+        assert!(export.eintrag_orte == reps::tb_eintrag_ort::get_list(&db2, m).unwrap());
+        // A repeated import changes nothing.
+        super::import_export0(&mut db2, &export).unwrap();
+        assert!(export.eintraege == reps::tb_eintrag::get_list(&db2, m).unwrap());
+        assert_eq!(
+            "a &lt;b&gt; &amp; &quot;c&quot;",
+            super::escape_html("a <b> & \"c\"")
+        );
+    }
+}
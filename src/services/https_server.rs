@@ -1,4 +1,9 @@
-use crate::{apis::services, base::functions, config::RsbpError, services::client_service};
+use crate::{
+    apis::services,
+    base::functions,
+    config::RsbpError,
+    services::{address_service, client_service},
+};
 use lazy_static::lazy_static;
 use mio::{
     self,
@@ -374,6 +379,24 @@ fn get_http_request(buf: &[u8]) -> Result<HttpRequest, RsbpError> {
     })
 }
 
+/// Get the resource of a path without query.
+fn get_resource(path: &str) -> &str {
+    match path.find('?') {
+        Some(i) => &path[..i],
+        None => path,
+    }
+}
+
+/// Get a value of the query of a path.
+fn get_query_value(path: &str, key: &str) -> Option<String> {
+    let i = path.find('?')?;
+    path[i + 1..]
+        .split('&')
+        .filter_map(|p| p.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v.to_string())
+}
+
 /// Handle a HTTP request.
 fn handle_http_request(request: Result<HttpRequest, RsbpError>) -> HttpResponse {
     let daten = services::get_daten();
@@ -448,13 +471,28 @@ fn handle_http_request(request: Result<HttpRequest, RsbpError>) -> HttpResponse
         }
         if token.is_empty() || token != daten.benutzer_id {
             error = format!("Not allowed {}", token);
+        } else {
+            match client_service::replicate_table(&daten, &table, &mode, &data) {
+                Ok(json) => {
+                    contenttype = "application/json; charset=utf-8";
+                    content = json;
+                }
+                Err(err) => error = format!("{}", err),
+            }
         }
-        match client_service::replicate_table(&daten, &table, &mode, &data) {
-            Ok(json) => {
-                contenttype = "application/json; charset=utf-8";
-                content = json;
+    } else if verb == "GET" && get_resource(&path) == "/birthdays.ics" {
+        // Read-only calendar subscription, e.g. /birthdays.ics?token=...
+        let token = get_query_value(&path, "token").unwrap_or_default();
+        if token.is_empty() || token != daten.benutzer_id {
+            error = format!("Not allowed {}", token);
+        } else {
+            match address_service::get_birthday_calendar(&daten) {
+                Ok(lines) => {
+                    contenttype = "text/calendar; charset=utf-8";
+                    content = lines.join("\r\n") + "\r\n";
+                }
+                Err(err) => error = format!("{}", err),
             }
-            Err(err) => error = format!("{}", err),
         }
     } else if path == "/" {
         let version =
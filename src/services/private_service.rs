@@ -0,0 +1,2100 @@
+use super::{
+    diary_service,
+    reps::{self, DbContext},
+    undo::UndoRedoStack,
+};
+use crate::{
+    apis::{
+        enums::{BikeTypeEnum, LanguageEnum},
+        services::ServiceDaten,
+    },
+    base::functions,
+    config::RsbpError,
+    res::messages::M,
+    Result,
+};
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use diesel::Connection;
+use rsbp_rep::{
+    models::{FzBuch, FzBuchautor, FzBuchserie, FzFahrrad, FzFahrradstand, FzNotiz, TbOrt},
+    models_ext::{
+        FzBookImport, FzBookStatistics, FzBuchExt, FzMileageStatistics, FzReadingLog,
+        FzReadingMonth, FzSeriesReport,
+    },
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+};
+
+/// Get a list of authors.
+/// * daten: Service data for database access.
+/// * name: Affected name filter with wildcards.
+/// * returns: List of authors or possibly errors.
+pub fn get_author_list<'a>(
+    daten: &'a ServiceDaten,
+    name: &Option<String>,
+) -> Result<Vec<FzBuchautor>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let l = reps::fz_buchautor::get_list_ext(&db, name)?;
+    Ok(l)
+}
+
+/// Get an author.
+/// * daten: Service data for database access.
+/// * uid: Affected author ID.
+/// * returns: Author or possibly errors.
+pub fn get_author<'a>(daten: &'a ServiceDaten, uid: &String) -> Result<Option<FzBuchautor>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let e = reps::fz_buchautor::get(&db, &daten.mandant_nr, uid)?;
+    Ok(e)
+}
+
+/// Save an author.
+/// * daten: Service data for database access.
+/// * uid: Affected ID, empty for a new author.
+/// * name: Affected name.
+/// * firstname: Affected first name.
+/// * memo: Affected memo.
+/// * returns: Saved author or possibly errors.
+pub fn save_author<'a>(
+    daten: &'a ServiceDaten,
+    uid: &String,
+    name: &String,
+    firstname: &String,
+    memo: &String,
+) -> Result<FzBuchautor> {
+    let n = name.trim().to_string();
+    if n.len() <= 0 {
+        return Err(RsbpError::error_msg(M::FZ032, daten.config.is_de()));
+    }
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<FzBuchautor, RsbpError, _>(|| {
+        let e = reps::fz_buchautor::save(
+            &mut db,
+            &daten.mandant_nr,
+            &get_uid(uid),
+            &n,
+            &functions::to_ostr(firstname.trim()),
+            &functions::to_ostr(memo.trim()),
+        )?;
+        Ok(e)
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Delete an author if no book references it.
+/// * daten: Service data for database access.
+/// * e: Affected Entity.
+/// * returns: Possibly errors.
+pub fn delete_author<'a>(daten: &'a ServiceDaten, e: &FzBuchautor) -> Result<()> {
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<(), RsbpError, _>(|| {
+        if reps::fz_buch::get_list_ext(&db, Some(&e.uid), None, None)?.len() > 0 {
+            return Err(RsbpError::error_msg(M::FZ039, daten.config.is_de()));
+        }
+        reps::fz_buchautor::delete(&mut db, e)?;
+        Ok(())
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Get a list of series.
+/// * daten: Service data for database access.
+/// * name: Affected name filter with wildcards.
+/// * returns: List of series or possibly errors.
+pub fn get_series_list<'a>(
+    daten: &'a ServiceDaten,
+    name: &Option<String>,
+) -> Result<Vec<FzBuchserie>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let l = reps::fz_buchserie::get_list_ext(&db, name)?;
+    Ok(l)
+}
+
+/// Get a series.
+/// * daten: Service data for database access.
+/// * uid: Affected series ID.
+/// * returns: Series or possibly errors.
+pub fn get_series<'a>(daten: &'a ServiceDaten, uid: &String) -> Result<Option<FzBuchserie>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let e = reps::fz_buchserie::get(&db, &daten.mandant_nr, uid)?;
+    Ok(e)
+}
+
+/// Save a series.
+/// * daten: Service data for database access.
+/// * uid: Affected ID, empty for a new series.
+/// * name: Affected name.
+/// * memo: Affected memo.
+/// * returns: Saved series or possibly errors.
+pub fn save_series<'a>(
+    daten: &'a ServiceDaten,
+    uid: &String,
+    name: &String,
+    memo: &String,
+) -> Result<FzBuchserie> {
+    let n = name.trim().to_string();
+    if n.len() <= 0 {
+        return Err(RsbpError::error_msg(M::FZ033, daten.config.is_de()));
+    }
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<FzBuchserie, RsbpError, _>(|| {
+        let e = reps::fz_buchserie::save(
+            &mut db,
+            &daten.mandant_nr,
+            &get_uid(uid),
+            &n,
+            &functions::to_ostr(memo.trim()),
+        )?;
+        Ok(e)
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Delete a series if no book references it.
+/// * daten: Service data for database access.
+/// * e: Affected Entity.
+/// * returns: Possibly errors.
+pub fn delete_series<'a>(daten: &'a ServiceDaten, e: &FzBuchserie) -> Result<()> {
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<(), RsbpError, _>(|| {
+        if reps::fz_buch::get_list_ext(&db, None, Some(&e.uid), None)?.len() > 0 {
+            return Err(RsbpError::error_msg(M::FZ040, daten.config.is_de()));
+        }
+        reps::fz_buchserie::delete(&mut db, e)?;
+        Ok(())
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Get a list of books with author, series and reading status.
+/// * daten: Service data for database access.
+/// * author_uid: Affected author ID.
+/// * series_uid: Affected series ID.
+/// * title: Affected title filter with wildcards.
+/// * returns: List of books or possibly errors.
+pub fn get_book_list<'a>(
+    daten: &'a ServiceDaten,
+    author_uid: &Option<String>,
+    series_uid: &Option<String>,
+    title: &Option<String>,
+) -> Result<Vec<FzBuchExt>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let l = get_book_list0(&db, author_uid, series_uid, title)?;
+    Ok(l)
+}
+
+/// Get a book with author, series and reading status.
+/// * daten: Service data for database access.
+/// * uid: Affected book ID.
+/// * returns: Book or possibly errors.
+pub fn get_book<'a>(daten: &'a ServiceDaten, uid: &String) -> Result<Option<FzBuchExt>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    if let Some(b) = reps::fz_buch::get(&db, &daten.mandant_nr, uid)? {
+        let a = reps::fz_buchautor::get(&db, &daten.mandant_nr, &b.autor_uid)?;
+        let s = reps::fz_buchserie::get(&db, &daten.mandant_nr, &b.serie_uid)?;
+        let st = reps::fz_buchstatus::get(&db, &daten.mandant_nr, &b.uid)?;
+        return Ok(Some(FzBuchExt {
+            autor_name: a.map_or(String::new(), |a| get_author_name(&a)),
+            serie_name: s.map_or(String::new(), |s| s.name),
+            ist_besitz: st.as_ref().map_or(false, |st| st.ist_besitz),
+            lesedatum: st.as_ref().and_then(|st| st.lesedatum),
+            hoerdatum: st.as_ref().and_then(|st| st.hoerdatum),
+            buch: b,
+        }));
+    }
+    Ok(None)
+}
+
+/// Save a book with its reading status.
+/// * daten: Service data for database access.
+/// * uid: Affected ID, empty for a new book.
+/// * author_uid: Affected author ID.
+/// * series_uid: Affected series ID, empty for "No series".
+/// * serial_nr: Affected serial number within the series.
+/// * title: Affected title.
+/// * subtitle: Affected subtitle.
+/// * pages: Affected number of pages.
+/// * language_nr: Affected language number.
+/// * memo: Affected memo.
+/// * owned: Affected is the book owned.
+/// * read: Affected read date.
+/// * heard: Affected listened date.
+/// * diary: Affected should a newly set read or listened date be noted in the diary.
+/// * returns: Saved book or possibly errors.
+pub fn save_book<'a>(
+    daten: &'a ServiceDaten,
+    uid: &String,
+    author_uid: &String,
+    series_uid: &String,
+    serial_nr: i32,
+    title: &String,
+    subtitle: &String,
+    pages: i32,
+    language_nr: i32,
+    memo: &String,
+    owned: bool,
+    read: &Option<NaiveDate>,
+    heard: &Option<NaiveDate>,
+    diary: bool,
+) -> Result<FzBuch> {
+    let mut r: Vec<String> = vec![];
+    let is_de = daten.config.is_de();
+    let t = title.trim().to_string();
+    if t.len() <= 0 {
+        r.push(M::mec(M::FZ041, is_de).into_owned());
+    }
+    if author_uid.is_empty() {
+        r.push(M::mec(M::FZ042, is_de).into_owned());
+    }
+    if r.len() > 0 {
+        return Err(RsbpError::error(&r));
+    }
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<FzBuch, RsbpError, _>(|| {
+        if reps::fz_buchautor::get(&db, &daten.mandant_nr, author_uid)?.is_none() {
+            return Err(RsbpError::error_msg(M::FZ042, is_de));
+        }
+        let suid = if series_uid.is_empty() {
+            get_or_create_no_series(&mut db)?.uid
+        } else if reps::fz_buchserie::get(&db, &daten.mandant_nr, series_uid)?.is_none() {
+            return Err(RsbpError::error_msg(M::FZ043, is_de));
+        } else {
+            series_uid.clone()
+        };
+        let e = reps::fz_buch::save(
+            &mut db,
+            &daten.mandant_nr,
+            &get_uid(uid),
+            author_uid,
+            &suid,
+            &serial_nr,
+            &t,
+            &functions::to_ostr(subtitle.trim()),
+            &pages,
+            &language_nr,
+            &functions::to_ostr(memo.trim()),
+        )?;
+        let st = reps::fz_buchstatus::get(&db, &daten.mandant_nr, &e.uid)?;
+        save_book_status(&mut db, &e.uid, owned, read, heard)?;
+        if diary {
+            if let Some(d) =
+                read.filter(|d| st.as_ref().map_or(true, |st| st.lesedatum != Some(*d)))
+            {
+                let line = M::mecf(M::FZ053_, &[t.as_str()], is_de);
+                diary_service::append_entry(&mut db, &d, line.as_str())?;
+            }
+            if let Some(d) =
+                heard.filter(|d| st.as_ref().map_or(true, |st| st.hoerdatum != Some(*d)))
+            {
+                let line = M::mecf(M::FZ054_, &[t.as_str()], is_de);
+                diary_service::append_entry(&mut db, &d, line.as_str())?;
+            }
+        }
+        Ok(e)
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Delete a book with its reading status.
+/// * daten: Service data for database access.
+/// * e: Affected Entity.
+/// * returns: Possibly errors.
+pub fn delete_book<'a>(daten: &'a ServiceDaten, e: &FzBuch) -> Result<()> {
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<(), RsbpError, _>(|| {
+        if let Some(st) = reps::fz_buchstatus::get(&db, &daten.mandant_nr, &e.uid)? {
+            reps::fz_buchstatus::delete(&mut db, &st)?;
+        }
+        reps::fz_buch::delete(&mut db, e)?;
+        Ok(())
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Get the books finished within a period with the diary entries of the same days.
+/// * daten: Service data for database access.
+/// * from: Affected from date.
+/// * to: Affected to date.
+/// * returns: Books ordered by read or listened date or possibly errors.
+pub fn get_reading_log<'a>(
+    daten: &'a ServiceDaten,
+    from: &Option<NaiveDate>,
+    to: &Option<NaiveDate>,
+) -> Result<Vec<FzReadingLog>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let mut l: Vec<FzReadingLog> = vec![];
+    for b in get_book_list0(&db, &None, &None, &None)? {
+        for (d, heard) in [(b.lesedatum, false), (b.hoerdatum, true)].iter() {
+            if let Some(d) = d {
+                if from.map_or(false, |f| *d < f) || to.map_or(false, |t| *d > t) {
+                    continue;
+                }
+                let e = reps::tb_eintrag::get(&db, &daten.mandant_nr, d)?;
+                l.push(FzReadingLog {
+                    datum: *d,
+                    buch: b.clone(),
+                    heard: *heard,
+                    eintrag: e.map(|e| e.eintrag),
+                });
+            }
+        }
+    }
+    l.sort_by(|a, b| {
+        a.datum
+            .cmp(&b.datum)
+            .then(a.buch.buch.titel.cmp(&b.buch.buch.titel))
+    });
+    Ok(l)
+}
+
+/// Get the books finished per month within a period.
+/// * daten: Service data for database access.
+/// * from: Affected from date.
+/// * to: Affected to date.
+/// * returns: Months with finished books in ascending order or possibly errors.
+pub fn get_reading_timeline<'a>(
+    daten: &'a ServiceDaten,
+    from: &Option<NaiveDate>,
+    to: &Option<NaiveDate>,
+) -> Result<Vec<FzReadingMonth>> {
+    let mut v: Vec<FzReadingMonth> = vec![];
+    for e in get_reading_log(daten, from, to)? {
+        let (year, month) = (e.datum.year(), e.datum.month());
+        match v.last_mut() {
+            Some(m) if m.year == year && m.month == month => m.books.push(e),
+            _ => v.push(FzReadingMonth {
+                year,
+                month,
+                books: vec![e],
+            }),
+        }
+    }
+    Ok(v)
+}
+
+/// Get the reading statistics of the current user for a date.
+/// * daten: Service data for database access.
+/// * date: Affected date.
+/// * returns: Statistics numbers with formatted lines or possibly errors.
+pub fn get_book_statistics<'a>(
+    daten: &'a ServiceDaten,
+    date: &NaiveDate,
+) -> Result<(FzBookStatistics, Vec<String>)> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let is_de = daten.config.is_de();
+    let b = match reps::benutzer::get(&db, &daten.mandant_nr, &daten.benutzer_id)? {
+        Some(b) => b,
+        None => {
+            return Err(RsbpError::error_string(
+                M::mecf(M::FZ036_, &[daten.benutzer_id.as_str()], is_de).as_str(),
+            ))
+        }
+    };
+    let english = LanguageEnum::to_i32(LanguageEnum::English);
+    let mut st = FzBookStatistics::default();
+    st.person = b.benutzer_id.clone();
+    if let Some(g) = b.geburt {
+        st.days = (*date - g).num_days().max(0);
+    }
+    for e in get_book_list0(&db, &None, &None, &None)? {
+        let pr = e.serie_name.to_lowercase().starts_with("perry rhodan");
+        let read = e.lesedatum.map_or(false, |d| d <= *date);
+        let heard = e.hoerdatum.map_or(false, |d| d <= *date);
+        st.books += 1;
+        if read {
+            st.read += 1;
+            if e.buch.sprache_nr == english {
+                st.english += 1;
+            }
+        }
+        if heard {
+            st.heard += 1;
+        }
+        if pr {
+            st.pr_booklets += 1;
+            if read {
+                st.pr_read += 1;
+                st.pr_pages += e.buch.seiten as i64;
+            }
+        } else if read {
+            st.book_pages += e.buch.seiten as i64;
+        }
+    }
+    st.pages = st.book_pages + st.pr_pages;
+    if st.days > 0 {
+        st.pages_per_day = st.pages as f64 / st.days as f64;
+    }
+    let n = |i: &i64| functions::i64_to_str_0(i, is_de);
+    let pc = |i: &i64| {
+        let p = if st.books > 0 {
+            *i as f64 * 100.0 / st.books as f64
+        } else {
+            0.0
+        };
+        M::mecf(
+            M::FZ008_,
+            &[functions::f64_to_str_4(&p, is_de).as_str()],
+            is_de,
+        )
+    };
+    let v = vec![
+        M::mecf(M::FZ003_, &[st.person.as_str()], is_de),
+        M::mecf(M::FZ004_, &[n(&st.days).as_str()], is_de),
+        M::mecf(M::FZ005_, &[n(&st.books).as_str()], is_de),
+        M::mecf(M::FZ006_, &[n(&st.read).as_str()], is_de) + pc(&st.read).as_str(),
+        M::mecf(M::FZ007_, &[n(&st.heard).as_str()], is_de) + pc(&st.heard).as_str(),
+        M::mecf(M::FZ009_, &[n(&st.english).as_str()], is_de),
+        M::mecf(M::FZ010_, &[n(&st.pr_booklets).as_str()], is_de),
+        M::mecf(M::FZ011_, &[n(&st.pr_read).as_str()], is_de),
+        M::mecf(M::FZ012_, &[n(&st.book_pages).as_str()], is_de),
+        M::mecf(M::FZ013_, &[n(&st.pr_pages).as_str()], is_de),
+        M::mecf(M::FZ014_, &[n(&st.pages).as_str()], is_de),
+        M::mecf(
+            M::FZ015_,
+            &[functions::f64_to_str_4(&st.pages_per_day, is_de).as_str()],
+            is_de,
+        ),
+    ];
+    Ok((st, v))
+}
+
+/// Read a CSV or JSON catalogue file with books and match authors, series and existing books
+/// without saving anything. CSV files need a header line, JSON files an array of objects.
+/// Columns or keys: title, subtitle, author, firstname, series, number, pages, language, owned.
+/// * daten: Service data for database access.
+/// * file: Affected catalogue file name.
+/// * returns: Preview of the books or possibly errors.
+pub fn get_book_import<'a>(daten: &'a ServiceDaten, file: &str) -> Result<Vec<FzBookImport>> {
+    let is_de = daten.config.is_de();
+    let content = match fs::read_to_string(file) {
+        Ok(c) => c,
+        Err(_) => {
+            return Err(RsbpError::error_string(
+                M::mecf(M::M1034_, &[file], is_de).as_str(),
+            ))
+        }
+    };
+    let records = if content.trim_start().starts_with(|c| c == '[' || c == '{') {
+        get_json_records(content.as_str())?
+    } else {
+        get_csv_records(content.as_str(), is_de)?
+    };
+    let mut l = get_book_imports(records, is_de)?;
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let mut im = BookImportMatcher::new(&db)?;
+    for b in l.iter_mut() {
+        im.check(b);
+    }
+    Ok(l)
+}
+
+/// Get the books of catalogue records with author names split into name and first name.
+fn get_book_imports(
+    records: Vec<(usize, HashMap<String, String>)>,
+    is_de: bool,
+) -> Result<Vec<FzBookImport>> {
+    let mut r: Vec<String> = vec![];
+    let mut l: Vec<FzBookImport> = vec![];
+    for (line, m) in records {
+        let g = |k: &str| m.get(k).map_or("", |v| v.trim());
+        let (mut name, mut firstname) = (g("author").to_string(), g("firstname").to_string());
+        if firstname.is_empty() {
+            if let Some(p) = name.find(',') {
+                firstname = name[p + 1..].trim().to_string();
+                name = name[..p].trim().to_string();
+            } else if let Some(p) = name.rfind(' ') {
+                firstname = name[..p].trim().to_string();
+                name = name[p + 1..].to_string();
+            }
+        }
+        let b = FzBookImport {
+            line,
+            titel: g("title").to_string(),
+            untertitel: functions::to_ostr(g("subtitle")),
+            autor_name: name,
+            autor_vorname: functions::to_ostr(firstname.as_str()),
+            serie_name: g("series").to_string(),
+            seriennummer: functions::to_i32(g("number")),
+            seiten: functions::to_i32(g("pages")),
+            sprache_nr: get_language(g("language")),
+            ist_besitz: ["true", "1", "x", "yes", "ja"]
+                .contains(&g("owned").to_lowercase().as_str()),
+            ..Default::default()
+        };
+        if b.titel.is_empty() {
+            r.push(format!("{}: {}", line, M::mec(M::FZ041, is_de)));
+        }
+        if b.autor_name.is_empty() {
+            r.push(format!("{}: {}", line, M::mec(M::FZ042, is_de)));
+        }
+        l.push(b);
+    }
+    if r.len() > 0 {
+        return Err(RsbpError::error(&r));
+    }
+    Ok(l)
+}
+
+/// Save the books of a catalogue import preview without duplicates in one transaction.
+/// Missing authors and series are created.
+/// * daten: Service data for database access.
+/// * list: Affected preview of the books.
+/// * returns: Number of saved books or possibly errors.
+pub fn import_books<'a>(daten: &'a ServiceDaten, list: &Vec<FzBookImport>) -> Result<usize> {
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<usize, RsbpError, _>(|| {
+        let mandant_nr = daten.mandant_nr;
+        let mut im = BookImportMatcher::new(&db)?;
+        let mut n = 0;
+        for b0 in list {
+            let mut b = b0.clone();
+            if im.check(&mut b) {
+                continue;
+            }
+            let auid = match b.autor_uid {
+                Some(uid) => uid,
+                None => {
+                    let a = reps::fz_buchautor::save(
+                        &mut db,
+                        &mandant_nr,
+                        &functions::get_uid(),
+                        &b.autor_name,
+                        &b.autor_vorname,
+                        &None,
+                    )?;
+                    im.authors
+                        .insert(get_import_key(&get_author_name(&a)), a.uid.clone());
+                    a.uid
+                }
+            };
+            let suid = match b.serie_uid {
+                Some(uid) => uid,
+                None if b.serie_name.is_empty() => get_or_create_no_series(&mut db)?.uid,
+                None => {
+                    let s = reps::fz_buchserie::save(
+                        &mut db,
+                        &mandant_nr,
+                        &functions::get_uid(),
+                        &b.serie_name,
+                        &None,
+                    )?;
+                    im.series.insert(get_import_key(&s.name), s.uid.clone());
+                    s.uid
+                }
+            };
+            let e = reps::fz_buch::save(
+                &mut db,
+                &mandant_nr,
+                &functions::get_uid(),
+                &auid,
+                &suid,
+                &b.seriennummer,
+                &b.titel,
+                &b.untertitel,
+                &b.seiten,
+                &b.sprache_nr,
+                &None,
+            )?;
+            save_book_status(&mut db, &e.uid, b.ist_besitz, &None, &None)?;
+            n += 1;
+        }
+        Ok(n)
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Get the completeness and reading order report of book series for storing in a file.
+/// * daten: Service data for database access.
+/// * series_uid: Affected series ID or None for all series.
+/// * csv: Affected format: CSV lines or text lines.
+/// * returns: Vector of lines or possibly errors.
+pub fn get_series_report_file<'a>(
+    daten: &'a ServiceDaten,
+    series_uid: &Option<String>,
+    csv: bool,
+) -> Result<Vec<String>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let is_de = daten.config.is_de();
+    let rl = get_series_report0(&db, series_uid)?;
+    let book = |b: &FzBuchExt| format!("{} {}", b.buch.seriennummer, b.buch.titel);
+    let mut v: Vec<String> = vec![];
+    if csv {
+        v.push(functions::to_csv(&["series", "kind", "number", "title"]));
+        for r in rl.iter() {
+            let name = r.serie.name.as_str();
+            for m in r.missing.iter() {
+                v.push(functions::to_csv(&[
+                    name,
+                    "missing",
+                    m.to_string().as_str(),
+                    "",
+                ]));
+            }
+            for b in r.unread.iter() {
+                let nr = b.buch.seriennummer.to_string();
+                v.push(functions::to_csv(&[
+                    name,
+                    "unread",
+                    nr.as_str(),
+                    b.buch.titel.as_str(),
+                ]));
+            }
+            if let Some(b) = &r.next {
+                let nr = b.buch.seriennummer.to_string();
+                v.push(functions::to_csv(&[
+                    name,
+                    "next",
+                    nr.as_str(),
+                    b.buch.titel.as_str(),
+                ]));
+            }
+        }
+        return Ok(v);
+    }
+    let now = daten.get_now().format("%Y-%m-%d %H:%M:%S").to_string();
+    v.push(M::mecf(M::FZ048_, &[now.as_str()], is_de));
+    for r in rl.iter() {
+        v.push("".into());
+        v.push(M::mecf(
+            M::FZ049_,
+            &[
+                r.serie.name.as_str(),
+                r.books.to_string().as_str(),
+                r.from.to_string().as_str(),
+                r.to.to_string().as_str(),
+            ],
+            is_de,
+        ));
+        if !r.missing.is_empty() {
+            let m = get_ranges(&r.missing);
+            v.push(M::mecf(M::FZ050_, &[m.as_str()], is_de));
+        }
+        if !r.unread.is_empty() {
+            let u = r.unread.iter().map(|b| book(b)).collect::<Vec<String>>();
+            v.push(M::mecf(M::FZ051_, &[u.join(", ").as_str()], is_de));
+        }
+        if let Some(b) = &r.next {
+            v.push(M::mecf(M::FZ052_, &[book(b).as_str()], is_de));
+        }
+    }
+    Ok(v)
+}
+
+/// Get a list of bikes.
+/// * daten: Service data for database access.
+/// * returns: List of bikes or possibly errors.
+pub fn get_bike_list<'a>(daten: &'a ServiceDaten) -> Result<Vec<FzFahrrad>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let l = reps::fz_fahrrad::get_list_ext(&db)?;
+    Ok(l)
+}
+
+/// Get a bike.
+/// * daten: Service data for database access.
+/// * uid: Affected bike ID.
+/// * returns: Bike or possibly errors.
+pub fn get_bike<'a>(daten: &'a ServiceDaten, uid: &String) -> Result<Option<FzFahrrad>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let e = reps::fz_fahrrad::get(&db, &daten.mandant_nr, uid)?;
+    Ok(e)
+}
+
+/// Save a bike.
+/// * daten: Service data for database access.
+/// * uid: Affected ID, empty for a new bike.
+/// * desc: Affected description.
+/// * typ: Affected type, see BikeTypeEnum.
+/// * returns: Saved bike or possibly errors.
+pub fn save_bike<'a>(
+    daten: &'a ServiceDaten,
+    uid: &String,
+    desc: &String,
+    typ: i32,
+) -> Result<FzFahrrad> {
+    let mut r: Vec<String> = vec![];
+    let is_de = daten.config.is_de();
+    let d = desc.trim().to_string();
+    if d.len() <= 0 {
+        r.push(M::mec(M::FZ037, is_de).into_owned());
+    }
+    if typ != BikeTypeEnum::to_i32(BikeTypeEnum::Tour)
+        && typ != BikeTypeEnum::to_i32(BikeTypeEnum::Weekly)
+    {
+        r.push(M::mec(M::FZ038, is_de).into_owned());
+    }
+    if r.len() > 0 {
+        return Err(RsbpError::error(&r));
+    }
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<FzFahrrad, RsbpError, _>(|| {
+        let e = reps::fz_fahrrad::save(&mut db, &daten.mandant_nr, &get_uid(uid), &d, &typ)?;
+        Ok(e)
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Delete a bike with all its mileages.
+/// * daten: Service data for database access.
+/// * e: Affected Entity.
+/// * returns: Possibly errors.
+pub fn delete_bike<'a>(daten: &'a ServiceDaten, e: &FzFahrrad) -> Result<()> {
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<(), RsbpError, _>(|| {
+        for st in reps::fz_fahrradstand::get_list_ext(&db, Some(&e.uid), None, None)? {
+            reps::fz_fahrradstand::delete(&mut db, &st)?;
+        }
+        reps::fz_fahrrad::delete(&mut db, e)?;
+        Ok(())
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Get a list of mileages.
+/// * daten: Service data for database access.
+/// * bike_uid: Affected bike ID.
+/// * from: Affected from date.
+/// * to: Affected to date.
+/// * returns: List of mileages ordered by date or possibly errors.
+pub fn get_mileage_list<'a>(
+    daten: &'a ServiceDaten,
+    bike_uid: &Option<String>,
+    from: &Option<NaiveDate>,
+    to: &Option<NaiveDate>,
+) -> Result<Vec<FzFahrradstand>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let l =
+        reps::fz_fahrradstand::get_list_ext(&db, bike_uid.as_ref(), from.as_ref(), to.as_ref())?;
+    Ok(l)
+}
+
+/// Get a mileage.
+/// * daten: Service data for database access.
+/// * bike_uid: Affected bike ID.
+/// * date: Affected date.
+/// * nr: Affected number within the date.
+/// * returns: Mileage or possibly errors.
+pub fn get_mileage<'a>(
+    daten: &'a ServiceDaten,
+    bike_uid: &String,
+    date: &NaiveDate,
+    nr: i32,
+) -> Result<Option<FzFahrradstand>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let e = reps::fz_fahrradstand::get(&db, &daten.mandant_nr, bike_uid, date, &nr)?;
+    Ok(e)
+}
+
+/// Save a mileage. Either the odometer reading or the km of the period can be given,
+/// the other value is derived from the previous reading. A reading of 0 with 0 km
+/// resets the odometer and is only possible at the end.
+/// * daten: Service data for database access.
+/// * bike_uid: Affected bike ID.
+/// * date: Affected date.
+/// * nr: Affected number within the date, None for a new mileage.
+/// * counter: Affected odometer reading or 0.
+/// * km: Affected km of the period or 0.
+/// * average: Affected average speed or 0 for km per day of weekly bikes.
+/// * desc: Affected description.
+/// * returns: Saved mileage or possibly errors.
+pub fn save_mileage<'a>(
+    daten: &'a ServiceDaten,
+    bike_uid: &String,
+    date: &NaiveDate,
+    nr: Option<i32>,
+    counter: f64,
+    km: f64,
+    average: f64,
+    desc: &String,
+) -> Result<FzFahrradstand> {
+    check_mileage(bike_uid, counter, km, average, daten.config.is_de())?;
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<FzFahrradstand, RsbpError, _>(|| {
+        let e = save_mileage0(
+            &mut db,
+            bike_uid,
+            date,
+            nr,
+            counter,
+            km,
+            average,
+            &functions::to_ostr(desc.trim()),
+        )?;
+        Ok(e)
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Delete a mileage if it is the last one of the bike.
+/// * daten: Service data for database access.
+/// * e: Affected Entity.
+/// * returns: Possibly errors.
+pub fn delete_mileage<'a>(daten: &'a ServiceDaten, e: &FzFahrradstand) -> Result<()> {
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<(), RsbpError, _>(|| delete_mileage0(&mut db, e));
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Import a ride from a CSV track file with timestamp, latitude, longitude and elevation
+/// as a new mileage of a bike. The distance is calculated with the haversine formula and the
+/// average speed in km/h is saved as period average.
+/// * daten: Service data for database access.
+/// * bike_uid: Affected bike ID.
+/// * file: Affected CSV file name.
+/// * radius: Maximum distance in km of start and end to a diary position. 0 means no linking.
+/// * returns: Saved mileage with linked positions or possibly errors.
+pub fn import_track<'a>(
+    daten: &'a ServiceDaten,
+    bike_uid: &String,
+    file: &str,
+    radius: f64,
+) -> Result<(FzFahrradstand, Vec<TbOrt>)> {
+    let is_de = daten.config.is_de();
+    if bike_uid.is_empty() {
+        return Err(RsbpError::error_msg(M::FZ019, is_de));
+    }
+    let path = Path::new(file);
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(_) => {
+            return Err(RsbpError::error_string(
+                M::mecf(M::M1034_, &[file], is_de).as_str(),
+            ))
+        }
+    };
+    let points = get_track_points(content.as_str(), is_de)?;
+    let (first, last) = match (points.first(), points.last()) {
+        (Some(f), Some(l)) if points.len() >= 2 => (f, l),
+        _ => {
+            return Err(RsbpError::error_string(
+                M::mecf(M::FZ046_, &[file], is_de).as_str(),
+            ))
+        }
+    };
+    let km = points
+        .windows(2)
+        .map(|w| functions::get_distance(w[0].1, w[0].2, w[1].1, w[1].2))
+        .sum::<f64>();
+    let km = (km * 100.0).round() / 100.0;
+    let secs = (last.0 - first.0).num_seconds();
+    let speed = if secs > 0 {
+        (km * 3600.0 / secs as f64 * 100.0).round() / 100.0
+    } else {
+        0.0
+    };
+    let date = first.0.date();
+    let desc = path.file_name().map(|n| n.to_string_lossy().to_string());
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<(FzFahrradstand, Vec<TbOrt>), RsbpError, _>(|| {
+        let e = save_mileage0(&mut db, bike_uid, &date, None, 0.0, km, speed, &desc)?;
+        let mut pl: Vec<TbOrt> = vec![];
+        if radius > 0.0 {
+            let ol = reps::tb_ort::get_list(&db, daten.mandant_nr)?;
+            for p in [first, last].iter() {
+                let near = ol
+                    .iter()
+                    .map(|o| (functions::get_distance(p.1, p.2, o.breite, o.laenge), o))
+                    .filter(|(d, _)| *d <= radius)
+                    .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+                if let Some((_, o)) = near {
+                    if pl.iter().any(|a| a.uid == o.uid) {
+                        continue;
+                    }
+                    let l = reps::tb_eintrag_ort::get_list_ext(
+                        &db,
+                        Some(&date),
+                        &0,
+                        None,
+                        Some(&o.uid),
+                    )?;
+                    if l.is_empty() {
+                        diary_service::optimize_positions(
+                            &mut db, &o.uid, &date, &date, &None, &None,
+                        )?;
+                    }
+                    pl.push(o.clone());
+                }
+            }
+        }
+        Ok((e, pl))
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Parse the points of a CSV track with timestamp, latitude, longitude and optional elevation.
+/// A header line and empty lines are skipped. Comma and semicolon are possible separators.
+fn get_track_points(content: &str, is_de: bool) -> Result<Vec<(NaiveDateTime, f64, f64, f64)>> {
+    let mut v = vec![];
+    let sep = match content.lines().next() {
+        Some(l) if l.contains(';') => ';',
+        _ => ',',
+    };
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let err = || RsbpError::error_string(M::mecf(M::M1020_, &[line], is_de).as_str());
+        let cols = functions::from_csv(line, sep).ok_or_else(err)?;
+        let t = match functions::to_ondt(cols[0].as_str()) {
+            Some(t) => t,
+            None if i == 0 => continue,
+            None => return Err(err()),
+        };
+        let mut cs = cols.iter().skip(1).map(|c| c.trim()).collect::<Vec<&str>>();
+        if cs.len() > 2 && cs[2].is_empty() {
+            // Without elevation
+            cs.truncate(2);
+        }
+        let sep2 = if sep == ';' { "; " } else { ", " };
+        match functions::to_coordinates(cs.join(sep2).as_str()) {
+            Some((lat, lon, ele)) if lat.abs() <= 90.0 && lon.abs() <= 180.0 => {
+                v.push((t, lat, lon, ele))
+            }
+            _ => return Err(err()),
+        }
+    }
+    Ok(v)
+}
+
+/// Get the mileage statistics per bike and year up to a date.
+/// * daten: Service data for database access.
+/// * date: Affected date.
+/// * returns: Statistics numbers with formatted lines or possibly errors.
+pub fn get_mileage_statistics<'a>(
+    daten: &'a ServiceDaten,
+    date: &NaiveDate,
+) -> Result<(FzMileageStatistics, Vec<String>)> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let is_de = daten.config.is_de();
+    let st = get_mileage_statistics0(&db, date)?;
+    let n = |f: &f64| functions::i64_to_str_0(&(f.round() as i64), is_de);
+    let y = st.years.iter().position(|y| *y == date.year());
+    let mut v: Vec<String> = vec![];
+    for (i, b) in st.bikes.iter().enumerate() {
+        let kmy = y.map_or(0.0, |y| st.km[i][y]);
+        let km: f64 = st.km[i].iter().sum();
+        v.push(M::mecf(
+            M::FZ016_,
+            &[
+                format!("{}: ", b).as_str(),
+                n(&kmy).as_str(),
+                n(&km).as_str(),
+            ],
+            is_de,
+        ));
+    }
+    let kmy = y.map_or(0.0, |y| st.year_sums[y]);
+    v.push(M::mecf(
+        M::FZ016_,
+        &[
+            format!("{}: ", M::mec(M::FZ018, is_de)).as_str(),
+            n(&kmy).as_str(),
+            n(&st.sum).as_str(),
+        ],
+        is_de,
+    ));
+    v.push(M::mecf(
+        M::FZ017_,
+        &[
+            "",
+            functions::f64_to_str_4(&st.km_per_day, is_de).as_str(),
+            functions::f64_to_str_4(&(st.km_per_day * 365.0), is_de).as_str(),
+        ],
+        is_de,
+    ));
+    v.push("".into());
+    v.push(format!("{}:", M::mec(M::FZ044, is_de)));
+    let mut cum = 0.0;
+    for (i, year) in st.years.iter().enumerate() {
+        cum += st.year_sums[i];
+        v.push(M::mecf(
+            M::FZ016_,
+            &[
+                format!("{}: ", year).as_str(),
+                n(&st.year_sums[i]).as_str(),
+                n(&cum).as_str(),
+            ],
+            is_de,
+        ));
+    }
+    v.push(format!(
+        "{}: {} km",
+        M::mec(M::FZ045, is_de),
+        n(&st.year_average)
+    ));
+    Ok((st, v))
+}
+
+/// Get the yearly mileage matrix with bikes as columns as CSV lines.
+/// * daten: Service data for database access.
+/// * date: Affected date.
+/// * returns: Vector of CSV lines or possibly errors.
+pub fn get_mileage_csv<'a>(daten: &'a ServiceDaten, date: &NaiveDate) -> Result<Vec<String>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let is_de = daten.config.is_de();
+    let st = get_mileage_statistics0(&db, date)?;
+    let sum = M::mec(M::FZ018, is_de).to_string();
+    let mut v: Vec<String> = vec![];
+    let mut h: Vec<&str> = vec![""];
+    h.extend(st.bikes.iter().map(|b| b.as_str()));
+    h.push(sum.as_str());
+    v.push(functions::to_csv(&h));
+    for (i, year) in st.years.iter().enumerate() {
+        let mut l = vec![year.to_string()];
+        l.extend(st.km.iter().map(|k| format!("{:.2}", k[i])));
+        l.push(format!("{:.2}", st.year_sums[i]));
+        v.push(functions::to_csv(
+            &l.iter().map(|s| s.as_str()).collect::<Vec<&str>>(),
+        ));
+    }
+    let mut l = vec![sum.clone()];
+    l.extend(
+        st.km
+            .iter()
+            .map(|k| format!("{:.2}", k.iter().sum::<f64>())),
+    );
+    l.push(format!("{:.2}", st.sum));
+    v.push(functions::to_csv(
+        &l.iter().map(|s| s.as_str()).collect::<Vec<&str>>(),
+    ));
+    Ok(v)
+}
+
+/// Calculate the mileage statistics per bike and year up to a date.
+fn get_mileage_statistics0(db: &DbContext, date: &NaiveDate) -> Result<FzMileageStatistics> {
+    let bl = reps::fz_fahrrad::get_list_ext(db)?;
+    let ml = reps::fz_fahrradstand::get_list_ext(db, None, None, Some(date))?;
+    let mut st = FzMileageStatistics::default();
+    if let (Some(min), Some(max)) = (
+        ml.iter().map(|m| m.datum.year()).min(),
+        ml.iter().map(|m| m.datum.year()).max(),
+    ) {
+        st.years = (min..=max).collect();
+    }
+    st.bikes = bl.iter().map(|b| b.bezeichnung.clone()).collect();
+    st.km = vec![vec![0.0; st.years.len()]; bl.len()];
+    st.year_sums = vec![0.0; st.years.len()];
+    let from = functions::nd_add_dmy(date, 0, 0, -1).unwrap_or(*date);
+    let mut last_year = 0.0;
+    for m in ml.iter() {
+        if let Some(b) = bl.iter().position(|b| b.uid == m.fahrrad_uid) {
+            let y = (m.datum.year() - st.years[0]) as usize;
+            st.km[b][y] += m.periode_km;
+            st.year_sums[y] += m.periode_km;
+            st.sum += m.periode_km;
+            if m.datum > from {
+                last_year += m.periode_km;
+            }
+        }
+    }
+    if st.years.len() > 0 {
+        st.year_average = st.sum / st.years.len() as f64;
+    }
+    st.km_per_day = last_year / (*date - from).num_days().max(1) as f64;
+    Ok(st)
+}
+
+/// Get a list of notes with topic or memo fitting the search patterns.
+/// * daten: Service data for database access.
+/// * search: Affected search strings.
+/// * returns: List of notes or possibly errors.
+pub fn get_note_list<'a>(daten: &'a ServiceDaten, search: &[String; 9]) -> Result<Vec<FzNotiz>> {
+    let s = diary_service::check_search(search);
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let l = reps::fz_notiz::get_list_search(&db, &s)?;
+    Ok(l)
+}
+
+/// Get a note.
+/// * daten: Service data for database access.
+/// * uid: Affected note ID.
+/// * returns: Note or possibly errors.
+pub fn get_note<'a>(daten: &'a ServiceDaten, uid: &String) -> Result<Option<FzNotiz>> {
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let e = reps::fz_notiz::get(&db, &daten.mandant_nr, uid)?;
+    Ok(e)
+}
+
+/// Save a note.
+/// * daten: Service data for database access.
+/// * uid: Affected ID, empty for a new note.
+/// * topic: Affected topic.
+/// * memo: Affected memo.
+/// * returns: Saved note or possibly errors.
+pub fn save_note<'a>(
+    daten: &'a ServiceDaten,
+    uid: &String,
+    topic: &String,
+    memo: &String,
+) -> Result<FzNotiz> {
+    let t = topic.trim().to_string();
+    if t.len() <= 0 {
+        return Err(RsbpError::error_msg(M::FZ035, daten.config.is_de()));
+    }
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<FzNotiz, RsbpError, _>(|| {
+        let e = reps::fz_notiz::save(
+            &mut db,
+            &daten.mandant_nr,
+            &get_uid(uid),
+            &t,
+            &functions::to_ostr(memo.trim_end()),
+        )?;
+        Ok(e)
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Delete a note.
+/// * daten: Service data for database access.
+/// * e: Affected Entity.
+/// * returns: Possibly errors.
+pub fn delete_note<'a>(daten: &'a ServiceDaten, e: &FzNotiz) -> Result<()> {
+    let c = reps::establish_connection(daten);
+    let mut db = DbContext::new(daten, &c);
+    let tr = c.transaction::<(), RsbpError, _>(|| {
+        reps::fz_notiz::delete(&mut db, e)?;
+        Ok(())
+    });
+    if tr.is_ok() {
+        UndoRedoStack::add_undo(&mut db.ul);
+    }
+    tr
+}
+
+/// Get a vector of all fitting notes for storing in a file.
+/// * daten: Service data for database access.
+/// * search: Affected search strings.
+/// * returns: Vector of lines with topics and memos or possibly errors.
+pub fn get_note_file<'a>(daten: &'a ServiceDaten, search: &[String; 9]) -> Result<Vec<String>> {
+    let s = diary_service::check_search(search);
+    let c = reps::establish_connection(daten);
+    let db = DbContext::new(daten, &c);
+    let l = reps::fz_notiz::get_list_search(&db, &s)?;
+    let is_de = daten.config.is_de();
+    let mut v: Vec<String> = vec![];
+    let now = daten.get_now().format("%Y-%m-%d %H:%M:%S").to_string();
+    v.push(M::mecf(M::FZ047_, &[now.as_str()], is_de));
+    v.push(M::tb003(&s, is_de));
+    for e in l {
+        v.push("".into());
+        v.push(e.thema);
+        if let Some(n) = e.notiz {
+            v.push(n);
+        }
+    }
+    Ok(v)
+}
+
+/// Save a mileage and adjust the following mileage within a transaction.
+fn save_mileage0(
+    db: &mut DbContext,
+    bike_uid: &String,
+    date: &NaiveDate,
+    nr: Option<i32>,
+    counter: f64,
+    km: f64,
+    average: f64,
+    desc: &Option<String>,
+) -> Result<FzFahrradstand> {
+    let daten = db.daten;
+    let is_de = daten.config.is_de();
+    let bike = match reps::fz_fahrrad::get(db, &daten.mandant_nr, bike_uid)? {
+        Some(b) => b,
+        None => {
+            return Err(RsbpError::error_string(
+                M::mecf(M::FZ024_, &[bike_uid.as_str()], is_de).as_str(),
+            ))
+        }
+    };
+    let weekly = bike.typ == BikeTypeEnum::to_i32(BikeTypeEnum::Weekly);
+    let l = reps::fz_fahrradstand::get_list_ext(db, Some(bike_uid), None, None)?;
+    let (pos, n, ruid) = match nr {
+        Some(n) => match l.iter().position(|e| e.datum == *date && e.nr == n) {
+            Some(p) => (p, n, l[p].replikation_uid.clone()),
+            None => {
+                return Err(RsbpError::error_string(
+                    M::mecf(M::FZ029_, &[format!("{} {}", date, n).as_str()], is_de).as_str(),
+                ))
+            }
+        },
+        None => {
+            if l.iter().any(|e| e.datum > *date) {
+                return Err(RsbpError::error_msg(M::FZ026, is_de));
+            }
+            let n = l
+                .iter()
+                .filter(|e| e.datum == *date)
+                .map(|e| e.nr + 1)
+                .max()
+                .unwrap_or(0);
+            (l.len(), n, Some(functions::get_uid()))
+        }
+    };
+    let prev = if pos > 0 { l.get(pos - 1) } else { None };
+    let next = if nr.is_some() { l.get(pos + 1) } else { None };
+    let prev_counter = prev.map_or(0.0, |p| p.zaehler_km);
+    let (z, k) = if counter > 0.0 {
+        if counter < prev_counter {
+            return Err(RsbpError::error_string(
+                M::mecf(
+                    M::FZ025_,
+                    &[functions::i64_to_str_0(&(prev_counter.ceil() as i64), is_de).as_str()],
+                    is_de,
+                )
+                .as_str(),
+            ));
+        }
+        (counter, counter - prev_counter)
+    } else if km > 0.0 {
+        (prev_counter + km, km)
+    } else {
+        if next.is_some() {
+            return Err(RsbpError::error_msg(M::FZ027, is_de));
+        }
+        (0.0, 0.0)
+    };
+    let schnitt = get_average(weekly, average, &prev.map(|p| p.datum), date, k);
+    let e = reps::fz_fahrradstand::save0(
+        db,
+        &daten.mandant_nr,
+        bike_uid,
+        date,
+        &n,
+        &z,
+        &k,
+        &schnitt,
+        desc,
+        &None,
+        &None,
+        &None,
+        &None,
+        &ruid,
+    )?;
+    if let Some(nx) = next {
+        if nx.zaehler_km > 0.0 {
+            let nk = nx.zaehler_km - z;
+            if nk < 0.0 {
+                return Err(RsbpError::error_msg(M::FZ030, is_de));
+            }
+            let ns = get_average(weekly, 0.0, &Some(*date), &nx.datum, nk);
+            reps::fz_fahrradstand::save0(
+                db,
+                &nx.mandant_nr,
+                &nx.fahrrad_uid,
+                &nx.datum,
+                &nx.nr,
+                &nx.zaehler_km,
+                &nk,
+                &functions::iif_f64(weekly, ns, nx.periode_schnitt),
+                &nx.beschreibung,
+                &None,
+                &None,
+                &None,
+                &None,
+                &nx.replikation_uid,
+            )?;
+        }
+    }
+    Ok(e)
+}
+
+/// Check the values of a mileage.
+fn check_mileage(
+    bike_uid: &String,
+    counter: f64,
+    km: f64,
+    average: f64,
+    is_de: bool,
+) -> Result<()> {
+    let mut r: Vec<String> = vec![];
+    if bike_uid.is_empty() {
+        r.push(M::mec(M::FZ019, is_de).into_owned());
+    }
+    if counter < 0.0 {
+        r.push(M::mec(M::FZ020, is_de).into_owned());
+    }
+    if km < 0.0 {
+        r.push(M::mec(M::FZ021, is_de).into_owned());
+    }
+    if average < 0.0 {
+        r.push(M::mec(M::FZ022, is_de).into_owned());
+    }
+    if r.len() > 0 {
+        return Err(RsbpError::error(&r));
+    }
+    Ok(())
+}
+
+/// Delete a mileage if it is the last one of the bike.
+fn delete_mileage0(db: &mut DbContext, e: &FzFahrradstand) -> Result<()> {
+    let l = reps::fz_fahrradstand::get_list_ext(db, Some(&e.fahrrad_uid), None, None)?;
+    if let Some(last) = l.last() {
+        if last.datum != e.datum || last.nr != e.nr {
+            return Err(RsbpError::error_msg(M::FZ031, db.daten.config.is_de()));
+        }
+    }
+    reps::fz_fahrradstand::delete(db, e)?;
+    Ok(())
+}
+
+/// Get the average of a mileage: the given average speed or km per day for weekly bikes.
+fn get_average(
+    weekly: bool,
+    average: f64,
+    prev: &Option<NaiveDate>,
+    date: &NaiveDate,
+    km: f64,
+) -> f64 {
+    if average > 0.0 || !weekly {
+        return average;
+    }
+    if let Some(p) = prev {
+        let days = (*date - *p).num_days();
+        if days > 0 {
+            return km / days as f64;
+        }
+    }
+    0.0
+}
+
+/// Get a list of books with author, series and reading status.
+fn get_book_list0(
+    db: &DbContext,
+    author_uid: &Option<String>,
+    series_uid: &Option<String>,
+    title: &Option<String>,
+) -> Result<Vec<FzBuchExt>> {
+    let mandant_nr = db.daten.mandant_nr;
+    let authors: HashMap<String, String> = reps::fz_buchautor::get_list(db, mandant_nr)?
+        .iter()
+        .map(|a| (a.uid.clone(), get_author_name(a)))
+        .collect();
+    let series: HashMap<String, String> = reps::fz_buchserie::get_list(db, mandant_nr)?
+        .into_iter()
+        .map(|s| (s.uid, s.name))
+        .collect();
+    let mut status = HashMap::new();
+    for st in reps::fz_buchstatus::get_list(db, mandant_nr)? {
+        status.insert(st.buch_uid.clone(), st);
+    }
+    let bl =
+        reps::fz_buch::get_list_ext(db, author_uid.as_ref(), series_uid.as_ref(), title.as_ref())?;
+    let mut l: Vec<FzBuchExt> = vec![];
+    for b in bl {
+        let st = status.get(&b.uid);
+        l.push(FzBuchExt {
+            autor_name: authors.get(&b.autor_uid).cloned().unwrap_or_default(),
+            serie_name: series.get(&b.serie_uid).cloned().unwrap_or_default(),
+            ist_besitz: st.map_or(false, |st| st.ist_besitz),
+            lesedatum: st.and_then(|st| st.lesedatum),
+            hoerdatum: st.and_then(|st| st.hoerdatum),
+            buch: b,
+        });
+    }
+    Ok(l)
+}
+
+/// Matcher of imported books with existing authors, series and books.
+struct BookImportMatcher {
+    /// Author IDs by normalized full name.
+    authors: HashMap<String, String>,
+    /// Series IDs by normalized name.
+    series: HashMap<String, String>,
+    /// Normalized full author names and titles of existing or checked books.
+    books: HashSet<(String, String)>,
+}
+
+impl BookImportMatcher {
+    fn new(db: &DbContext) -> Result<BookImportMatcher> {
+        let mandant_nr = db.daten.mandant_nr;
+        let al = reps::fz_buchautor::get_list(db, mandant_nr)?;
+        let authors: HashMap<String, String> = al
+            .iter()
+            .map(|a| (get_import_key(&get_author_name(a)), a.uid.clone()))
+            .collect();
+        let series = reps::fz_buchserie::get_list(db, mandant_nr)?
+            .into_iter()
+            .map(|s| (get_import_key(&s.name), s.uid))
+            .collect();
+        let names: HashMap<&String, String> = al
+            .iter()
+            .map(|a| (&a.uid, get_import_key(&get_author_name(a))))
+            .collect();
+        let books = reps::fz_buch::get_list(db, mandant_nr)?
+            .iter()
+            .filter_map(|b| {
+                names
+                    .get(&b.autor_uid)
+                    .map(|n| (n.clone(), get_import_key(&b.titel)))
+            })
+            .collect();
+        Ok(BookImportMatcher {
+            authors,
+            series,
+            books,
+        })
+    }
+
+    /// Set the author, series and duplicate status of a book.
+    /// * returns: Is the book a duplicate?
+    fn check(&mut self, b: &mut FzBookImport) -> bool {
+        let name = get_import_key(&match &b.autor_vorname {
+            Some(v) => format!("{}, {}", b.autor_name, v),
+            None => b.autor_name.clone(),
+        });
+        b.autor_uid = self.authors.get(&name).cloned();
+        b.serie_uid = self.series.get(&get_import_key(&b.serie_name)).cloned();
+        b.duplicate = !self.books.insert((name, get_import_key(&b.titel)));
+        b.duplicate
+    }
+}
+
+/// Get a normalized key for comparing names and titles.
+fn get_import_key(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Get the language number of a language code or name.
+fn get_language(s: &str) -> i32 {
+    let l = match s.to_lowercase().as_str() {
+        "" | "0" | "de" | "ger" | "deu" | "german" | "deutsch" => LanguageEnum::German,
+        "1" | "en" | "eng" | "english" | "englisch" => LanguageEnum::English,
+        "2" | "fr" | "fre" | "fra" | "french" | "französisch" => LanguageEnum::French,
+        _ => LanguageEnum::Other,
+    };
+    LanguageEnum::to_i32(l)
+}
+
+/// Get the column names of a catalogue with known aliases.
+fn get_import_column(s: &str) -> String {
+    let c = s.trim().to_lowercase();
+    match c.as_str() {
+        "titel" => "title",
+        "untertitel" => "subtitle",
+        "autor" => "author",
+        "vorname" => "firstname",
+        "serie" => "series",
+        "nummer" | "seriennummer" => "number",
+        "seiten" => "pages",
+        "sprache" => "language",
+        "besitz" => "owned",
+        _ => c.as_str(),
+    }
+    .to_string()
+}
+
+/// Get the records of a CSV catalogue with header line.
+fn get_csv_records(content: &str, is_de: bool) -> Result<Vec<(usize, HashMap<String, String>)>> {
+    let mut v = vec![];
+    let mut header: Vec<String> = vec![];
+    let sep = match content.lines().next() {
+        Some(l) if l.contains(';') => ';',
+        _ => ',',
+    };
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let cols = match functions::from_csv(line, sep) {
+            Some(c) => c,
+            None => {
+                return Err(RsbpError::error_string(
+                    M::mecf(M::M1020_, &[line], is_de).as_str(),
+                ))
+            }
+        };
+        if header.is_empty() {
+            header = cols.iter().map(|c| get_import_column(c)).collect();
+            if !header.iter().any(|h| h == "title") || !header.iter().any(|h| h == "author") {
+                return Err(RsbpError::error_string(
+                    M::mecf(M::M1020_, &[line], is_de).as_str(),
+                ));
+            }
+            continue;
+        }
+        let m = header.iter().cloned().zip(cols.into_iter()).collect();
+        v.push((i + 1, m));
+    }
+    Ok(v)
+}
+
+/// Get the records of a JSON catalogue with an array of objects.
+fn get_json_records(content: &str) -> Result<Vec<(usize, HashMap<String, String>)>> {
+    let jo: serde_json::Value = serde_json::from_str(content)
+        .map_err(|err| RsbpError::error_string(err.to_string().as_str()))?;
+    let empty = vec![];
+    let jarr = match jo.as_array() {
+        Some(a) => a,
+        None => jo["books"].as_array().unwrap_or(&empty),
+    };
+    let mut v = vec![];
+    for (i, a) in jarr.iter().enumerate() {
+        let mut m = HashMap::new();
+        if let Some(o) = a.as_object() {
+            for (k, val) in o {
+                let s = match val {
+                    serde_json::Value::String(s) => s.clone(),
+                    serde_json::Value::Null => String::new(),
+                    _ => val.to_string(),
+                };
+                m.insert(get_import_column(k), s);
+            }
+        }
+        v.push((i + 1, m));
+    }
+    Ok(v)
+}
+
+/// Get the completeness and reading order of series with numbered books.
+fn get_series_report0(db: &DbContext, series_uid: &Option<String>) -> Result<Vec<FzSeriesReport>> {
+    let mut v: Vec<FzSeriesReport> = vec![];
+    let all = get_book_list0(db, &None, series_uid, &None)?;
+    for s in reps::fz_buchserie::get_list_ext(db, &None)? {
+        if series_uid.as_ref().map_or(false, |uid| *uid != s.uid) {
+            continue;
+        }
+        let mut bl: Vec<FzBuchExt> = all
+            .iter()
+            .filter(|b| b.buch.serie_uid == s.uid && b.buch.seriennummer > 0)
+            .cloned()
+            .collect();
+        if bl.is_empty() {
+            continue;
+        }
+        bl.sort_by_key(|b| b.buch.seriennummer);
+        let from = bl[0].buch.seriennummer;
+        let to = bl[bl.len() - 1].buch.seriennummer;
+        let missing = (from..=to)
+            .filter(|nr| !bl.iter().any(|b| b.buch.seriennummer == *nr))
+            .collect();
+        let last_read = bl
+            .iter()
+            .filter(|b| b.lesedatum.is_some())
+            .map(|b| b.buch.seriennummer)
+            .max()
+            .unwrap_or(0);
+        let unread: Vec<FzBuchExt> = bl
+            .iter()
+            .filter(|b| b.ist_besitz && b.lesedatum.is_none())
+            .cloned()
+            .collect();
+        let next = unread
+            .iter()
+            .find(|b| b.buch.seriennummer > last_read)
+            .or_else(|| unread.first())
+            .cloned();
+        v.push(FzSeriesReport {
+            serie: s,
+            books: bl.len(),
+            from,
+            to,
+            missing,
+            unread,
+            next,
+        });
+    }
+    Ok(v)
+}
+
+/// Join numbers to ranges, e.g. 1, 3-5.
+fn get_ranges(numbers: &[i32]) -> String {
+    let mut v: Vec<String> = vec![];
+    let mut i = 0;
+    while i < numbers.len() {
+        let mut j = i;
+        while j + 1 < numbers.len() && numbers[j + 1] == numbers[j] + 1 {
+            j += 1;
+        }
+        if j > i {
+            v.push(format!("{}-{}", numbers[i], numbers[j]));
+        } else {
+            v.push(numbers[i].to_string());
+        }
+        i = j + 1;
+    }
+    v.join(", ")
+}
+
+/// Save the reading status of a book.
+fn save_book_status(
+    db: &mut DbContext,
+    uid: &String,
+    owned: bool,
+    read: &Option<NaiveDate>,
+    heard: &Option<NaiveDate>,
+) -> Result<()> {
+    let mandant_nr = db.daten.mandant_nr;
+    let ruid = match reps::fz_buchstatus::get(db, &mandant_nr, uid)? {
+        Some(st) if st.replikation_uid.is_some() => st.replikation_uid,
+        _ => Some(functions::get_uid()),
+    };
+    reps::fz_buchstatus::save0(
+        db,
+        &mandant_nr,
+        uid,
+        &owned,
+        read,
+        heard,
+        &None,
+        &None,
+        &None,
+        &None,
+        &ruid,
+    )?;
+    Ok(())
+}
+
+/// Get the default series "No series" and create it if it is missing.
+/// Must be called within a transaction as it possibly saves the series.
+fn get_or_create_no_series(db: &mut DbContext) -> Result<FzBuchserie> {
+    let names = [M::mec(M::FZ034, true), M::mec(M::FZ034, false)];
+    for s in reps::fz_buchserie::get_list(db, db.daten.mandant_nr)? {
+        if names.iter().any(|n| functions::cmp(n, s.name.as_str())) {
+            return Ok(s);
+        }
+    }
+    let mandant_nr = db.daten.mandant_nr;
+    let name = M::mec(M::FZ034, db.daten.config.is_de()).to_string();
+    let s = reps::fz_buchserie::save(db, &mandant_nr, &functions::get_uid(), &name, &None)?;
+    Ok(s)
+}
+
+/// Get the name of an author with first name.
+fn get_author_name(a: &FzBuchautor) -> String {
+    match &a.vorname {
+        Some(v) if !v.is_empty() => format!("{}, {}", a.name, v),
+        _ => a.name.clone(),
+    }
+}
+
+/// Get the ID or a new ID if empty.
+fn get_uid(uid: &String) -> String {
+    if uid.is_empty() {
+        return functions::get_uid();
+    }
+    uid.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_mileage, delete_mileage0, get_book_imports, get_book_list0, get_csv_records,
+        get_json_records, get_or_create_no_series, get_ranges, get_series_report0,
+        get_track_points, save_book_status, save_mileage0, BookImportMatcher,
+    };
+    use crate::{
+        apis::{enums::BikeTypeEnum, services::ServiceDaten},
+        res::messages::M,
+        services::reps::{self, DbContext},
+    };
+    use chrono::NaiveDate;
+
+    fn book(db: &mut DbContext, uid: &str, author: &str, series: &str, nr: i32, title: &str) {
+        reps::fz_buch::save(
+            db,
+            &0,
+            &uid.to_string(),
+            &author.to_string(),
+            &series.to_string(),
+            &nr,
+            &title.to_string(),
+            &None,
+            &100,
+            &0,
+            &None,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn books() {
+        let daten = ServiceDaten::init();
+        let c = reps::establish_test_connection();
+        let mut db = DbContext::new(&daten, &c);
+        reps::fz_buchautor::save(
+            &mut db,
+            &0,
+            &"a1".into(),
+            &"Pratchett".into(),
+            &Some("Terry".into()),
+            &None,
+        )
+        .unwrap();
+        reps::fz_buchserie::save(&mut db, &0, &"s1".into(), &"Discworld".into(), &None).unwrap();
+        let ns = get_or_create_no_series(&mut db).unwrap();
+        assert_eq!(ns.uid, get_or_create_no_series(&mut db).unwrap().uid);
+        assert_eq!(2, reps::fz_buchserie::get_list(&db, 0).unwrap().len());
+        book(&mut db, "b1", "a1", "s1", 1, "The Colour of Magic");
+        book(&mut db, "b2", "a1", "s1", 2, "The Light Fantastic");
+        book(&mut db, "b4", "a1", "s1", 4, "Mort");
+        book(&mut db, "b5", "a1", "s1", 5, "Sourcery");
+        book(&mut db, "b9", "a1", ns.uid.as_str(), 0, "Good Omens");
+        let read = Some(NaiveDate::from_ymd(2023, 1, 10));
+        save_book_status(&mut db, &"b1".into(), true, &read, &None).unwrap();
+        save_book_status(&mut db, &"b4".into(), true, &None, &None).unwrap();
+        save_book_status(&mut db, &"b5".into(), true, &None, &None).unwrap();
+        let ruid = reps::fz_buchstatus::get(&db, &0, &"b1".into())
+            .unwrap()
+            .unwrap()
+            .replikation_uid;
+        assert!(ruid.is_some());
+        save_book_status(&mut db, &"b1".into(), false, &read, &read).unwrap();
+        let st = reps::fz_buchstatus::get(&db, &0, &"b1".into())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            (ruid, false, read),
+            (st.replikation_uid, st.ist_besitz, st.hoerdatum)
+        );
+
+        let l = get_book_list0(&db, &None, &Some("s1".into()), &None).unwrap();
+        assert_eq!(4, l.len());
+        let b = l.iter().find(|b| b.buch.uid == "b1").unwrap();
+        assert_eq!(
+            ("Pratchett, Terry", "Discworld"),
+            (b.autor_name.as_str(), b.serie_name.as_str())
+        );
+        assert_eq!(read, b.lesedatum);
+    }
+
+    fn err<T>(r: crate::Result<T>) -> String {
+        match r {
+            Ok(_) => String::new(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn mileages() {
+        let daten = ServiceDaten::init();
+        let is_de = daten.config.is_de();
+        let c = reps::establish_test_connection();
+        let mut db = DbContext::new(&daten, &c);
+        let tour = BikeTypeEnum::to_i32(BikeTypeEnum::Tour);
+        let weekly = BikeTypeEnum::to_i32(BikeTypeEnum::Weekly);
+        reps::fz_fahrrad::save(&mut db, &0, &"b1".into(), &"Tour".into(), &tour).unwrap();
+        reps::fz_fahrrad::save(&mut db, &0, &"b2".into(), &"Week".into(), &weekly).unwrap();
+        let d = |day: u32| NaiveDate::from_ymd(2023, 1, day);
+        let msg = |m: M| M::mec(m, is_de).to_string();
+        let list = |db: &DbContext, uid: &str| {
+            reps::fz_fahrradstand::get_list_ext(db, Some(&uid.to_string()), None, None)
+                .unwrap()
+                .iter()
+                .map(|e| (e.nr, e.zaehler_km, e.periode_km, e.periode_schnitt))
+                .collect::<Vec<(i32, f64, f64, f64)>>()
+        };
+        let b1 = "b1".to_string();
+
+        let e = err(check_mileage(&b1, -1.0, -1.0, -1.0, is_de));
+        assert!(e.contains(&msg(M::FZ020)) && e.contains(&msg(M::FZ021)));
+        assert!(e.contains(&msg(M::FZ022)));
+        assert!(check_mileage(&b1, 1.0, 0.0, 0.0, is_de).is_ok());
+        let e = err(save_mileage0(
+            &mut db,
+            &"b9".into(),
+            &d(1),
+            None,
+            1.0,
+            0.0,
+            0.0,
+            &None,
+        ));
+        assert_eq!(M::mecf(M::FZ024_, &["b9"], is_de), e);
+
+        // Tour bike: counter, km and a second tour at the same day
+        save_mileage0(&mut db, &b1, &d(1), None, 100.0, 0.0, 20.0, &None).unwrap();
+        save_mileage0(&mut db, &b1, &d(8), None, 0.0, 50.0, 25.0, &None).unwrap();
+        save_mileage0(&mut db, &b1, &d(8), None, 170.0, 0.0, 0.0, &None).unwrap();
+        assert_eq!(
+            vec![
+                (0, 100.0, 100.0, 20.0),
+                (0, 150.0, 50.0, 25.0),
+                (1, 170.0, 20.0, 0.0)
+            ],
+            list(&db, "b1")
+        );
+        let e = err(save_mileage0(
+            &mut db,
+            &b1,
+            &d(5),
+            None,
+            200.0,
+            0.0,
+            0.0,
+            &None,
+        ));
+        assert_eq!(msg(M::FZ026), e);
+        let e = err(save_mileage0(
+            &mut db,
+            &b1,
+            &d(15),
+            None,
+            160.0,
+            0.0,
+            0.0,
+            &None,
+        ));
+        assert_eq!(M::mecf(M::FZ025_, &["170"], is_de), e);
+        let e = err(save_mileage0(
+            &mut db,
+            &b1,
+            &d(8),
+            Some(5),
+            160.0,
+            0.0,
+            0.0,
+            &None,
+        ));
+        assert_eq!(M::mecf(M::FZ029_, &["2023-01-08 5"], is_de), e);
+
+        // Changing a counter recalculates the km of the following mileage.
+        save_mileage0(&mut db, &b1, &d(1), Some(0), 120.0, 0.0, 20.0, &None).unwrap();
+        assert_eq!((0, 150.0, 30.0, 25.0), list(&db, "b1")[1]);
+        let e = err(save_mileage0(
+            &mut db,
+            &b1,
+            &d(1),
+            Some(0),
+            160.0,
+            0.0,
+            0.0,
+            &None,
+        ));
+        assert_eq!(msg(M::FZ030), e);
+        let e = err(save_mileage0(
+            &mut db,
+            &b1,
+            &d(8),
+            Some(0),
+            0.0,
+            0.0,
+            0.0,
+            &None,
+        ));
+        assert_eq!(msg(M::FZ027), e);
+
+        // Weekly bike: average is km per day.
+        let b2 = "b2".to_string();
+        save_mileage0(&mut db, &b2, &d(1), None, 1000.0, 0.0, 0.0, &None).unwrap();
+        save_mileage0(&mut db, &b2, &d(8), None, 1070.0, 0.0, 0.0, &None).unwrap();
+        assert_eq!((0, 1070.0, 70.0, 10.0), list(&db, "b2")[1]);
+        save_mileage0(&mut db, &b2, &d(1), Some(0), 1014.0, 0.0, 0.0, &None).unwrap();
+        assert_eq!((0, 1070.0, 56.0, 8.0), list(&db, "b2")[1]);
+
+        // Only the last mileage can be deleted.
+        let l = reps::fz_fahrradstand::get_list_ext(&db, Some(&b1), None, None).unwrap();
+        assert_eq!(msg(M::FZ031), err(delete_mileage0(&mut db, &l[0])));
+        delete_mileage0(&mut db, &l[2]).unwrap();
+        assert_eq!(2, list(&db, "b1").len());
+    }
+
+    #[test]
+    fn track_points() {
+        let p = |content: &str| {
+            get_track_points(content, false)
+                .unwrap()
+                .iter()
+                .map(|p| (p.0.format("%H:%M").to_string(), p.1, p.2, p.3))
+                .collect::<Vec<(String, f64, f64, f64)>>()
+        };
+        let v = p("time,lat,lon,ele\n2023-05-01T10:00:00,52.5163,13.3777,34\n2023-05-01 10:05:00,52,13,\n2023-05-01 10:10:00,-52.5,-13.25\n");
+        assert_eq!(
+            vec![
+                ("10:00".to_string(), 52.5163, 13.3777, 34.0),
+                ("10:05".to_string(), 52.0, 13.0, 0.0),
+                ("10:10".to_string(), -52.5, -13.25, 0.0)
+            ],
+            v
+        );
+        let v = p("Zeit;Breite;Länge;Höhe\n2023-05-01 10:00:00;52,5163;13,3777;34,5\n2023-05-01 10:05:00;52.5;13;\n");
+        assert_eq!(
+            vec![
+                ("10:00".to_string(), 52.5163, 13.3777, 34.5),
+                ("10:05".to_string(), 52.5, 13.0, 0.0)
+            ],
+            v
+        );
+        assert!(get_track_points("2023-05-01 10:00:00,95,13,0", false).is_err());
+        assert!(get_track_points("2023-05-01 10:00:00,52.5", false).is_err());
+        assert!(get_track_points("2023-05-01 10:00:00,52.5,x13,0", false).is_err());
+    }
+
+    #[test]
+    fn series_report() {
+        let daten = ServiceDaten::init();
+        let c = reps::establish_test_connection();
+        let mut db = DbContext::new(&daten, &c);
+        reps::fz_buchautor::save(&mut db, &0, &"a1".into(), &"Pratchett".into(), &None, &None)
+            .unwrap();
+        reps::fz_buchserie::save(&mut db, &0, &"s1".into(), &"Discworld".into(), &None).unwrap();
+        let ns = get_or_create_no_series(&mut db).unwrap();
+        book(&mut db, "b1", "a1", "s1", 1, "The Colour of Magic");
+        book(&mut db, "b2", "a1", "s1", 2, "The Light Fantastic");
+        book(&mut db, "b4", "a1", "s1", 4, "Mort");
+        book(&mut db, "b5", "a1", "s1", 5, "Sourcery");
+        book(&mut db, "b9", "a1", ns.uid.as_str(), 0, "Good Omens");
+        let read = Some(NaiveDate::from_ymd(2023, 1, 10));
+        save_book_status(&mut db, &"b1".into(), false, &read, &read).unwrap();
+        save_book_status(&mut db, &"b4".into(), true, &None, &None).unwrap();
+        save_book_status(&mut db, &"b5".into(), true, &None, &None).unwrap();
+
+        let r = get_series_report0(&db, &None).unwrap();
+        assert_eq!(1, r.len());
+        assert_eq!((4, 1, 5), (r[0].books, r[0].from, r[0].to));
+        assert_eq!(vec![3], r[0].missing);
+        assert_eq!(2, r[0].unread.len());
+        assert_eq!(
+            Some("b4".to_string()),
+            r[0].next.as_ref().map(|b| b.buch.uid.clone())
+        );
+    }
+
+    #[test]
+    fn ranges() {
+        assert_eq!("", get_ranges(&[]));
+        assert_eq!("7", get_ranges(&[7]));
+        assert_eq!("1-3", get_ranges(&[1, 2, 3]));
+        assert_eq!("1, 3-5, 8, 10-11", get_ranges(&[1, 3, 4, 5, 8, 10, 11]));
+    }
+
+    #[test]
+    fn book_import() {
+        let daten = ServiceDaten::init();
+        let c = reps::establish_test_connection();
+        let mut db = DbContext::new(&daten, &c);
+        reps::fz_buchautor::save(
+            &mut db,
+            &0,
+            &"a1".into(),
+            &"Pratchett".into(),
+            &Some("Terry".into()),
+            &None,
+        )
+        .unwrap();
+        reps::fz_buchserie::save(&mut db, &0, &"s1".into(), &"Discworld".into(), &None).unwrap();
+        book(&mut db, "b1", "a1", "s1", 1, "The Colour of Magic");
+        let csv = "Titel;Autor;Vorname;Serie;Nummer;ISBN;Besitz
+The  colour of MAGIC;pratchett;terry;discworld;1;978-0-552-12475-3;x
+The Light Fantastic;Pratchett, Terry;;Discworld;2;;
+The Light Fantastic;Terry Pratchett;;Discworld;2;;
+Mort;Terry  Pratchett;;Scheibenwelt;4;;ja
+Good Omens;Gaiman;Neil;;0;;
+";
+        let records = get_csv_records(csv, true).unwrap();
+        let mut l = get_book_imports(records, true).unwrap();
+        let mut im = BookImportMatcher::new(&db).unwrap();
+        let r = l
+            .iter_mut()
+            .map(|b| {
+                let d = im.check(b);
+                (d, b.autor_uid.clone(), b.serie_uid.clone())
+            })
+            .collect::<Vec<(bool, Option<String>, Option<String>)>>();
+        let a1 = Some("a1".to_string());
+        let s1 = Some("s1".to_string());
+        assert_eq!(
+            vec![
+                (true, a1.clone(), s1.clone()),
+                (false, a1.clone(), s1.clone()),
+                (true, a1.clone(), s1.clone()),
+                (false, a1.clone(), None),
+                (false, None, None),
+            ],
+            r
+        );
+        assert_eq!((2, 4, 0), (l[0].line, l[3].seriennummer, l[0].sprache_nr));
+        assert_eq!((true, false), (l[3].ist_besitz, l[1].ist_besitz));
+        assert_eq!(
+            ("Gaiman", Some("Neil".to_string())),
+            (l[4].autor_name.as_str(), l[4].autor_vorname.clone())
+        );
+
+        let json = r#"{"books": [{"title": "Mort", "author": "Pratchett, Terry", "number": 4, "language": "en"}, {"author": "X"}]}"#;
+        let records = get_json_records(json).unwrap();
+        assert_eq!(2, records.len());
+        let e = get_book_imports(records, false).unwrap_err().to_string();
+        assert!(e.starts_with("2: "));
+        let records = get_json_records(&json.replace(r#", {"author": "X"}"#, "")).unwrap();
+        let l = get_book_imports(records, false).unwrap();
+        assert_eq!(
+            ("Pratchett", 4, 1),
+            (l[0].autor_name.as_str(), l[0].seriennummer, l[0].sprache_nr)
+        );
+    }
+}
@@ -1,10 +1,25 @@
+pub mod ad_adresse;
 pub mod ad_person;
+pub mod ad_sitz;
 pub mod benutzer;
+pub mod fz_buch;
+pub mod fz_buchautor;
+pub mod fz_buchserie;
+pub mod fz_buchstatus;
+pub mod fz_fahrrad;
+pub mod fz_fahrradstand;
+pub mod fz_notiz;
 pub mod ma_mandant;
 pub mod ma_parameter;
+pub mod sb_ereignis;
+pub mod sb_familie;
+pub mod sb_kind;
+pub mod sb_person;
+pub mod sb_quelle;
 pub mod tb_eintrag;
 pub mod tb_eintrag_ort;
 pub mod tb_ort;
+pub mod tb_ort_adresse;
 
 use super::undo::UndoList;
 use crate::{apis::services::ServiceDaten, res};
@@ -80,6 +95,16 @@ impl<'a> DbContext<'a> {
     }
 }
 
+/// Liefert eine Datenbank im Speicher mit allen Tabellen aus tables.xml für Tests.
+#[cfg(test)]
+pub fn establish_test_connection() -> SqliteConnection {
+    let c = SqliteConnection::establish(":memory:").unwrap();
+    for sql in tests::get_create_tables() {
+        diesel::sql_query(sql).execute(&c).unwrap();
+    }
+    c
+}
+
 #[cfg(test)]
 mod tests {
     use crate::base::functions;
@@ -127,6 +152,36 @@ mod tests {
         }
     }
 
+    /// Liefert die CREATE TABLE-Anweisungen aller Tabellen.
+    pub(super) fn get_create_tables() -> Vec<String> {
+        let mut tables: Vec<Table> = Vec::new();
+        read_tables(&mut tables);
+        let mut v: Vec<String> = Vec::new();
+        for t in tables.iter() {
+            let mut cols: Vec<String> = Vec::new();
+            let mut pk: Vec<String> = Vec::new();
+            for c in t.columns.iter() {
+                let type_ = functions::iif(c.type_ == "VARCHAR", "TEXT", c.type_.as_str());
+                cols.push(format!(
+                    "{} {}{}",
+                    c.name.to_lowercase(),
+                    type_,
+                    functions::iif(c.nullable, "", " NOT NULL")
+                ));
+                if c.primary_key {
+                    pk.push(c.name.to_lowercase());
+                }
+            }
+            cols.push(format!("PRIMARY KEY ({})", pk.join(", ")));
+            v.push(format!(
+                "CREATE TABLE {} ({})",
+                t.name.to_uppercase(),
+                cols.join(", ")
+            ));
+        }
+        v
+    }
+
     /// Repositories zusammenstellen.
     fn create_reps(tables: &Vec<&Table>) -> String {
         let mut sb = String::new();
@@ -0,0 +1,229 @@
+use super::DbContext;
+use crate::{config::RsbpError, services::undo::UndoEntry, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use rsbp_rep::{models::AdAdresse, schema::*};
+
+/// Undo a dataset.
+pub fn undo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<AdAdresse>(or)?;
+    let oa = UndoEntry::from_str::<AdAdresse>(ac)?;
+    if let (Some(o), Some(_a)) = (&oo, &oa) {
+        // Update
+        update(db, o)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        delete(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        insert(db, o)?;
+    }
+    Ok(())
+}
+
+/// Redo a dataset.
+pub fn redo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<AdAdresse>(or)?;
+    let oa = UndoEntry::from_str::<AdAdresse>(ac)?;
+    if let (Some(_o), Some(a)) = (&oo, &oa) {
+        // Update
+        update(db, a)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        insert(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        delete(db, o)?;
+    }
+    Ok(())
+}
+
+/// Save dataset with all values.
+#[allow(dead_code)]
+pub fn save0(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    staat_: &Option<String>,
+    plz_: &Option<String>,
+    ort_: &String,
+    strasse_: &Option<String>,
+    hausnr_: &Option<String>,
+    angelegt_von_: &Option<String>,
+    angelegt_am_: &Option<NaiveDateTime>,
+    geaendert_von_: &Option<String>,
+    geaendert_am_: &Option<NaiveDateTime>,
+) -> Result<AdAdresse> {
+    let op = AD_ADRESSE::table
+        .filter(
+            AD_ADRESSE::mandant_nr
+                .eq(mandant_nr_)
+                .and(AD_ADRESSE::uid.eq(uid_.clone())),
+        )
+        .first::<AdAdresse>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    let mut p = AdAdresse {
+        mandant_nr: *mandant_nr_,
+        uid: uid_.clone(),
+        staat: staat_.clone(),
+        plz: plz_.clone(),
+        ort: ort_.clone(),
+        strasse: strasse_.clone(),
+        hausnr: hausnr_.clone(),
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+    };
+    if let Some(pu) = op {
+        if p != pu {
+            p.angelegt_von = pu.angelegt_von;
+            p.angelegt_am = pu.angelegt_am;
+            p.geaendert_von = pu.geaendert_von;
+            p.geaendert_am = pu.geaendert_am;
+            if p.angelegt_von.is_none() || !angelegt_von_.is_none() {
+                super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+            }
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+            update(db, &p)?;
+        }
+    } else {
+        super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+        if !geaendert_von_.is_none() {
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+        }
+        insert(db, &p)?;
+    }
+    return Ok(p);
+}
+
+/// Save dataset without revision columns.
+#[allow(dead_code)]
+pub fn save(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    staat_: &Option<String>,
+    plz_: &Option<String>,
+    ort_: &String,
+    strasse_: &Option<String>,
+    hausnr_: &Option<String>,
+) -> Result<AdAdresse> {
+    save0(
+        db,
+        mandant_nr_,
+        uid_,
+        staat_,
+        plz_,
+        ort_,
+        strasse_,
+        hausnr_,
+        &None,
+        &None,
+        &None,
+        &None,
+    )
+}
+
+/// Get dataset by primary key.
+#[allow(dead_code)]
+pub fn get(db: &DbContext, mandant_nr_: &i32, uid_: &String) -> Result<Option<AdAdresse>> {
+    let p = AD_ADRESSE::table
+        .filter(
+            AD_ADRESSE::mandant_nr
+                .eq(mandant_nr_)
+                .and(AD_ADRESSE::uid.eq(uid_.clone())),
+        )
+        .first::<AdAdresse>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get dataset by primary key.
+pub fn get2(db: &DbContext, b: &AdAdresse) -> Result<Option<AdAdresse>> {
+    let p = AD_ADRESSE::table
+        .filter(
+            AD_ADRESSE::mandant_nr
+                .eq(b.mandant_nr)
+                .and(AD_ADRESSE::uid.eq(b.uid.clone())),
+        )
+        .first::<AdAdresse>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get list.
+#[allow(dead_code)]
+pub fn get_list(db: &DbContext, mandant_nr_: i32) -> Result<Vec<AdAdresse>> {
+    let list = AD_ADRESSE::table
+        .filter(AD_ADRESSE::mandant_nr.eq(mandant_nr_))
+        .load::<AdAdresse>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
+
+/// Insert a dataset.
+pub fn insert<'a>(db: &mut DbContext, b: &'a AdAdresse) -> Result<&'a AdAdresse> {
+    let rows = diesel::insert_into(AD_ADRESSE::table)
+        .values(b)
+        .execute(db.c)?;
+    if rows <= 0 {
+        return Err(RsbpError::NotFound);
+    }
+    db.ul.add(&UndoEntry::ad_adresse(None, Some(b)));
+    Ok(b)
+}
+
+/// Update a dataset.
+pub fn update<'a>(db: &mut DbContext, b: &'a AdAdresse) -> Result<&'a AdAdresse> {
+    let oo = get2(&db, b)?;
+    let rows = diesel::update(
+        AD_ADRESSE::table.filter(
+            AD_ADRESSE::mandant_nr
+                .eq(b.mandant_nr)
+                .and(AD_ADRESSE::uid.eq(b.uid.clone())),
+        ),
+    )
+    .set((
+        AD_ADRESSE::staat.eq(b.staat.as_ref()),
+        AD_ADRESSE::plz.eq(b.plz.as_ref()),
+        AD_ADRESSE::ort.eq(b.ort.as_str()),
+        AD_ADRESSE::strasse.eq(b.strasse.as_ref()),
+        AD_ADRESSE::hausnr.eq(b.hausnr.as_ref()),
+        AD_ADRESSE::angelegt_von.eq(b.angelegt_von.as_ref()),
+        AD_ADRESSE::angelegt_am.eq(b.angelegt_am),
+        AD_ADRESSE::geaendert_von.eq(b.geaendert_von.as_ref()),
+        AD_ADRESSE::geaendert_am.eq(b.geaendert_am),
+    ))
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::ad_adresse(Some(&o), Some(b)));
+    }
+    Ok(b)
+}
+
+/// Delete a dataset.
+pub fn delete(db: &mut DbContext, b: &AdAdresse) -> Result<()> {
+    let oo = get2(db, b)?;
+    let rows = diesel::delete(
+        AD_ADRESSE::table.filter(
+            AD_ADRESSE::mandant_nr
+                .eq(b.mandant_nr)
+                .and(AD_ADRESSE::uid.eq(b.uid.clone())),
+        ),
+    )
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::ad_adresse(Some(&o), None));
+    }
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use super::DbContext;
 use crate::{config::RsbpError, services::undo::UndoEntry, Result};
-use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use chrono::{NaiveDate, NaiveDateTime};
 use diesel::prelude::*;
 use rsbp_rep::{models::AdPerson, schema::*};
 
@@ -263,29 +263,23 @@ pub fn delete(db: &mut DbContext, b: &AdPerson) -> Result<()> {
     Ok(())
 }
 
-/// Get birthday list.
-pub fn get_list_ext(db: &DbContext, from: &NaiveDate, to: &NaiveDate) -> Result<Vec<AdPerson>> {
-    let f = (from.month() * 100 + from.day()) as i32;
-    let t = (to.month() * 100 + to.day()) as i32;
-    let mut q = AD_PERSON::table.into_boxed().filter(
-        AD_PERSON::mandant_nr
-            .eq(db.daten.mandant_nr)
-            .and(AD_PERSON::person_status.eq(0))
-            .and(AD_PERSON::geburtk.ne(0)),
-    );
-    if from.year() == to.year() {
-        q = q.filter(AD_PERSON::geburtk.ge(f).and(AD_PERSON::geburtk.le(t)));
-    } else {
-        q = q.filter(AD_PERSON::geburtk.lt(t).or(AD_PERSON::geburtk.ge(f)));
+/// Get list filtered by name or first name ordered by name.
+pub fn get_list_ext2(
+    db: &DbContext,
+    name_: Option<&String>,
+    actual: bool,
+) -> Result<Vec<AdPerson>> {
+    let mut q = AD_PERSON::table
+        .into_boxed()
+        .filter(AD_PERSON::mandant_nr.eq(db.daten.mandant_nr));
+    if let Some(n) = name_ {
+        q = q.filter(AD_PERSON::name1.like(n).or(AD_PERSON::vorname.like(n)));
+    }
+    if actual {
+        q = q.filter(AD_PERSON::person_status.eq(0));
     }
     let list = q
-        .order((
-            AD_PERSON::geburtk,
-            AD_PERSON::name1,
-            AD_PERSON::vorname,
-            AD_PERSON::uid,
-        ))
-        .limit(-1)
+        .order((AD_PERSON::name1, AD_PERSON::vorname, AD_PERSON::uid))
         .load::<AdPerson>(db.c)
         .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
     Ok(list)
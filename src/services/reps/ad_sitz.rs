@@ -0,0 +1,305 @@
+use super::DbContext;
+use crate::{config::RsbpError, services::undo::UndoEntry, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use rsbp_rep::{models::AdSitz, schema::*};
+
+/// Undo a dataset.
+pub fn undo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<AdSitz>(or)?;
+    let oa = UndoEntry::from_str::<AdSitz>(ac)?;
+    if let (Some(o), Some(_a)) = (&oo, &oa) {
+        // Update
+        update(db, o)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        delete(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        insert(db, o)?;
+    }
+    Ok(())
+}
+
+/// Redo a dataset.
+pub fn redo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<AdSitz>(or)?;
+    let oa = UndoEntry::from_str::<AdSitz>(ac)?;
+    if let (Some(_o), Some(a)) = (&oo, &oa) {
+        // Update
+        update(db, a)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        insert(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        delete(db, o)?;
+    }
+    Ok(())
+}
+
+/// Save dataset with all values.
+#[allow(dead_code)]
+pub fn save0(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    person_uid_: &String,
+    reihenfolge_: &i32,
+    uid_: &String,
+    typ_: &i32,
+    name_: &String,
+    adresse_uid_: &Option<String>,
+    telefon_: &Option<String>,
+    fax_: &Option<String>,
+    mobil_: &Option<String>,
+    email_: &Option<String>,
+    homepage_: &Option<String>,
+    postfach_: &Option<String>,
+    bemerkung_: &Option<String>,
+    sitz_status_: &i32,
+    angelegt_von_: &Option<String>,
+    angelegt_am_: &Option<NaiveDateTime>,
+    geaendert_von_: &Option<String>,
+    geaendert_am_: &Option<NaiveDateTime>,
+) -> Result<AdSitz> {
+    let op = AD_SITZ::table
+        .filter(
+            AD_SITZ::mandant_nr
+                .eq(mandant_nr_)
+                .and(AD_SITZ::person_uid.eq(person_uid_.clone()))
+                .and(AD_SITZ::reihenfolge.eq(reihenfolge_))
+                .and(AD_SITZ::uid.eq(uid_.clone())),
+        )
+        .first::<AdSitz>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    let mut p = AdSitz {
+        mandant_nr: *mandant_nr_,
+        person_uid: person_uid_.clone(),
+        reihenfolge: *reihenfolge_,
+        uid: uid_.clone(),
+        typ: *typ_,
+        name: name_.clone(),
+        adresse_uid: adresse_uid_.clone(),
+        telefon: telefon_.clone(),
+        fax: fax_.clone(),
+        mobil: mobil_.clone(),
+        email: email_.clone(),
+        homepage: homepage_.clone(),
+        postfach: postfach_.clone(),
+        bemerkung: bemerkung_.clone(),
+        sitz_status: *sitz_status_,
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+    };
+    if let Some(pu) = op {
+        if p != pu {
+            p.angelegt_von = pu.angelegt_von;
+            p.angelegt_am = pu.angelegt_am;
+            p.geaendert_von = pu.geaendert_von;
+            p.geaendert_am = pu.geaendert_am;
+            if p.angelegt_von.is_none() || !angelegt_von_.is_none() {
+                super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+            }
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+            update(db, &p)?;
+        }
+    } else {
+        super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+        if !geaendert_von_.is_none() {
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+        }
+        insert(db, &p)?;
+    }
+    return Ok(p);
+}
+
+/// Save dataset without revision columns.
+#[allow(dead_code)]
+pub fn save(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    person_uid_: &String,
+    reihenfolge_: &i32,
+    uid_: &String,
+    typ_: &i32,
+    name_: &String,
+    adresse_uid_: &Option<String>,
+    telefon_: &Option<String>,
+    fax_: &Option<String>,
+    mobil_: &Option<String>,
+    email_: &Option<String>,
+    homepage_: &Option<String>,
+    postfach_: &Option<String>,
+    bemerkung_: &Option<String>,
+    sitz_status_: &i32,
+) -> Result<AdSitz> {
+    save0(
+        db,
+        mandant_nr_,
+        person_uid_,
+        reihenfolge_,
+        uid_,
+        typ_,
+        name_,
+        adresse_uid_,
+        telefon_,
+        fax_,
+        mobil_,
+        email_,
+        homepage_,
+        postfach_,
+        bemerkung_,
+        sitz_status_,
+        &None,
+        &None,
+        &None,
+        &None,
+    )
+}
+
+/// Get dataset by primary key.
+#[allow(dead_code)]
+pub fn get(
+    db: &DbContext,
+    mandant_nr_: &i32,
+    person_uid_: &String,
+    reihenfolge_: &i32,
+    uid_: &String,
+) -> Result<Option<AdSitz>> {
+    let p = AD_SITZ::table
+        .filter(
+            AD_SITZ::mandant_nr
+                .eq(mandant_nr_)
+                .and(AD_SITZ::person_uid.eq(person_uid_.clone()))
+                .and(AD_SITZ::reihenfolge.eq(reihenfolge_))
+                .and(AD_SITZ::uid.eq(uid_.clone())),
+        )
+        .first::<AdSitz>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get dataset by primary key.
+pub fn get2(db: &DbContext, b: &AdSitz) -> Result<Option<AdSitz>> {
+    let p = AD_SITZ::table
+        .filter(
+            AD_SITZ::mandant_nr
+                .eq(b.mandant_nr)
+                .and(AD_SITZ::person_uid.eq(b.person_uid.clone()))
+                .and(AD_SITZ::reihenfolge.eq(b.reihenfolge))
+                .and(AD_SITZ::uid.eq(b.uid.clone())),
+        )
+        .first::<AdSitz>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get list.
+#[allow(dead_code)]
+pub fn get_list(db: &DbContext, mandant_nr_: i32) -> Result<Vec<AdSitz>> {
+    let list = AD_SITZ::table
+        .filter(AD_SITZ::mandant_nr.eq(mandant_nr_))
+        .load::<AdSitz>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
+
+/// Insert a dataset.
+pub fn insert<'a>(db: &mut DbContext, b: &'a AdSitz) -> Result<&'a AdSitz> {
+    let rows = diesel::insert_into(AD_SITZ::table)
+        .values(b)
+        .execute(db.c)?;
+    if rows <= 0 {
+        return Err(RsbpError::NotFound);
+    }
+    db.ul.add(&UndoEntry::ad_sitz(None, Some(b)));
+    Ok(b)
+}
+
+/// Update a dataset.
+pub fn update<'a>(db: &mut DbContext, b: &'a AdSitz) -> Result<&'a AdSitz> {
+    let oo = get2(&db, b)?;
+    let rows = diesel::update(
+        AD_SITZ::table.filter(
+            AD_SITZ::mandant_nr
+                .eq(b.mandant_nr)
+                .and(AD_SITZ::person_uid.eq(b.person_uid.clone()))
+                .and(AD_SITZ::reihenfolge.eq(b.reihenfolge))
+                .and(AD_SITZ::uid.eq(b.uid.clone())),
+        ),
+    )
+    .set((
+        AD_SITZ::typ.eq(b.typ),
+        AD_SITZ::name.eq(b.name.as_str()),
+        AD_SITZ::adresse_uid.eq(b.adresse_uid.as_ref()),
+        AD_SITZ::telefon.eq(b.telefon.as_ref()),
+        AD_SITZ::fax.eq(b.fax.as_ref()),
+        AD_SITZ::mobil.eq(b.mobil.as_ref()),
+        AD_SITZ::email.eq(b.email.as_ref()),
+        AD_SITZ::homepage.eq(b.homepage.as_ref()),
+        AD_SITZ::postfach.eq(b.postfach.as_ref()),
+        AD_SITZ::bemerkung.eq(b.bemerkung.as_ref()),
+        AD_SITZ::sitz_status.eq(b.sitz_status),
+        AD_SITZ::angelegt_von.eq(b.angelegt_von.as_ref()),
+        AD_SITZ::angelegt_am.eq(b.angelegt_am),
+        AD_SITZ::geaendert_von.eq(b.geaendert_von.as_ref()),
+        AD_SITZ::geaendert_am.eq(b.geaendert_am),
+    ))
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::ad_sitz(Some(&o), Some(b)));
+    }
+    Ok(b)
+}
+
+/// Delete a dataset.
+pub fn delete(db: &mut DbContext, b: &AdSitz) -> Result<()> {
+    let oo = get2(db, b)?;
+    let rows = diesel::delete(
+        AD_SITZ::table.filter(
+            AD_SITZ::mandant_nr
+                .eq(b.mandant_nr)
+                .and(AD_SITZ::person_uid.eq(b.person_uid.clone()))
+                .and(AD_SITZ::reihenfolge.eq(b.reihenfolge))
+                .and(AD_SITZ::uid.eq(b.uid.clone())),
+        ),
+    )
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::ad_sitz(Some(&o), None));
+    }
+    Ok(())
+}
+
+/// Get list of a person or an address ordered by person and order.
+pub fn get_list_ext(
+    db: &DbContext,
+    person_uid_: Option<&String>,
+    adresse_uid_: Option<&String>,
+) -> Result<Vec<AdSitz>> {
+    let mut q = AD_SITZ::table
+        .into_boxed()
+        .filter(AD_SITZ::mandant_nr.eq(db.daten.mandant_nr));
+    if let Some(p) = person_uid_ {
+        q = q.filter(AD_SITZ::person_uid.eq(p));
+    }
+    if let Some(a) = adresse_uid_ {
+        q = q.filter(AD_SITZ::adresse_uid.eq(a));
+    }
+    let list = q
+        .order((AD_SITZ::person_uid, AD_SITZ::reihenfolge, AD_SITZ::uid))
+        .load::<AdSitz>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
@@ -0,0 +1,270 @@
+use super::DbContext;
+use crate::{config::RsbpError, services::undo::UndoEntry, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use rsbp_rep::{models::FzBuch, schema::*};
+
+/// Undo a dataset.
+pub fn undo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<FzBuch>(or)?;
+    let oa = UndoEntry::from_str::<FzBuch>(ac)?;
+    if let (Some(o), Some(_a)) = (&oo, &oa) {
+        // Update
+        update(db, o)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        delete(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        insert(db, o)?;
+    }
+    Ok(())
+}
+
+/// Redo a dataset.
+pub fn redo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<FzBuch>(or)?;
+    let oa = UndoEntry::from_str::<FzBuch>(ac)?;
+    if let (Some(_o), Some(a)) = (&oo, &oa) {
+        // Update
+        update(db, a)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        insert(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        delete(db, o)?;
+    }
+    Ok(())
+}
+
+/// Save dataset with all values.
+#[allow(dead_code)]
+pub fn save0(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    autor_uid_: &String,
+    serie_uid_: &String,
+    seriennummer_: &i32,
+    titel_: &String,
+    untertitel_: &Option<String>,
+    seiten_: &i32,
+    sprache_nr_: &i32,
+    notiz_: &Option<String>,
+    angelegt_von_: &Option<String>,
+    angelegt_am_: &Option<NaiveDateTime>,
+    geaendert_von_: &Option<String>,
+    geaendert_am_: &Option<NaiveDateTime>,
+) -> Result<FzBuch> {
+    let op = FZ_BUCH::table
+        .filter(
+            FZ_BUCH::mandant_nr
+                .eq(mandant_nr_)
+                .and(FZ_BUCH::uid.eq(uid_.clone())),
+        )
+        .first::<FzBuch>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    let mut p = FzBuch {
+        mandant_nr: *mandant_nr_,
+        uid: uid_.clone(),
+        autor_uid: autor_uid_.clone(),
+        serie_uid: serie_uid_.clone(),
+        seriennummer: *seriennummer_,
+        titel: titel_.clone(),
+        untertitel: untertitel_.clone(),
+        seiten: *seiten_,
+        sprache_nr: *sprache_nr_,
+        notiz: notiz_.clone(),
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+    };
+    if let Some(pu) = op {
+        if p != pu {
+            p.angelegt_von = pu.angelegt_von;
+            p.angelegt_am = pu.angelegt_am;
+            p.geaendert_von = pu.geaendert_von;
+            p.geaendert_am = pu.geaendert_am;
+            if p.angelegt_von.is_none() || !angelegt_von_.is_none() {
+                super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+            }
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+            update(db, &p)?;
+        }
+    } else {
+        super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+        if !geaendert_von_.is_none() {
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+        }
+        insert(db, &p)?;
+    }
+    return Ok(p);
+}
+
+/// Save dataset without revision columns.
+#[allow(dead_code)]
+pub fn save(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    autor_uid_: &String,
+    serie_uid_: &String,
+    seriennummer_: &i32,
+    titel_: &String,
+    untertitel_: &Option<String>,
+    seiten_: &i32,
+    sprache_nr_: &i32,
+    notiz_: &Option<String>,
+) -> Result<FzBuch> {
+    save0(
+        db,
+        mandant_nr_,
+        uid_,
+        autor_uid_,
+        serie_uid_,
+        seriennummer_,
+        titel_,
+        untertitel_,
+        seiten_,
+        sprache_nr_,
+        notiz_,
+        &None,
+        &None,
+        &None,
+        &None,
+    )
+}
+
+/// Get dataset by primary key.
+#[allow(dead_code)]
+pub fn get(db: &DbContext, mandant_nr_: &i32, uid_: &String) -> Result<Option<FzBuch>> {
+    let p = FZ_BUCH::table
+        .filter(
+            FZ_BUCH::mandant_nr
+                .eq(mandant_nr_)
+                .and(FZ_BUCH::uid.eq(uid_.clone())),
+        )
+        .first::<FzBuch>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get dataset by primary key.
+pub fn get2(db: &DbContext, b: &FzBuch) -> Result<Option<FzBuch>> {
+    let p = FZ_BUCH::table
+        .filter(
+            FZ_BUCH::mandant_nr
+                .eq(b.mandant_nr)
+                .and(FZ_BUCH::uid.eq(b.uid.clone())),
+        )
+        .first::<FzBuch>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get list.
+#[allow(dead_code)]
+pub fn get_list(db: &DbContext, mandant_nr_: i32) -> Result<Vec<FzBuch>> {
+    let list = FZ_BUCH::table
+        .filter(FZ_BUCH::mandant_nr.eq(mandant_nr_))
+        .load::<FzBuch>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
+
+/// Insert a dataset.
+pub fn insert<'a>(db: &mut DbContext, b: &'a FzBuch) -> Result<&'a FzBuch> {
+    let rows = diesel::insert_into(FZ_BUCH::table)
+        .values(b)
+        .execute(db.c)?;
+    if rows <= 0 {
+        return Err(RsbpError::NotFound);
+    }
+    db.ul.add(&UndoEntry::fz_buch(None, Some(b)));
+    Ok(b)
+}
+
+/// Update a dataset.
+pub fn update<'a>(db: &mut DbContext, b: &'a FzBuch) -> Result<&'a FzBuch> {
+    let oo = get2(&db, b)?;
+    let rows = diesel::update(
+        FZ_BUCH::table.filter(
+            FZ_BUCH::mandant_nr
+                .eq(b.mandant_nr)
+                .and(FZ_BUCH::uid.eq(b.uid.clone())),
+        ),
+    )
+    .set((
+        FZ_BUCH::autor_uid.eq(b.autor_uid.as_str()),
+        FZ_BUCH::serie_uid.eq(b.serie_uid.as_str()),
+        FZ_BUCH::seriennummer.eq(b.seriennummer),
+        FZ_BUCH::titel.eq(b.titel.as_str()),
+        FZ_BUCH::untertitel.eq(b.untertitel.as_ref()),
+        FZ_BUCH::seiten.eq(b.seiten),
+        FZ_BUCH::sprache_nr.eq(b.sprache_nr),
+        FZ_BUCH::notiz.eq(b.notiz.as_ref()),
+        FZ_BUCH::angelegt_von.eq(b.angelegt_von.as_ref()),
+        FZ_BUCH::angelegt_am.eq(b.angelegt_am),
+        FZ_BUCH::geaendert_von.eq(b.geaendert_von.as_ref()),
+        FZ_BUCH::geaendert_am.eq(b.geaendert_am),
+    ))
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::fz_buch(Some(&o), Some(b)));
+    }
+    Ok(b)
+}
+
+/// Delete a dataset.
+pub fn delete(db: &mut DbContext, b: &FzBuch) -> Result<()> {
+    let oo = get2(db, b)?;
+    let rows = diesel::delete(
+        FZ_BUCH::table.filter(
+            FZ_BUCH::mandant_nr
+                .eq(b.mandant_nr)
+                .and(FZ_BUCH::uid.eq(b.uid.clone())),
+        ),
+    )
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::fz_buch(Some(&o), None));
+    }
+    Ok(())
+}
+
+/// Get list of books filtered by author, series and title.
+pub fn get_list_ext(
+    db: &DbContext,
+    autor_uid_: Option<&String>,
+    serie_uid_: Option<&String>,
+    titel_: Option<&String>,
+) -> Result<Vec<FzBuch>> {
+    let mut q = FZ_BUCH::table
+        .into_boxed()
+        .filter(FZ_BUCH::mandant_nr.eq(db.daten.mandant_nr));
+    if let Some(a) = autor_uid_ {
+        q = q.filter(FZ_BUCH::autor_uid.eq(a));
+    }
+    if let Some(s) = serie_uid_ {
+        q = q.filter(FZ_BUCH::serie_uid.eq(s));
+    }
+    if let Some(t) = titel_ {
+        q = q.filter(FZ_BUCH::titel.like(t));
+    }
+    let list = q
+        .order((FZ_BUCH::serie_uid, FZ_BUCH::seriennummer, FZ_BUCH::titel))
+        .load::<FzBuch>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
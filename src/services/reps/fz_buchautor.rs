@@ -0,0 +1,234 @@
+use super::DbContext;
+use crate::{config::RsbpError, services::undo::UndoEntry, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use rsbp_rep::{models::FzBuchautor, schema::*};
+
+/// Undo a dataset.
+pub fn undo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<FzBuchautor>(or)?;
+    let oa = UndoEntry::from_str::<FzBuchautor>(ac)?;
+    if let (Some(o), Some(_a)) = (&oo, &oa) {
+        // Update
+        update(db, o)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        delete(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        insert(db, o)?;
+    }
+    Ok(())
+}
+
+/// Redo a dataset.
+pub fn redo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<FzBuchautor>(or)?;
+    let oa = UndoEntry::from_str::<FzBuchautor>(ac)?;
+    if let (Some(_o), Some(a)) = (&oo, &oa) {
+        // Update
+        update(db, a)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        insert(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        delete(db, o)?;
+    }
+    Ok(())
+}
+
+/// Save dataset with all values.
+#[allow(dead_code)]
+pub fn save0(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    name_: &String,
+    vorname_: &Option<String>,
+    notiz_: &Option<String>,
+    angelegt_von_: &Option<String>,
+    angelegt_am_: &Option<NaiveDateTime>,
+    geaendert_von_: &Option<String>,
+    geaendert_am_: &Option<NaiveDateTime>,
+) -> Result<FzBuchautor> {
+    let op = FZ_BUCHAUTOR::table
+        .filter(
+            FZ_BUCHAUTOR::mandant_nr
+                .eq(mandant_nr_)
+                .and(FZ_BUCHAUTOR::uid.eq(uid_.clone())),
+        )
+        .first::<FzBuchautor>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    let mut p = FzBuchautor {
+        mandant_nr: *mandant_nr_,
+        uid: uid_.clone(),
+        name: name_.clone(),
+        vorname: vorname_.clone(),
+        notiz: notiz_.clone(),
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+    };
+    if let Some(pu) = op {
+        if p != pu {
+            p.angelegt_von = pu.angelegt_von;
+            p.angelegt_am = pu.angelegt_am;
+            p.geaendert_von = pu.geaendert_von;
+            p.geaendert_am = pu.geaendert_am;
+            if p.angelegt_von.is_none() || !angelegt_von_.is_none() {
+                super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+            }
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+            update(db, &p)?;
+        }
+    } else {
+        super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+        if !geaendert_von_.is_none() {
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+        }
+        insert(db, &p)?;
+    }
+    return Ok(p);
+}
+
+/// Save dataset without revision columns.
+#[allow(dead_code)]
+pub fn save(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    name_: &String,
+    vorname_: &Option<String>,
+    notiz_: &Option<String>,
+) -> Result<FzBuchautor> {
+    save0(
+        db,
+        mandant_nr_,
+        uid_,
+        name_,
+        vorname_,
+        notiz_,
+        &None,
+        &None,
+        &None,
+        &None,
+    )
+}
+
+/// Get dataset by primary key.
+#[allow(dead_code)]
+pub fn get(db: &DbContext, mandant_nr_: &i32, uid_: &String) -> Result<Option<FzBuchautor>> {
+    let p = FZ_BUCHAUTOR::table
+        .filter(
+            FZ_BUCHAUTOR::mandant_nr
+                .eq(mandant_nr_)
+                .and(FZ_BUCHAUTOR::uid.eq(uid_.clone())),
+        )
+        .first::<FzBuchautor>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get dataset by primary key.
+pub fn get2(db: &DbContext, b: &FzBuchautor) -> Result<Option<FzBuchautor>> {
+    let p = FZ_BUCHAUTOR::table
+        .filter(
+            FZ_BUCHAUTOR::mandant_nr
+                .eq(b.mandant_nr)
+                .and(FZ_BUCHAUTOR::uid.eq(b.uid.clone())),
+        )
+        .first::<FzBuchautor>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get list.
+#[allow(dead_code)]
+pub fn get_list(db: &DbContext, mandant_nr_: i32) -> Result<Vec<FzBuchautor>> {
+    let list = FZ_BUCHAUTOR::table
+        .filter(FZ_BUCHAUTOR::mandant_nr.eq(mandant_nr_))
+        .load::<FzBuchautor>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
+
+/// Insert a dataset.
+pub fn insert<'a>(db: &mut DbContext, b: &'a FzBuchautor) -> Result<&'a FzBuchautor> {
+    let rows = diesel::insert_into(FZ_BUCHAUTOR::table)
+        .values(b)
+        .execute(db.c)?;
+    if rows <= 0 {
+        return Err(RsbpError::NotFound);
+    }
+    db.ul.add(&UndoEntry::fz_buchautor(None, Some(b)));
+    Ok(b)
+}
+
+/// Update a dataset.
+pub fn update<'a>(db: &mut DbContext, b: &'a FzBuchautor) -> Result<&'a FzBuchautor> {
+    let oo = get2(&db, b)?;
+    let rows = diesel::update(
+        FZ_BUCHAUTOR::table.filter(
+            FZ_BUCHAUTOR::mandant_nr
+                .eq(b.mandant_nr)
+                .and(FZ_BUCHAUTOR::uid.eq(b.uid.clone())),
+        ),
+    )
+    .set((
+        FZ_BUCHAUTOR::name.eq(b.name.as_str()),
+        FZ_BUCHAUTOR::vorname.eq(b.vorname.as_ref()),
+        FZ_BUCHAUTOR::notiz.eq(b.notiz.as_ref()),
+        FZ_BUCHAUTOR::angelegt_von.eq(b.angelegt_von.as_ref()),
+        FZ_BUCHAUTOR::angelegt_am.eq(b.angelegt_am),
+        FZ_BUCHAUTOR::geaendert_von.eq(b.geaendert_von.as_ref()),
+        FZ_BUCHAUTOR::geaendert_am.eq(b.geaendert_am),
+    ))
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::fz_buchautor(Some(&o), Some(b)));
+    }
+    Ok(b)
+}
+
+/// Delete a dataset.
+pub fn delete(db: &mut DbContext, b: &FzBuchautor) -> Result<()> {
+    let oo = get2(db, b)?;
+    let rows = diesel::delete(
+        FZ_BUCHAUTOR::table.filter(
+            FZ_BUCHAUTOR::mandant_nr
+                .eq(b.mandant_nr)
+                .and(FZ_BUCHAUTOR::uid.eq(b.uid.clone())),
+        ),
+    )
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::fz_buchautor(Some(&o), None));
+    }
+    Ok(())
+}
+
+/// Get list of authors filtered by name.
+pub fn get_list_ext(db: &DbContext, name_: &Option<String>) -> Result<Vec<FzBuchautor>> {
+    let mut q = FZ_BUCHAUTOR::table
+        .into_boxed()
+        .filter(FZ_BUCHAUTOR::mandant_nr.eq(db.daten.mandant_nr));
+    if let Some(n) = name_ {
+        q = q.filter(FZ_BUCHAUTOR::name.like(n));
+    }
+    let list = q
+        .order((FZ_BUCHAUTOR::name, FZ_BUCHAUTOR::vorname, FZ_BUCHAUTOR::uid))
+        .load::<FzBuchautor>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
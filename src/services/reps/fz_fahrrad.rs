@@ -0,0 +1,224 @@
+use super::DbContext;
+use crate::{config::RsbpError, services::undo::UndoEntry, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use rsbp_rep::{models::FzFahrrad, schema::*};
+
+/// Undo a dataset.
+pub fn undo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<FzFahrrad>(or)?;
+    let oa = UndoEntry::from_str::<FzFahrrad>(ac)?;
+    if let (Some(o), Some(_a)) = (&oo, &oa) {
+        // Update
+        update(db, o)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        delete(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        insert(db, o)?;
+    }
+    Ok(())
+}
+
+/// Redo a dataset.
+pub fn redo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<FzFahrrad>(or)?;
+    let oa = UndoEntry::from_str::<FzFahrrad>(ac)?;
+    if let (Some(_o), Some(a)) = (&oo, &oa) {
+        // Update
+        update(db, a)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        insert(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        delete(db, o)?;
+    }
+    Ok(())
+}
+
+/// Save dataset with all values.
+#[allow(dead_code)]
+pub fn save0(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    bezeichnung_: &String,
+    typ_: &i32,
+    angelegt_von_: &Option<String>,
+    angelegt_am_: &Option<NaiveDateTime>,
+    geaendert_von_: &Option<String>,
+    geaendert_am_: &Option<NaiveDateTime>,
+) -> Result<FzFahrrad> {
+    let op = FZ_FAHRRAD::table
+        .filter(
+            FZ_FAHRRAD::mandant_nr
+                .eq(mandant_nr_)
+                .and(FZ_FAHRRAD::uid.eq(uid_.clone())),
+        )
+        .first::<FzFahrrad>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    let mut p = FzFahrrad {
+        mandant_nr: *mandant_nr_,
+        uid: uid_.clone(),
+        bezeichnung: bezeichnung_.clone(),
+        typ: *typ_,
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+    };
+    if let Some(pu) = op {
+        if p != pu {
+            p.angelegt_von = pu.angelegt_von;
+            p.angelegt_am = pu.angelegt_am;
+            p.geaendert_von = pu.geaendert_von;
+            p.geaendert_am = pu.geaendert_am;
+            if p.angelegt_von.is_none() || !angelegt_von_.is_none() {
+                super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+            }
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+            update(db, &p)?;
+        }
+    } else {
+        super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+        if !geaendert_von_.is_none() {
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+        }
+        insert(db, &p)?;
+    }
+    return Ok(p);
+}
+
+/// Save dataset without revision columns.
+#[allow(dead_code)]
+pub fn save(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    bezeichnung_: &String,
+    typ_: &i32,
+) -> Result<FzFahrrad> {
+    save0(
+        db,
+        mandant_nr_,
+        uid_,
+        bezeichnung_,
+        typ_,
+        &None,
+        &None,
+        &None,
+        &None,
+    )
+}
+
+/// Get dataset by primary key.
+#[allow(dead_code)]
+pub fn get(db: &DbContext, mandant_nr_: &i32, uid_: &String) -> Result<Option<FzFahrrad>> {
+    let p = FZ_FAHRRAD::table
+        .filter(
+            FZ_FAHRRAD::mandant_nr
+                .eq(mandant_nr_)
+                .and(FZ_FAHRRAD::uid.eq(uid_.clone())),
+        )
+        .first::<FzFahrrad>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get dataset by primary key.
+pub fn get2(db: &DbContext, b: &FzFahrrad) -> Result<Option<FzFahrrad>> {
+    let p = FZ_FAHRRAD::table
+        .filter(
+            FZ_FAHRRAD::mandant_nr
+                .eq(b.mandant_nr)
+                .and(FZ_FAHRRAD::uid.eq(b.uid.clone())),
+        )
+        .first::<FzFahrrad>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get list.
+#[allow(dead_code)]
+pub fn get_list(db: &DbContext, mandant_nr_: i32) -> Result<Vec<FzFahrrad>> {
+    let list = FZ_FAHRRAD::table
+        .filter(FZ_FAHRRAD::mandant_nr.eq(mandant_nr_))
+        .load::<FzFahrrad>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
+
+/// Insert a dataset.
+pub fn insert<'a>(db: &mut DbContext, b: &'a FzFahrrad) -> Result<&'a FzFahrrad> {
+    let rows = diesel::insert_into(FZ_FAHRRAD::table)
+        .values(b)
+        .execute(db.c)?;
+    if rows <= 0 {
+        return Err(RsbpError::NotFound);
+    }
+    db.ul.add(&UndoEntry::fz_fahrrad(None, Some(b)));
+    Ok(b)
+}
+
+/// Update a dataset.
+pub fn update<'a>(db: &mut DbContext, b: &'a FzFahrrad) -> Result<&'a FzFahrrad> {
+    let oo = get2(&db, b)?;
+    let rows = diesel::update(
+        FZ_FAHRRAD::table.filter(
+            FZ_FAHRRAD::mandant_nr
+                .eq(b.mandant_nr)
+                .and(FZ_FAHRRAD::uid.eq(b.uid.clone())),
+        ),
+    )
+    .set((
+        FZ_FAHRRAD::bezeichnung.eq(b.bezeichnung.as_str()),
+        FZ_FAHRRAD::typ.eq(b.typ),
+        FZ_FAHRRAD::angelegt_von.eq(b.angelegt_von.as_ref()),
+        FZ_FAHRRAD::angelegt_am.eq(b.angelegt_am),
+        FZ_FAHRRAD::geaendert_von.eq(b.geaendert_von.as_ref()),
+        FZ_FAHRRAD::geaendert_am.eq(b.geaendert_am),
+    ))
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::fz_fahrrad(Some(&o), Some(b)));
+    }
+    Ok(b)
+}
+
+/// Delete a dataset.
+pub fn delete(db: &mut DbContext, b: &FzFahrrad) -> Result<()> {
+    let oo = get2(db, b)?;
+    let rows = diesel::delete(
+        FZ_FAHRRAD::table.filter(
+            FZ_FAHRRAD::mandant_nr
+                .eq(b.mandant_nr)
+                .and(FZ_FAHRRAD::uid.eq(b.uid.clone())),
+        ),
+    )
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::fz_fahrrad(Some(&o), None));
+    }
+    Ok(())
+}
+
+/// Get list of bikes ordered by description.
+pub fn get_list_ext(db: &DbContext) -> Result<Vec<FzFahrrad>> {
+    let list = FZ_FAHRRAD::table
+        .filter(FZ_FAHRRAD::mandant_nr.eq(db.daten.mandant_nr))
+        .order((FZ_FAHRRAD::bezeichnung, FZ_FAHRRAD::uid))
+        .load::<FzFahrrad>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
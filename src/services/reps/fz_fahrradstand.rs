@@ -0,0 +1,299 @@
+use super::DbContext;
+use crate::{config::RsbpError, services::undo::UndoEntry, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use rsbp_rep::{models::FzFahrradstand, schema::*};
+
+/// Undo a dataset.
+pub fn undo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<FzFahrradstand>(or)?;
+    let oa = UndoEntry::from_str::<FzFahrradstand>(ac)?;
+    if let (Some(o), Some(_a)) = (&oo, &oa) {
+        // Update
+        update(db, o)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        delete(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        insert(db, o)?;
+    }
+    Ok(())
+}
+
+/// Redo a dataset.
+pub fn redo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<FzFahrradstand>(or)?;
+    let oa = UndoEntry::from_str::<FzFahrradstand>(ac)?;
+    if let (Some(_o), Some(a)) = (&oo, &oa) {
+        // Update
+        update(db, a)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        insert(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        delete(db, o)?;
+    }
+    Ok(())
+}
+
+/// Save dataset with all values.
+#[allow(dead_code)]
+pub fn save0(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    fahrrad_uid_: &String,
+    datum_: &NaiveDate,
+    nr_: &i32,
+    zaehler_km_: &f64,
+    periode_km_: &f64,
+    periode_schnitt_: &f64,
+    beschreibung_: &Option<String>,
+    angelegt_von_: &Option<String>,
+    angelegt_am_: &Option<NaiveDateTime>,
+    geaendert_von_: &Option<String>,
+    geaendert_am_: &Option<NaiveDateTime>,
+    replikation_uid_: &Option<String>,
+) -> Result<FzFahrradstand> {
+    let op = FZ_FAHRRADSTAND::table
+        .filter(
+            FZ_FAHRRADSTAND::mandant_nr
+                .eq(mandant_nr_)
+                .and(FZ_FAHRRADSTAND::fahrrad_uid.eq(fahrrad_uid_.clone()))
+                .and(FZ_FAHRRADSTAND::datum.eq(datum_.clone()))
+                .and(FZ_FAHRRADSTAND::nr.eq(nr_)),
+        )
+        .first::<FzFahrradstand>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    let mut p = FzFahrradstand {
+        mandant_nr: *mandant_nr_,
+        fahrrad_uid: fahrrad_uid_.clone(),
+        datum: datum_.clone(),
+        nr: *nr_,
+        zaehler_km: *zaehler_km_,
+        periode_km: *periode_km_,
+        periode_schnitt: *periode_schnitt_,
+        beschreibung: beschreibung_.clone(),
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+        replikation_uid: replikation_uid_.clone(),
+    };
+    if let Some(pu) = op {
+        if p != pu {
+            p.angelegt_von = pu.angelegt_von;
+            p.angelegt_am = pu.angelegt_am;
+            p.geaendert_von = pu.geaendert_von;
+            p.geaendert_am = pu.geaendert_am;
+            if p.angelegt_von.is_none() || !angelegt_von_.is_none() {
+                super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+            }
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+            update(db, &p)?;
+        }
+    } else {
+        super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+        if !geaendert_von_.is_none() {
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+        }
+        insert(db, &p)?;
+    }
+    return Ok(p);
+}
+
+/// Save dataset without revision columns.
+#[allow(dead_code)]
+pub fn save(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    fahrrad_uid_: &String,
+    datum_: &NaiveDate,
+    nr_: &i32,
+    zaehler_km_: &f64,
+    periode_km_: &f64,
+    periode_schnitt_: &f64,
+    beschreibung_: &Option<String>,
+) -> Result<FzFahrradstand> {
+    save0(
+        db,
+        mandant_nr_,
+        fahrrad_uid_,
+        datum_,
+        nr_,
+        zaehler_km_,
+        periode_km_,
+        periode_schnitt_,
+        beschreibung_,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    )
+}
+
+/// Get dataset by primary key.
+#[allow(dead_code)]
+pub fn get(
+    db: &DbContext,
+    mandant_nr_: &i32,
+    fahrrad_uid_: &String,
+    datum_: &NaiveDate,
+    nr_: &i32,
+) -> Result<Option<FzFahrradstand>> {
+    let p = FZ_FAHRRADSTAND::table
+        .filter(
+            FZ_FAHRRADSTAND::mandant_nr
+                .eq(mandant_nr_)
+                .and(FZ_FAHRRADSTAND::fahrrad_uid.eq(fahrrad_uid_.clone()))
+                .and(FZ_FAHRRADSTAND::datum.eq(datum_.clone()))
+                .and(FZ_FAHRRADSTAND::nr.eq(nr_)),
+        )
+        .first::<FzFahrradstand>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get dataset by primary key.
+pub fn get2(db: &DbContext, b: &FzFahrradstand) -> Result<Option<FzFahrradstand>> {
+    let p = FZ_FAHRRADSTAND::table
+        .filter(
+            FZ_FAHRRADSTAND::mandant_nr
+                .eq(b.mandant_nr)
+                .and(FZ_FAHRRADSTAND::fahrrad_uid.eq(b.fahrrad_uid.clone()))
+                .and(FZ_FAHRRADSTAND::datum.eq(b.datum.clone()))
+                .and(FZ_FAHRRADSTAND::nr.eq(b.nr)),
+        )
+        .first::<FzFahrradstand>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get list.
+#[allow(dead_code)]
+pub fn get_list(db: &DbContext, mandant_nr_: i32) -> Result<Vec<FzFahrradstand>> {
+    let list = FZ_FAHRRADSTAND::table
+        .filter(FZ_FAHRRADSTAND::mandant_nr.eq(mandant_nr_))
+        .load::<FzFahrradstand>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
+
+/// Insert a dataset.
+pub fn insert<'a>(db: &mut DbContext, b: &'a FzFahrradstand) -> Result<&'a FzFahrradstand> {
+    let rows = diesel::insert_into(FZ_FAHRRADSTAND::table)
+        .values(b)
+        .execute(db.c)?;
+    if rows <= 0 {
+        return Err(RsbpError::NotFound);
+    }
+    db.ul.add(&UndoEntry::fz_fahrradstand(None, Some(b)));
+    Ok(b)
+}
+
+/// Update a dataset.
+pub fn update<'a>(db: &mut DbContext, b: &'a FzFahrradstand) -> Result<&'a FzFahrradstand> {
+    let oo = get2(&db, b)?;
+    let rows = diesel::update(
+        FZ_FAHRRADSTAND::table.filter(
+            FZ_FAHRRADSTAND::mandant_nr
+                .eq(b.mandant_nr)
+                .and(FZ_FAHRRADSTAND::fahrrad_uid.eq(b.fahrrad_uid.clone()))
+                .and(FZ_FAHRRADSTAND::datum.eq(b.datum.clone()))
+                .and(FZ_FAHRRADSTAND::nr.eq(b.nr)),
+        ),
+    )
+    .set((
+        FZ_FAHRRADSTAND::zaehler_km.eq(b.zaehler_km),
+        FZ_FAHRRADSTAND::periode_km.eq(b.periode_km),
+        FZ_FAHRRADSTAND::periode_schnitt.eq(b.periode_schnitt),
+        FZ_FAHRRADSTAND::beschreibung.eq(b.beschreibung.as_ref()),
+        FZ_FAHRRADSTAND::angelegt_von.eq(b.angelegt_von.as_ref()),
+        FZ_FAHRRADSTAND::angelegt_am.eq(b.angelegt_am),
+        FZ_FAHRRADSTAND::geaendert_von.eq(b.geaendert_von.as_ref()),
+        FZ_FAHRRADSTAND::geaendert_am.eq(b.geaendert_am),
+        FZ_FAHRRADSTAND::replikation_uid.eq(b.replikation_uid.as_ref()),
+    ))
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::fz_fahrradstand(Some(&o), Some(b)));
+    }
+    Ok(b)
+}
+
+/// Delete a dataset.
+pub fn delete(db: &mut DbContext, b: &FzFahrradstand) -> Result<()> {
+    let oo = get2(db, b)?;
+    let rows = diesel::delete(
+        FZ_FAHRRADSTAND::table.filter(
+            FZ_FAHRRADSTAND::mandant_nr
+                .eq(b.mandant_nr)
+                .and(FZ_FAHRRADSTAND::fahrrad_uid.eq(b.fahrrad_uid.clone()))
+                .and(FZ_FAHRRADSTAND::datum.eq(b.datum.clone()))
+                .and(FZ_FAHRRADSTAND::nr.eq(b.nr)),
+        ),
+    )
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::fz_fahrradstand(Some(&o), None));
+    }
+    Ok(())
+}
+
+/// Get list of mileages filtered by bike and period ordered by date.
+pub fn get_list_ext(
+    db: &DbContext,
+    fahrrad_uid_: Option<&String>,
+    from: Option<&NaiveDate>,
+    to: Option<&NaiveDate>,
+) -> Result<Vec<FzFahrradstand>> {
+    let mut q = FZ_FAHRRADSTAND::table
+        .into_boxed()
+        .filter(FZ_FAHRRADSTAND::mandant_nr.eq(db.daten.mandant_nr));
+    if let Some(f) = fahrrad_uid_ {
+        q = q.filter(FZ_FAHRRADSTAND::fahrrad_uid.eq(f));
+    }
+    if let Some(d) = from {
+        q = q.filter(FZ_FAHRRADSTAND::datum.ge(d));
+    }
+    if let Some(d) = to {
+        q = q.filter(FZ_FAHRRADSTAND::datum.le(d));
+    }
+    let list = q
+        .order((
+            FZ_FAHRRADSTAND::fahrrad_uid,
+            FZ_FAHRRADSTAND::datum,
+            FZ_FAHRRADSTAND::nr,
+        ))
+        .load::<FzFahrradstand>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
+
+/// Get dataset by replication ID.
+pub fn get_by_replication_uid(
+    db: &DbContext,
+    replikation_uid_: &String,
+) -> Result<Option<FzFahrradstand>> {
+    let p = FZ_FAHRRADSTAND::table
+        .filter(
+            FZ_FAHRRADSTAND::mandant_nr
+                .eq(db.daten.mandant_nr)
+                .and(FZ_FAHRRADSTAND::replikation_uid.eq(replikation_uid_)),
+        )
+        .first::<FzFahrradstand>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
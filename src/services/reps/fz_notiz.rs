@@ -0,0 +1,260 @@
+use super::DbContext;
+use crate::{base::functions, config::RsbpError, services::undo::UndoEntry, Result};
+use chrono::NaiveDateTime;
+use diesel::{
+    prelude::*,
+    sql_query,
+    sql_types::{Integer, Text},
+};
+use rsbp_rep::{models::FzNotiz, schema::*};
+
+/// Undo a dataset.
+pub fn undo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<FzNotiz>(or)?;
+    let oa = UndoEntry::from_str::<FzNotiz>(ac)?;
+    if let (Some(o), Some(_a)) = (&oo, &oa) {
+        // Update
+        update(db, o)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        delete(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        insert(db, o)?;
+    }
+    Ok(())
+}
+
+/// Redo a dataset.
+pub fn redo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<FzNotiz>(or)?;
+    let oa = UndoEntry::from_str::<FzNotiz>(ac)?;
+    if let (Some(_o), Some(a)) = (&oo, &oa) {
+        // Update
+        update(db, a)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        insert(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        delete(db, o)?;
+    }
+    Ok(())
+}
+
+/// Save dataset with all values.
+#[allow(dead_code)]
+pub fn save0(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    thema_: &String,
+    notiz_: &Option<String>,
+    angelegt_von_: &Option<String>,
+    angelegt_am_: &Option<NaiveDateTime>,
+    geaendert_von_: &Option<String>,
+    geaendert_am_: &Option<NaiveDateTime>,
+) -> Result<FzNotiz> {
+    let op = FZ_NOTIZ::table
+        .filter(
+            FZ_NOTIZ::mandant_nr
+                .eq(mandant_nr_)
+                .and(FZ_NOTIZ::uid.eq(uid_.clone())),
+        )
+        .first::<FzNotiz>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    let mut p = FzNotiz {
+        mandant_nr: *mandant_nr_,
+        uid: uid_.clone(),
+        thema: thema_.clone(),
+        notiz: notiz_.clone(),
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+    };
+    if let Some(pu) = op {
+        if p != pu {
+            p.angelegt_von = pu.angelegt_von;
+            p.angelegt_am = pu.angelegt_am;
+            p.geaendert_von = pu.geaendert_von;
+            p.geaendert_am = pu.geaendert_am;
+            if p.angelegt_von.is_none() || !angelegt_von_.is_none() {
+                super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+            }
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+            update(db, &p)?;
+        }
+    } else {
+        super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+        if !geaendert_von_.is_none() {
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+        }
+        insert(db, &p)?;
+    }
+    return Ok(p);
+}
+
+/// Save dataset without revision columns.
+#[allow(dead_code)]
+pub fn save(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    thema_: &String,
+    notiz_: &Option<String>,
+) -> Result<FzNotiz> {
+    save0(
+        db,
+        mandant_nr_,
+        uid_,
+        thema_,
+        notiz_,
+        &None,
+        &None,
+        &None,
+        &None,
+    )
+}
+
+/// Get dataset by primary key.
+#[allow(dead_code)]
+pub fn get(db: &DbContext, mandant_nr_: &i32, uid_: &String) -> Result<Option<FzNotiz>> {
+    let p = FZ_NOTIZ::table
+        .filter(
+            FZ_NOTIZ::mandant_nr
+                .eq(mandant_nr_)
+                .and(FZ_NOTIZ::uid.eq(uid_.clone())),
+        )
+        .first::<FzNotiz>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get dataset by primary key.
+pub fn get2(db: &DbContext, b: &FzNotiz) -> Result<Option<FzNotiz>> {
+    let p = FZ_NOTIZ::table
+        .filter(
+            FZ_NOTIZ::mandant_nr
+                .eq(b.mandant_nr)
+                .and(FZ_NOTIZ::uid.eq(b.uid.clone())),
+        )
+        .first::<FzNotiz>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get list.
+#[allow(dead_code)]
+pub fn get_list(db: &DbContext, mandant_nr_: i32) -> Result<Vec<FzNotiz>> {
+    let list = FZ_NOTIZ::table
+        .filter(FZ_NOTIZ::mandant_nr.eq(mandant_nr_))
+        .load::<FzNotiz>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
+
+/// Insert a dataset.
+pub fn insert<'a>(db: &mut DbContext, b: &'a FzNotiz) -> Result<&'a FzNotiz> {
+    let rows = diesel::insert_into(FZ_NOTIZ::table)
+        .values(b)
+        .execute(db.c)?;
+    if rows <= 0 {
+        return Err(RsbpError::NotFound);
+    }
+    db.ul.add(&UndoEntry::fz_notiz(None, Some(b)));
+    Ok(b)
+}
+
+/// Update a dataset.
+pub fn update<'a>(db: &mut DbContext, b: &'a FzNotiz) -> Result<&'a FzNotiz> {
+    let oo = get2(&db, b)?;
+    let rows = diesel::update(
+        FZ_NOTIZ::table.filter(
+            FZ_NOTIZ::mandant_nr
+                .eq(b.mandant_nr)
+                .and(FZ_NOTIZ::uid.eq(b.uid.clone())),
+        ),
+    )
+    .set((
+        FZ_NOTIZ::thema.eq(b.thema.as_str()),
+        FZ_NOTIZ::notiz.eq(b.notiz.as_ref()),
+        FZ_NOTIZ::angelegt_von.eq(b.angelegt_von.as_ref()),
+        FZ_NOTIZ::angelegt_am.eq(b.angelegt_am),
+        FZ_NOTIZ::geaendert_von.eq(b.geaendert_von.as_ref()),
+        FZ_NOTIZ::geaendert_am.eq(b.geaendert_am),
+    ))
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::fz_notiz(Some(&o), Some(b)));
+    }
+    Ok(b)
+}
+
+/// Delete a dataset.
+pub fn delete(db: &mut DbContext, b: &FzNotiz) -> Result<()> {
+    let oo = get2(db, b)?;
+    let rows = diesel::delete(
+        FZ_NOTIZ::table.filter(
+            FZ_NOTIZ::mandant_nr
+                .eq(b.mandant_nr)
+                .and(FZ_NOTIZ::uid.eq(b.uid.clone())),
+        ),
+    )
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::fz_notiz(Some(&o), None));
+    }
+    Ok(())
+}
+
+/// Get search list with topic or memo fitting the search patterns.
+pub fn get_list_search(db: &DbContext, search: &[String; 9]) -> Result<Vec<FzNotiz>> {
+    let sql = "SELECT a.mandant_nr mandant_nr, a.uid uid, a.thema thema, a.notiz notiz, a.angelegt_von angelegt_von, a.angelegt_am angelegt_am, a.geaendert_von geaendert_von, a.geaendert_am geaendert_am
+      FROM (SELECT b.*, b.thema || ' ' || coalesce(b.notiz, '') text FROM FZ_Notiz b) a WHERE a.mandant_nr=?
+       AND ((0=? OR a.text like ?) OR (0=? OR a.text like ?) OR (0=? OR a.text like ?))
+       AND ((0=? OR a.text like ?) OR (0=? OR a.text like ?) OR (0=? OR a.text like ?))
+       AND (0=? OR NOT a.text like ?) AND (0=? OR NOT a.text like ?) AND (0=? OR NOT a.text like ?)
+      ORDER BY a.thema, a.uid";
+    let search1 = functions::iif_i32(search[0].is_empty(), 0, 1);
+    let search2 = functions::iif_i32(search[1].is_empty() && search1 == 0, 0, 1);
+    let search3 = functions::iif_i32(search[2].is_empty() && search2 == 0, 0, 1);
+    let search4 = functions::iif_i32(search[3].is_empty(), 0, 1);
+    let search5 = functions::iif_i32(search[4].is_empty() && search4 == 0, 0, 1);
+    let search6 = functions::iif_i32(search[5].is_empty() && search5 == 0, 0, 1);
+    let search7 = functions::iif_i32(search[6].is_empty(), 0, 1);
+    let search8 = functions::iif_i32(search[7].is_empty(), 0, 1);
+    let search9 = functions::iif_i32(search[8].is_empty(), 0, 1);
+    let list = sql_query(sql)
+        .bind::<Integer, _>(db.daten.mandant_nr)
+        .bind::<Integer, _>(search1)
+        .bind::<Text, _>(search[0].to_string())
+        .bind::<Integer, _>(search2)
+        .bind::<Text, _>(search[1].to_string())
+        .bind::<Integer, _>(search3)
+        .bind::<Text, _>(search[2].to_string())
+        .bind::<Integer, _>(search4)
+        .bind::<Text, _>(search[3].to_string())
+        .bind::<Integer, _>(search5)
+        .bind::<Text, _>(search[4].to_string())
+        .bind::<Integer, _>(search6)
+        .bind::<Text, _>(search[5].to_string())
+        .bind::<Integer, _>(search7)
+        .bind::<Text, _>(search[6].to_string())
+        .bind::<Integer, _>(search8)
+        .bind::<Text, _>(search[7].to_string())
+        .bind::<Integer, _>(search9)
+        .bind::<Text, _>(search[8].to_string())
+        .load::<FzNotiz>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
@@ -0,0 +1,326 @@
+use super::DbContext;
+use crate::{config::RsbpError, services::undo::UndoEntry, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use rsbp_rep::{models::SbEreignis, schema::*};
+
+/// Undo a dataset.
+pub fn undo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<SbEreignis>(or)?;
+    let oa = UndoEntry::from_str::<SbEreignis>(ac)?;
+    if let (Some(o), Some(_a)) = (&oo, &oa) {
+        // Update
+        update(db, o)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        delete(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        insert(db, o)?;
+    }
+    Ok(())
+}
+
+/// Redo a dataset.
+pub fn redo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<SbEreignis>(or)?;
+    let oa = UndoEntry::from_str::<SbEreignis>(ac)?;
+    if let (Some(_o), Some(a)) = (&oo, &oa) {
+        // Update
+        update(db, a)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        insert(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        delete(db, o)?;
+    }
+    Ok(())
+}
+
+/// Save dataset with all values.
+#[allow(dead_code)]
+pub fn save0(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    person_uid_: &String,
+    familie_uid_: &String,
+    typ_: &String,
+    tag1_: &i32,
+    monat1_: &i32,
+    jahr1_: &i32,
+    tag2_: &i32,
+    monat2_: &i32,
+    jahr2_: &i32,
+    datum_typ_: &String,
+    ort_: &Option<String>,
+    bemerkung_: &Option<String>,
+    quelle_uid_: &Option<String>,
+    angelegt_von_: &Option<String>,
+    angelegt_am_: &Option<NaiveDateTime>,
+    geaendert_von_: &Option<String>,
+    geaendert_am_: &Option<NaiveDateTime>,
+    replikation_uid_: &Option<String>,
+) -> Result<SbEreignis> {
+    let op = SB_EREIGNIS::table
+        .filter(
+            SB_EREIGNIS::mandant_nr
+                .eq(mandant_nr_)
+                .and(SB_EREIGNIS::person_uid.eq(person_uid_.clone()))
+                .and(SB_EREIGNIS::familie_uid.eq(familie_uid_.clone()))
+                .and(SB_EREIGNIS::typ.eq(typ_.clone())),
+        )
+        .first::<SbEreignis>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    let mut p = SbEreignis {
+        mandant_nr: *mandant_nr_,
+        person_uid: person_uid_.clone(),
+        familie_uid: familie_uid_.clone(),
+        typ: typ_.clone(),
+        tag1: *tag1_,
+        monat1: *monat1_,
+        jahr1: *jahr1_,
+        tag2: *tag2_,
+        monat2: *monat2_,
+        jahr2: *jahr2_,
+        datum_typ: datum_typ_.clone(),
+        ort: ort_.clone(),
+        bemerkung: bemerkung_.clone(),
+        quelle_uid: quelle_uid_.clone(),
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+        replikation_uid: replikation_uid_.clone(),
+    };
+    if let Some(pu) = op {
+        if p != pu {
+            p.angelegt_von = pu.angelegt_von;
+            p.angelegt_am = pu.angelegt_am;
+            p.geaendert_von = pu.geaendert_von;
+            p.geaendert_am = pu.geaendert_am;
+            if p.angelegt_von.is_none() || !angelegt_von_.is_none() {
+                super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+            }
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+            update(db, &p)?;
+        }
+    } else {
+        super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+        if !geaendert_von_.is_none() {
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+        }
+        insert(db, &p)?;
+    }
+    return Ok(p);
+}
+
+/// Save dataset without revision columns.
+#[allow(dead_code)]
+pub fn save(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    person_uid_: &String,
+    familie_uid_: &String,
+    typ_: &String,
+    tag1_: &i32,
+    monat1_: &i32,
+    jahr1_: &i32,
+    tag2_: &i32,
+    monat2_: &i32,
+    jahr2_: &i32,
+    datum_typ_: &String,
+    ort_: &Option<String>,
+    bemerkung_: &Option<String>,
+    quelle_uid_: &Option<String>,
+) -> Result<SbEreignis> {
+    save0(
+        db,
+        mandant_nr_,
+        person_uid_,
+        familie_uid_,
+        typ_,
+        tag1_,
+        monat1_,
+        jahr1_,
+        tag2_,
+        monat2_,
+        jahr2_,
+        datum_typ_,
+        ort_,
+        bemerkung_,
+        quelle_uid_,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    )
+}
+
+/// Get dataset by primary key.
+#[allow(dead_code)]
+pub fn get(
+    db: &DbContext,
+    mandant_nr_: &i32,
+    person_uid_: &String,
+    familie_uid_: &String,
+    typ_: &String,
+) -> Result<Option<SbEreignis>> {
+    let p = SB_EREIGNIS::table
+        .filter(
+            SB_EREIGNIS::mandant_nr
+                .eq(mandant_nr_)
+                .and(SB_EREIGNIS::person_uid.eq(person_uid_.clone()))
+                .and(SB_EREIGNIS::familie_uid.eq(familie_uid_.clone()))
+                .and(SB_EREIGNIS::typ.eq(typ_.clone())),
+        )
+        .first::<SbEreignis>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get dataset by primary key.
+pub fn get2(db: &DbContext, b: &SbEreignis) -> Result<Option<SbEreignis>> {
+    let p = SB_EREIGNIS::table
+        .filter(
+            SB_EREIGNIS::mandant_nr
+                .eq(b.mandant_nr)
+                .and(SB_EREIGNIS::person_uid.eq(b.person_uid.clone()))
+                .and(SB_EREIGNIS::familie_uid.eq(b.familie_uid.clone()))
+                .and(SB_EREIGNIS::typ.eq(b.typ.clone())),
+        )
+        .first::<SbEreignis>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get list.
+#[allow(dead_code)]
+pub fn get_list(db: &DbContext, mandant_nr_: i32) -> Result<Vec<SbEreignis>> {
+    let list = SB_EREIGNIS::table
+        .filter(SB_EREIGNIS::mandant_nr.eq(mandant_nr_))
+        .load::<SbEreignis>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
+
+/// Insert a dataset.
+pub fn insert<'a>(db: &mut DbContext, b: &'a SbEreignis) -> Result<&'a SbEreignis> {
+    let rows = diesel::insert_into(SB_EREIGNIS::table)
+        .values(b)
+        .execute(db.c)?;
+    if rows <= 0 {
+        return Err(RsbpError::NotFound);
+    }
+    db.ul.add(&UndoEntry::sb_ereignis(None, Some(b)));
+    Ok(b)
+}
+
+/// Update a dataset.
+pub fn update<'a>(db: &mut DbContext, b: &'a SbEreignis) -> Result<&'a SbEreignis> {
+    let oo = get2(&db, b)?;
+    let rows = diesel::update(
+        SB_EREIGNIS::table.filter(
+            SB_EREIGNIS::mandant_nr
+                .eq(b.mandant_nr)
+                .and(SB_EREIGNIS::person_uid.eq(b.person_uid.clone()))
+                .and(SB_EREIGNIS::familie_uid.eq(b.familie_uid.clone()))
+                .and(SB_EREIGNIS::typ.eq(b.typ.clone())),
+        ),
+    )
+    .set((
+        SB_EREIGNIS::tag1.eq(b.tag1),
+        SB_EREIGNIS::monat1.eq(b.monat1),
+        SB_EREIGNIS::jahr1.eq(b.jahr1),
+        SB_EREIGNIS::tag2.eq(b.tag2),
+        SB_EREIGNIS::monat2.eq(b.monat2),
+        SB_EREIGNIS::jahr2.eq(b.jahr2),
+        SB_EREIGNIS::datum_typ.eq(b.datum_typ.as_str()),
+        SB_EREIGNIS::ort.eq(b.ort.as_ref()),
+        SB_EREIGNIS::bemerkung.eq(b.bemerkung.as_ref()),
+        SB_EREIGNIS::quelle_uid.eq(b.quelle_uid.as_ref()),
+        SB_EREIGNIS::angelegt_von.eq(b.angelegt_von.as_ref()),
+        SB_EREIGNIS::angelegt_am.eq(b.angelegt_am),
+        SB_EREIGNIS::geaendert_von.eq(b.geaendert_von.as_ref()),
+        SB_EREIGNIS::geaendert_am.eq(b.geaendert_am),
+        SB_EREIGNIS::replikation_uid.eq(b.replikation_uid.as_ref()),
+    ))
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::sb_ereignis(Some(&o), Some(b)));
+    }
+    Ok(b)
+}
+
+/// Delete a dataset.
+pub fn delete(db: &mut DbContext, b: &SbEreignis) -> Result<()> {
+    let oo = get2(db, b)?;
+    let rows = diesel::delete(
+        SB_EREIGNIS::table.filter(
+            SB_EREIGNIS::mandant_nr
+                .eq(b.mandant_nr)
+                .and(SB_EREIGNIS::person_uid.eq(b.person_uid.clone()))
+                .and(SB_EREIGNIS::familie_uid.eq(b.familie_uid.clone()))
+                .and(SB_EREIGNIS::typ.eq(b.typ.clone())),
+        ),
+    )
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::sb_ereignis(Some(&o), None));
+    }
+    Ok(())
+}
+
+/// Get list of events citing a source.
+pub fn get_list_source(db: &DbContext, quelle_uid_: &String) -> Result<Vec<SbEreignis>> {
+    let list = SB_EREIGNIS::table
+        .filter(
+            SB_EREIGNIS::mandant_nr
+                .eq(db.daten.mandant_nr)
+                .and(SB_EREIGNIS::quelle_uid.eq(quelle_uid_)),
+        )
+        .order((
+            SB_EREIGNIS::person_uid,
+            SB_EREIGNIS::familie_uid,
+            SB_EREIGNIS::typ,
+        ))
+        .load::<SbEreignis>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
+
+/// Get list of events filtered by person and family.
+pub fn get_list_ext(
+    db: &DbContext,
+    person_uid_: Option<&String>,
+    familie_uid_: Option<&String>,
+) -> Result<Vec<SbEreignis>> {
+    let mut q = SB_EREIGNIS::table
+        .into_boxed()
+        .filter(SB_EREIGNIS::mandant_nr.eq(db.daten.mandant_nr));
+    if let Some(p) = person_uid_ {
+        q = q.filter(SB_EREIGNIS::person_uid.eq(p));
+    }
+    if let Some(f) = familie_uid_ {
+        q = q.filter(SB_EREIGNIS::familie_uid.eq(f));
+    }
+    let list = q
+        .order((
+            SB_EREIGNIS::person_uid,
+            SB_EREIGNIS::familie_uid,
+            SB_EREIGNIS::typ,
+        ))
+        .load::<SbEreignis>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
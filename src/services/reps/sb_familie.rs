@@ -0,0 +1,245 @@
+use super::DbContext;
+use crate::{config::RsbpError, services::undo::UndoEntry, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use rsbp_rep::{models::SbFamilie, schema::*};
+
+/// Undo a dataset.
+pub fn undo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<SbFamilie>(or)?;
+    let oa = UndoEntry::from_str::<SbFamilie>(ac)?;
+    if let (Some(o), Some(_a)) = (&oo, &oa) {
+        // Update
+        update(db, o)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        delete(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        insert(db, o)?;
+    }
+    Ok(())
+}
+
+/// Redo a dataset.
+pub fn redo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<SbFamilie>(or)?;
+    let oa = UndoEntry::from_str::<SbFamilie>(ac)?;
+    if let (Some(_o), Some(a)) = (&oo, &oa) {
+        // Update
+        update(db, a)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        insert(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        delete(db, o)?;
+    }
+    Ok(())
+}
+
+/// Save dataset with all values.
+#[allow(dead_code)]
+pub fn save0(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    mann_uid_: &Option<String>,
+    frau_uid_: &Option<String>,
+    status1_: &i32,
+    status2_: &i32,
+    status3_: &i32,
+    angelegt_von_: &Option<String>,
+    angelegt_am_: &Option<NaiveDateTime>,
+    geaendert_von_: &Option<String>,
+    geaendert_am_: &Option<NaiveDateTime>,
+) -> Result<SbFamilie> {
+    let op = SB_FAMILIE::table
+        .filter(
+            SB_FAMILIE::mandant_nr
+                .eq(mandant_nr_)
+                .and(SB_FAMILIE::uid.eq(uid_.clone())),
+        )
+        .first::<SbFamilie>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    let mut p = SbFamilie {
+        mandant_nr: *mandant_nr_,
+        uid: uid_.clone(),
+        mann_uid: mann_uid_.clone(),
+        frau_uid: frau_uid_.clone(),
+        status1: *status1_,
+        status2: *status2_,
+        status3: *status3_,
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+    };
+    if let Some(pu) = op {
+        if p != pu {
+            p.angelegt_von = pu.angelegt_von;
+            p.angelegt_am = pu.angelegt_am;
+            p.geaendert_von = pu.geaendert_von;
+            p.geaendert_am = pu.geaendert_am;
+            if p.angelegt_von.is_none() || !angelegt_von_.is_none() {
+                super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+            }
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+            update(db, &p)?;
+        }
+    } else {
+        super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+        if !geaendert_von_.is_none() {
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+        }
+        insert(db, &p)?;
+    }
+    return Ok(p);
+}
+
+/// Save dataset without revision columns.
+#[allow(dead_code)]
+pub fn save(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    mann_uid_: &Option<String>,
+    frau_uid_: &Option<String>,
+    status1_: &i32,
+    status2_: &i32,
+    status3_: &i32,
+) -> Result<SbFamilie> {
+    save0(
+        db,
+        mandant_nr_,
+        uid_,
+        mann_uid_,
+        frau_uid_,
+        status1_,
+        status2_,
+        status3_,
+        &None,
+        &None,
+        &None,
+        &None,
+    )
+}
+
+/// Get dataset by primary key.
+#[allow(dead_code)]
+pub fn get(db: &DbContext, mandant_nr_: &i32, uid_: &String) -> Result<Option<SbFamilie>> {
+    let p = SB_FAMILIE::table
+        .filter(
+            SB_FAMILIE::mandant_nr
+                .eq(mandant_nr_)
+                .and(SB_FAMILIE::uid.eq(uid_.clone())),
+        )
+        .first::<SbFamilie>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get dataset by primary key.
+pub fn get2(db: &DbContext, b: &SbFamilie) -> Result<Option<SbFamilie>> {
+    let p = SB_FAMILIE::table
+        .filter(
+            SB_FAMILIE::mandant_nr
+                .eq(b.mandant_nr)
+                .and(SB_FAMILIE::uid.eq(b.uid.clone())),
+        )
+        .first::<SbFamilie>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get list.
+#[allow(dead_code)]
+pub fn get_list(db: &DbContext, mandant_nr_: i32) -> Result<Vec<SbFamilie>> {
+    let list = SB_FAMILIE::table
+        .filter(SB_FAMILIE::mandant_nr.eq(mandant_nr_))
+        .load::<SbFamilie>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
+
+/// Insert a dataset.
+pub fn insert<'a>(db: &mut DbContext, b: &'a SbFamilie) -> Result<&'a SbFamilie> {
+    let rows = diesel::insert_into(SB_FAMILIE::table)
+        .values(b)
+        .execute(db.c)?;
+    if rows <= 0 {
+        return Err(RsbpError::NotFound);
+    }
+    db.ul.add(&UndoEntry::sb_familie(None, Some(b)));
+    Ok(b)
+}
+
+/// Update a dataset.
+pub fn update<'a>(db: &mut DbContext, b: &'a SbFamilie) -> Result<&'a SbFamilie> {
+    let oo = get2(&db, b)?;
+    let rows = diesel::update(
+        SB_FAMILIE::table.filter(
+            SB_FAMILIE::mandant_nr
+                .eq(b.mandant_nr)
+                .and(SB_FAMILIE::uid.eq(b.uid.clone())),
+        ),
+    )
+    .set((
+        SB_FAMILIE::mann_uid.eq(b.mann_uid.as_ref()),
+        SB_FAMILIE::frau_uid.eq(b.frau_uid.as_ref()),
+        SB_FAMILIE::status1.eq(b.status1),
+        SB_FAMILIE::status2.eq(b.status2),
+        SB_FAMILIE::status3.eq(b.status3),
+        SB_FAMILIE::angelegt_von.eq(b.angelegt_von.as_ref()),
+        SB_FAMILIE::angelegt_am.eq(b.angelegt_am),
+        SB_FAMILIE::geaendert_von.eq(b.geaendert_von.as_ref()),
+        SB_FAMILIE::geaendert_am.eq(b.geaendert_am),
+    ))
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::sb_familie(Some(&o), Some(b)));
+    }
+    Ok(b)
+}
+
+/// Delete a dataset.
+pub fn delete(db: &mut DbContext, b: &SbFamilie) -> Result<()> {
+    let oo = get2(db, b)?;
+    let rows = diesel::delete(
+        SB_FAMILIE::table.filter(
+            SB_FAMILIE::mandant_nr
+                .eq(b.mandant_nr)
+                .and(SB_FAMILIE::uid.eq(b.uid.clone())),
+        ),
+    )
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::sb_familie(Some(&o), None));
+    }
+    Ok(())
+}
+
+/// Get list of families with a person as father or mother.
+pub fn get_list_ext(db: &DbContext, person_uid_: &String) -> Result<Vec<SbFamilie>> {
+    let list = SB_FAMILIE::table
+        .filter(
+            SB_FAMILIE::mandant_nr.eq(db.daten.mandant_nr).and(
+                SB_FAMILIE::mann_uid
+                    .eq(person_uid_)
+                    .or(SB_FAMILIE::frau_uid.eq(person_uid_)),
+            ),
+        )
+        .order(SB_FAMILIE::uid)
+        .load::<SbFamilie>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
@@ -0,0 +1,244 @@
+use super::DbContext;
+use crate::{config::RsbpError, services::undo::UndoEntry, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use rsbp_rep::{models::SbKind, schema::*};
+
+/// Undo a dataset.
+pub fn undo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<SbKind>(or)?;
+    let oa = UndoEntry::from_str::<SbKind>(ac)?;
+    if let (Some(o), Some(_a)) = (&oo, &oa) {
+        // Update
+        update(db, o)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        delete(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        insert(db, o)?;
+    }
+    Ok(())
+}
+
+/// Redo a dataset.
+pub fn redo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<SbKind>(or)?;
+    let oa = UndoEntry::from_str::<SbKind>(ac)?;
+    if let (Some(_o), Some(a)) = (&oo, &oa) {
+        // Update
+        update(db, a)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        insert(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        delete(db, o)?;
+    }
+    Ok(())
+}
+
+/// Save dataset with all values.
+#[allow(dead_code)]
+pub fn save0(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    familie_uid_: &String,
+    kind_uid_: &String,
+    angelegt_von_: &Option<String>,
+    angelegt_am_: &Option<NaiveDateTime>,
+    geaendert_von_: &Option<String>,
+    geaendert_am_: &Option<NaiveDateTime>,
+    replikation_uid_: &Option<String>,
+) -> Result<SbKind> {
+    let op = SB_KIND::table
+        .filter(
+            SB_KIND::mandant_nr
+                .eq(mandant_nr_)
+                .and(SB_KIND::familie_uid.eq(familie_uid_.clone()))
+                .and(SB_KIND::kind_uid.eq(kind_uid_.clone())),
+        )
+        .first::<SbKind>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    let mut p = SbKind {
+        mandant_nr: *mandant_nr_,
+        familie_uid: familie_uid_.clone(),
+        kind_uid: kind_uid_.clone(),
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+        replikation_uid: replikation_uid_.clone(),
+    };
+    if let Some(pu) = op {
+        if p != pu {
+            p.angelegt_von = pu.angelegt_von;
+            p.angelegt_am = pu.angelegt_am;
+            p.geaendert_von = pu.geaendert_von;
+            p.geaendert_am = pu.geaendert_am;
+            if p.angelegt_von.is_none() || !angelegt_von_.is_none() {
+                super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+            }
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+            update(db, &p)?;
+        }
+    } else {
+        super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+        if !geaendert_von_.is_none() {
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+        }
+        insert(db, &p)?;
+    }
+    return Ok(p);
+}
+
+/// Save dataset without revision columns.
+#[allow(dead_code)]
+pub fn save(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    familie_uid_: &String,
+    kind_uid_: &String,
+) -> Result<SbKind> {
+    save0(
+        db,
+        mandant_nr_,
+        familie_uid_,
+        kind_uid_,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    )
+}
+
+/// Get dataset by primary key.
+#[allow(dead_code)]
+pub fn get(
+    db: &DbContext,
+    mandant_nr_: &i32,
+    familie_uid_: &String,
+    kind_uid_: &String,
+) -> Result<Option<SbKind>> {
+    let p = SB_KIND::table
+        .filter(
+            SB_KIND::mandant_nr
+                .eq(mandant_nr_)
+                .and(SB_KIND::familie_uid.eq(familie_uid_.clone()))
+                .and(SB_KIND::kind_uid.eq(kind_uid_.clone())),
+        )
+        .first::<SbKind>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get dataset by primary key.
+pub fn get2(db: &DbContext, b: &SbKind) -> Result<Option<SbKind>> {
+    let p = SB_KIND::table
+        .filter(
+            SB_KIND::mandant_nr
+                .eq(b.mandant_nr)
+                .and(SB_KIND::familie_uid.eq(b.familie_uid.clone()))
+                .and(SB_KIND::kind_uid.eq(b.kind_uid.clone())),
+        )
+        .first::<SbKind>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get list.
+#[allow(dead_code)]
+pub fn get_list(db: &DbContext, mandant_nr_: i32) -> Result<Vec<SbKind>> {
+    let list = SB_KIND::table
+        .filter(SB_KIND::mandant_nr.eq(mandant_nr_))
+        .load::<SbKind>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
+
+/// Insert a dataset.
+pub fn insert<'a>(db: &mut DbContext, b: &'a SbKind) -> Result<&'a SbKind> {
+    let rows = diesel::insert_into(SB_KIND::table)
+        .values(b)
+        .execute(db.c)?;
+    if rows <= 0 {
+        return Err(RsbpError::NotFound);
+    }
+    db.ul.add(&UndoEntry::sb_kind(None, Some(b)));
+    Ok(b)
+}
+
+/// Update a dataset.
+pub fn update<'a>(db: &mut DbContext, b: &'a SbKind) -> Result<&'a SbKind> {
+    let oo = get2(&db, b)?;
+    let rows = diesel::update(
+        SB_KIND::table.filter(
+            SB_KIND::mandant_nr
+                .eq(b.mandant_nr)
+                .and(SB_KIND::familie_uid.eq(b.familie_uid.clone()))
+                .and(SB_KIND::kind_uid.eq(b.kind_uid.clone())),
+        ),
+    )
+    .set((
+        SB_KIND::angelegt_von.eq(b.angelegt_von.as_ref()),
+        SB_KIND::angelegt_am.eq(b.angelegt_am),
+        SB_KIND::geaendert_von.eq(b.geaendert_von.as_ref()),
+        SB_KIND::geaendert_am.eq(b.geaendert_am),
+        SB_KIND::replikation_uid.eq(b.replikation_uid.as_ref()),
+    ))
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::sb_kind(Some(&o), Some(b)));
+    }
+    Ok(b)
+}
+
+/// Delete a dataset.
+pub fn delete(db: &mut DbContext, b: &SbKind) -> Result<()> {
+    let oo = get2(db, b)?;
+    let rows = diesel::delete(
+        SB_KIND::table.filter(
+            SB_KIND::mandant_nr
+                .eq(b.mandant_nr)
+                .and(SB_KIND::familie_uid.eq(b.familie_uid.clone()))
+                .and(SB_KIND::kind_uid.eq(b.kind_uid.clone())),
+        ),
+    )
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::sb_kind(Some(&o), None));
+    }
+    Ok(())
+}
+
+/// Get list of children filtered by family and child.
+pub fn get_list_ext(
+    db: &DbContext,
+    familie_uid_: Option<&String>,
+    kind_uid_: Option<&String>,
+) -> Result<Vec<SbKind>> {
+    let mut q = SB_KIND::table
+        .into_boxed()
+        .filter(SB_KIND::mandant_nr.eq(db.daten.mandant_nr));
+    if let Some(f) = familie_uid_ {
+        q = q.filter(SB_KIND::familie_uid.eq(f));
+    }
+    if let Some(k) = kind_uid_ {
+        q = q.filter(SB_KIND::kind_uid.eq(k));
+    }
+    let list = q
+        .order((SB_KIND::familie_uid, SB_KIND::kind_uid))
+        .load::<SbKind>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
@@ -0,0 +1,273 @@
+use super::DbContext;
+use crate::{config::RsbpError, services::undo::UndoEntry, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use rsbp_rep::{models::SbPerson, schema::*};
+
+/// Undo a dataset.
+pub fn undo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<SbPerson>(or)?;
+    let oa = UndoEntry::from_str::<SbPerson>(ac)?;
+    if let (Some(o), Some(_a)) = (&oo, &oa) {
+        // Update
+        update(db, o)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        delete(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        insert(db, o)?;
+    }
+    Ok(())
+}
+
+/// Redo a dataset.
+pub fn redo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<SbPerson>(or)?;
+    let oa = UndoEntry::from_str::<SbPerson>(ac)?;
+    if let (Some(_o), Some(a)) = (&oo, &oa) {
+        // Update
+        update(db, a)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        insert(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        delete(db, o)?;
+    }
+    Ok(())
+}
+
+/// Save dataset with all values.
+#[allow(dead_code)]
+pub fn save0(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    name_: &String,
+    vorname_: &Option<String>,
+    geburtsname_: &Option<String>,
+    geschlecht_: &Option<String>,
+    titel_: &Option<String>,
+    konfession_: &Option<String>,
+    bemerkung_: &Option<String>,
+    quelle_uid_: &Option<String>,
+    status1_: &i32,
+    status2_: &i32,
+    status3_: &i32,
+    angelegt_von_: &Option<String>,
+    angelegt_am_: &Option<NaiveDateTime>,
+    geaendert_von_: &Option<String>,
+    geaendert_am_: &Option<NaiveDateTime>,
+) -> Result<SbPerson> {
+    let op = SB_PERSON::table
+        .filter(
+            SB_PERSON::mandant_nr
+                .eq(mandant_nr_)
+                .and(SB_PERSON::uid.eq(uid_.clone())),
+        )
+        .first::<SbPerson>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    let mut p = SbPerson {
+        mandant_nr: *mandant_nr_,
+        uid: uid_.clone(),
+        name: name_.clone(),
+        vorname: vorname_.clone(),
+        geburtsname: geburtsname_.clone(),
+        geschlecht: geschlecht_.clone(),
+        titel: titel_.clone(),
+        konfession: konfession_.clone(),
+        bemerkung: bemerkung_.clone(),
+        quelle_uid: quelle_uid_.clone(),
+        status1: *status1_,
+        status2: *status2_,
+        status3: *status3_,
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+    };
+    if let Some(pu) = op {
+        if p != pu {
+            p.angelegt_von = pu.angelegt_von;
+            p.angelegt_am = pu.angelegt_am;
+            p.geaendert_von = pu.geaendert_von;
+            p.geaendert_am = pu.geaendert_am;
+            if p.angelegt_von.is_none() || !angelegt_von_.is_none() {
+                super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+            }
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+            update(db, &p)?;
+        }
+    } else {
+        super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+        if !geaendert_von_.is_none() {
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+        }
+        insert(db, &p)?;
+    }
+    return Ok(p);
+}
+
+/// Save dataset without revision columns.
+#[allow(dead_code)]
+pub fn save(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    name_: &String,
+    vorname_: &Option<String>,
+    geburtsname_: &Option<String>,
+    geschlecht_: &Option<String>,
+    titel_: &Option<String>,
+    konfession_: &Option<String>,
+    bemerkung_: &Option<String>,
+    quelle_uid_: &Option<String>,
+    status1_: &i32,
+    status2_: &i32,
+    status3_: &i32,
+) -> Result<SbPerson> {
+    save0(
+        db,
+        mandant_nr_,
+        uid_,
+        name_,
+        vorname_,
+        geburtsname_,
+        geschlecht_,
+        titel_,
+        konfession_,
+        bemerkung_,
+        quelle_uid_,
+        status1_,
+        status2_,
+        status3_,
+        &None,
+        &None,
+        &None,
+        &None,
+    )
+}
+
+/// Get dataset by primary key.
+#[allow(dead_code)]
+pub fn get(db: &DbContext, mandant_nr_: &i32, uid_: &String) -> Result<Option<SbPerson>> {
+    let p = SB_PERSON::table
+        .filter(
+            SB_PERSON::mandant_nr
+                .eq(mandant_nr_)
+                .and(SB_PERSON::uid.eq(uid_.clone())),
+        )
+        .first::<SbPerson>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get dataset by primary key.
+pub fn get2(db: &DbContext, b: &SbPerson) -> Result<Option<SbPerson>> {
+    let p = SB_PERSON::table
+        .filter(
+            SB_PERSON::mandant_nr
+                .eq(b.mandant_nr)
+                .and(SB_PERSON::uid.eq(b.uid.clone())),
+        )
+        .first::<SbPerson>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get list.
+#[allow(dead_code)]
+pub fn get_list(db: &DbContext, mandant_nr_: i32) -> Result<Vec<SbPerson>> {
+    let list = SB_PERSON::table
+        .filter(SB_PERSON::mandant_nr.eq(mandant_nr_))
+        .load::<SbPerson>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
+
+/// Insert a dataset.
+pub fn insert<'a>(db: &mut DbContext, b: &'a SbPerson) -> Result<&'a SbPerson> {
+    let rows = diesel::insert_into(SB_PERSON::table)
+        .values(b)
+        .execute(db.c)?;
+    if rows <= 0 {
+        return Err(RsbpError::NotFound);
+    }
+    db.ul.add(&UndoEntry::sb_person(None, Some(b)));
+    Ok(b)
+}
+
+/// Update a dataset.
+pub fn update<'a>(db: &mut DbContext, b: &'a SbPerson) -> Result<&'a SbPerson> {
+    let oo = get2(&db, b)?;
+    let rows = diesel::update(
+        SB_PERSON::table.filter(
+            SB_PERSON::mandant_nr
+                .eq(b.mandant_nr)
+                .and(SB_PERSON::uid.eq(b.uid.clone())),
+        ),
+    )
+    .set((
+        SB_PERSON::name.eq(b.name.as_str()),
+        SB_PERSON::vorname.eq(b.vorname.as_ref()),
+        SB_PERSON::geburtsname.eq(b.geburtsname.as_ref()),
+        SB_PERSON::geschlecht.eq(b.geschlecht.as_ref()),
+        SB_PERSON::titel.eq(b.titel.as_ref()),
+        SB_PERSON::konfession.eq(b.konfession.as_ref()),
+        SB_PERSON::bemerkung.eq(b.bemerkung.as_ref()),
+        SB_PERSON::quelle_uid.eq(b.quelle_uid.as_ref()),
+        SB_PERSON::status1.eq(b.status1),
+        SB_PERSON::status2.eq(b.status2),
+        SB_PERSON::status3.eq(b.status3),
+        SB_PERSON::angelegt_von.eq(b.angelegt_von.as_ref()),
+        SB_PERSON::angelegt_am.eq(b.angelegt_am),
+        SB_PERSON::geaendert_von.eq(b.geaendert_von.as_ref()),
+        SB_PERSON::geaendert_am.eq(b.geaendert_am),
+    ))
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::sb_person(Some(&o), Some(b)));
+    }
+    Ok(b)
+}
+
+/// Delete a dataset.
+pub fn delete(db: &mut DbContext, b: &SbPerson) -> Result<()> {
+    let oo = get2(db, b)?;
+    let rows = diesel::delete(
+        SB_PERSON::table.filter(
+            SB_PERSON::mandant_nr
+                .eq(b.mandant_nr)
+                .and(SB_PERSON::uid.eq(b.uid.clone())),
+        ),
+    )
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::sb_person(Some(&o), None));
+    }
+    Ok(())
+}
+
+/// Get list of ancestors citing a source.
+pub fn get_list_source(db: &DbContext, quelle_uid_: &String) -> Result<Vec<SbPerson>> {
+    let list = SB_PERSON::table
+        .filter(
+            SB_PERSON::mandant_nr
+                .eq(db.daten.mandant_nr)
+                .and(SB_PERSON::quelle_uid.eq(quelle_uid_)),
+        )
+        .order((SB_PERSON::name, SB_PERSON::vorname, SB_PERSON::uid))
+        .load::<SbPerson>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
@@ -0,0 +1,249 @@
+use super::DbContext;
+use crate::{config::RsbpError, services::undo::UndoEntry, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use rsbp_rep::{models::SbQuelle, schema::*};
+
+/// Undo a dataset.
+pub fn undo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<SbQuelle>(or)?;
+    let oa = UndoEntry::from_str::<SbQuelle>(ac)?;
+    if let (Some(o), Some(_a)) = (&oo, &oa) {
+        // Update
+        update(db, o)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        delete(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        insert(db, o)?;
+    }
+    Ok(())
+}
+
+/// Redo a dataset.
+pub fn redo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<SbQuelle>(or)?;
+    let oa = UndoEntry::from_str::<SbQuelle>(ac)?;
+    if let (Some(_o), Some(a)) = (&oo, &oa) {
+        // Update
+        update(db, a)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        insert(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        delete(db, o)?;
+    }
+    Ok(())
+}
+
+/// Save dataset with all values.
+#[allow(dead_code)]
+pub fn save0(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    beschreibung_: &String,
+    zitat_: &Option<String>,
+    bemerkung_: &Option<String>,
+    autor_: &String,
+    status1_: &i32,
+    status2_: &i32,
+    status3_: &i32,
+    angelegt_von_: &Option<String>,
+    angelegt_am_: &Option<NaiveDateTime>,
+    geaendert_von_: &Option<String>,
+    geaendert_am_: &Option<NaiveDateTime>,
+) -> Result<SbQuelle> {
+    let op = SB_QUELLE::table
+        .filter(
+            SB_QUELLE::mandant_nr
+                .eq(mandant_nr_)
+                .and(SB_QUELLE::uid.eq(uid_.clone())),
+        )
+        .first::<SbQuelle>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    let mut p = SbQuelle {
+        mandant_nr: *mandant_nr_,
+        uid: uid_.clone(),
+        beschreibung: beschreibung_.clone(),
+        zitat: zitat_.clone(),
+        bemerkung: bemerkung_.clone(),
+        autor: autor_.clone(),
+        status1: *status1_,
+        status2: *status2_,
+        status3: *status3_,
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+    };
+    if let Some(pu) = op {
+        if p != pu {
+            p.angelegt_von = pu.angelegt_von;
+            p.angelegt_am = pu.angelegt_am;
+            p.geaendert_von = pu.geaendert_von;
+            p.geaendert_am = pu.geaendert_am;
+            if p.angelegt_von.is_none() || !angelegt_von_.is_none() {
+                super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+            }
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+            update(db, &p)?;
+        }
+    } else {
+        super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+        if !geaendert_von_.is_none() {
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+        }
+        insert(db, &p)?;
+    }
+    return Ok(p);
+}
+
+/// Save dataset without revision columns.
+#[allow(dead_code)]
+pub fn save(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    uid_: &String,
+    beschreibung_: &String,
+    zitat_: &Option<String>,
+    bemerkung_: &Option<String>,
+    autor_: &String,
+    status1_: &i32,
+    status2_: &i32,
+    status3_: &i32,
+) -> Result<SbQuelle> {
+    save0(
+        db,
+        mandant_nr_,
+        uid_,
+        beschreibung_,
+        zitat_,
+        bemerkung_,
+        autor_,
+        status1_,
+        status2_,
+        status3_,
+        &None,
+        &None,
+        &None,
+        &None,
+    )
+}
+
+/// Get dataset by primary key.
+#[allow(dead_code)]
+pub fn get(db: &DbContext, mandant_nr_: &i32, uid_: &String) -> Result<Option<SbQuelle>> {
+    let p = SB_QUELLE::table
+        .filter(
+            SB_QUELLE::mandant_nr
+                .eq(mandant_nr_)
+                .and(SB_QUELLE::uid.eq(uid_.clone())),
+        )
+        .first::<SbQuelle>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get dataset by primary key.
+pub fn get2(db: &DbContext, b: &SbQuelle) -> Result<Option<SbQuelle>> {
+    let p = SB_QUELLE::table
+        .filter(
+            SB_QUELLE::mandant_nr
+                .eq(b.mandant_nr)
+                .and(SB_QUELLE::uid.eq(b.uid.clone())),
+        )
+        .first::<SbQuelle>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get list.
+#[allow(dead_code)]
+pub fn get_list(db: &DbContext, mandant_nr_: i32) -> Result<Vec<SbQuelle>> {
+    let list = SB_QUELLE::table
+        .filter(SB_QUELLE::mandant_nr.eq(mandant_nr_))
+        .load::<SbQuelle>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
+
+/// Insert a dataset.
+pub fn insert<'a>(db: &mut DbContext, b: &'a SbQuelle) -> Result<&'a SbQuelle> {
+    let rows = diesel::insert_into(SB_QUELLE::table)
+        .values(b)
+        .execute(db.c)?;
+    if rows <= 0 {
+        return Err(RsbpError::NotFound);
+    }
+    db.ul.add(&UndoEntry::sb_quelle(None, Some(b)));
+    Ok(b)
+}
+
+/// Update a dataset.
+pub fn update<'a>(db: &mut DbContext, b: &'a SbQuelle) -> Result<&'a SbQuelle> {
+    let oo = get2(&db, b)?;
+    let rows = diesel::update(
+        SB_QUELLE::table.filter(
+            SB_QUELLE::mandant_nr
+                .eq(b.mandant_nr)
+                .and(SB_QUELLE::uid.eq(b.uid.clone())),
+        ),
+    )
+    .set((
+        SB_QUELLE::beschreibung.eq(b.beschreibung.as_str()),
+        SB_QUELLE::zitat.eq(b.zitat.as_ref()),
+        SB_QUELLE::bemerkung.eq(b.bemerkung.as_ref()),
+        SB_QUELLE::autor.eq(b.autor.as_str()),
+        SB_QUELLE::status1.eq(b.status1),
+        SB_QUELLE::status2.eq(b.status2),
+        SB_QUELLE::status3.eq(b.status3),
+        SB_QUELLE::angelegt_von.eq(b.angelegt_von.as_ref()),
+        SB_QUELLE::angelegt_am.eq(b.angelegt_am),
+        SB_QUELLE::geaendert_von.eq(b.geaendert_von.as_ref()),
+        SB_QUELLE::geaendert_am.eq(b.geaendert_am),
+    ))
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::sb_quelle(Some(&o), Some(b)));
+    }
+    Ok(b)
+}
+
+/// Delete a dataset.
+pub fn delete(db: &mut DbContext, b: &SbQuelle) -> Result<()> {
+    let oo = get2(db, b)?;
+    let rows = diesel::delete(
+        SB_QUELLE::table.filter(
+            SB_QUELLE::mandant_nr
+                .eq(b.mandant_nr)
+                .and(SB_QUELLE::uid.eq(b.uid.clone())),
+        ),
+    )
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::sb_quelle(Some(&o), None));
+    }
+    Ok(())
+}
+
+/// Get list ordered by author and description.
+pub fn get_list_ext(db: &DbContext) -> Result<Vec<SbQuelle>> {
+    let list = SB_QUELLE::table
+        .filter(SB_QUELLE::mandant_nr.eq(db.daten.mandant_nr))
+        .order((SB_QUELLE::autor, SB_QUELLE::beschreibung, SB_QUELLE::uid))
+        .load::<SbQuelle>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
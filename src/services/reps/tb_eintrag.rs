@@ -7,9 +7,9 @@ use chrono::{NaiveDate, NaiveDateTime};
 use diesel::{
     prelude::*,
     sql_query,
-    sql_types::{Date, Integer, Text},
+    sql_types::{BigInt, Date, Integer, Text},
 };
-use rsbp_rep::{models::TbEintrag, schema::*};
+use rsbp_rep::{models::TbEintrag, models_ext::TbEintragFts, schema::*};
 
 /// Undo a dataset.
 pub fn undo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
@@ -261,18 +261,7 @@ pub fn get_list_search(
     let mut fromsql = db.daten.get_today();
     let mut to1 = 0;
     let mut tosql = db.daten.get_today();
-    let mut puid1 = 0;
-    let mut puidsql = "".to_string();
-    let mut subsql = " AND (0=? OR a.angelegt_von=?)".to_string();
-    if let Some(uid) = puid {
-        if uid == "0" {
-            subsql = " AND NOT EXISTS(SELECT * FROM TB_Eintrag_Ort b WHERE a.mandant_nr=b.mandant_nr AND b.datum_von<=a.datum AND a.datum<=b.datum_bis AND (0=? OR b.angelegt_von=?))".to_string();
-        } else {
-            puid1 = 1;
-            puidsql = uid.to_string();
-            subsql = " AND EXISTS(SELECT * FROM TB_Eintrag_Ort b WHERE a.mandant_nr=b.mandant_nr AND (0=? OR b.ort_uid=?) AND b.datum_von<=a.datum AND a.datum<=b.datum_bis)".to_string();
-        }
-    }
+    let (puid1, puidsql, subsql) = get_position_sql(puid);
     let sql = format!( "SELECT a.mandant_nr mandant_nr, a.datum datum, a.eintrag eintrag, a.angelegt_von angelegt_von, a.angelegt_am angelegt_am, a.geaendert_von geaendert_von, a.geaendert_am geaendert_am, a.replikation_uid replikation_uid
       FROM TB_Eintrag a WHERE a.mandant_nr=? AND (0=? OR a.datum<?) AND (0=? OR a.datum>?) AND (0=? OR a.datum>=?) AND (0=? OR a.datum<=?){}
        AND ((0=? OR a.eintrag like ?) OR (0=? OR a.eintrag like ?) OR (0=? OR a.eintrag like ?))
@@ -317,6 +306,7 @@ pub fn get_list_search(
         .bind::<Integer, _>(to1)
         .bind::<Date, _>(tosql)
         .bind::<Integer, _>(puid1)
+        .bind::<Text, _>(puidsql.to_string())
         .bind::<Text, _>(puidsql)
         .bind::<Integer, _>(search1)
         .bind::<Text, _>(search[0].to_string())
@@ -340,3 +330,173 @@ pub fn get_list_search(
         .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
     Ok(list)
 }
+
+/// Get the SQL condition for position filtering with 3 parameters.
+/// * puid: Affected position uid, person uid for positions linked to the person's addresses
+///   or "0" for entries without position.
+/// * returns: Flag and uid parameter and SQL condition.
+fn get_position_sql(puid: &Option<String>) -> (i32, String, String) {
+    let mut puid1 = 0;
+    let mut puidsql = "".to_string();
+    let mut subsql = " AND (0=? OR a.angelegt_von=? OR a.angelegt_von=?)".to_string();
+    if let Some(uid) = puid {
+        if uid == "0" {
+            subsql = " AND NOT EXISTS(SELECT * FROM TB_Eintrag_Ort b WHERE a.mandant_nr=b.mandant_nr AND b.datum_von<=a.datum AND a.datum<=b.datum_bis AND (0=? OR b.angelegt_von=? OR b.angelegt_von=?))".to_string();
+        } else {
+            // Position or person with positions linked to the addresses of the person.
+            puid1 = 1;
+            puidsql = uid.to_string();
+            subsql = " AND EXISTS(SELECT * FROM TB_Eintrag_Ort b WHERE a.mandant_nr=b.mandant_nr AND (0=? OR b.ort_uid=? OR b.ort_uid IN (SELECT d.ort_uid FROM TB_Ort_Adresse d, AD_Sitz s WHERE d.mandant_nr=a.mandant_nr AND s.mandant_nr=d.mandant_nr AND s.adresse_uid=d.adresse_uid AND s.person_uid=?)) AND b.datum_von<=a.datum AND a.datum<=b.datum_bis)".to_string();
+        }
+    }
+    (puid1, puidsql, subsql)
+}
+
+/// Name of the full-text index of the diary entries.
+const FTS: &str = "TB_EINTRAG_FTS";
+
+/// Get the SQL expression for the row ID of an entry in the full-text index.
+/// * t: Affected table or trigger alias of the entry.
+fn get_fts_rowid(t: &str) -> String {
+    format!(
+        "{0}.mandant_nr*1000000+CAST(julianday({0}.datum)-1721424.5 AS INTEGER)",
+        t
+    )
+}
+
+/// Does the full-text index exist?
+fn has_fts(db: &DbContext) -> Result<bool> {
+    let rows = diesel::select(diesel::dsl::sql::<BigInt>(
+        format!(
+            "(SELECT count(*) FROM sqlite_master WHERE type='table' AND name='{}')",
+            FTS
+        )
+        .as_str(),
+    ))
+    .get_result::<i64>(db.c)
+    .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(rows > 0)
+}
+
+/// Create the full-text index if necessary and fill it with all entries.
+/// Triggers keep the index up to date with all changes of the diary entries.
+/// * rebuild: Should an existing index be filled again?
+/// * returns: Number of entries in the index.
+pub fn create_fts(db: &DbContext, rebuild: bool) -> Result<usize> {
+    let exists = has_fts(db)?;
+    let insert = format!(
+        "INSERT INTO {0}(rowid, eintrag, mandant_nr, datum) VALUES ({1}, new.eintrag, new.mandant_nr, new.datum);",
+        FTS,
+        get_fts_rowid("new")
+    );
+    let delete = format!("DELETE FROM {} WHERE rowid={};", FTS, get_fts_rowid("old"));
+    let mut sqls = vec![
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS {0}_AI AFTER INSERT ON TB_EINTRAG BEGIN {1} END",
+            FTS, insert
+        ),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS {0}_AU AFTER UPDATE ON TB_EINTRAG BEGIN {1} {2} END",
+            FTS, delete, insert
+        ),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS {0}_AD AFTER DELETE ON TB_EINTRAG BEGIN {1} END",
+            FTS, delete
+        ),
+    ];
+    if !exists {
+        sqls.insert(0, format!("CREATE VIRTUAL TABLE {} USING fts5(eintrag, mandant_nr UNINDEXED, datum UNINDEXED, tokenize='unicode61 remove_diacritics 2')", FTS));
+    }
+    if !exists || rebuild {
+        sqls.push(format!("DELETE FROM {}", FTS));
+        sqls.push(format!(
+            "INSERT INTO {}(rowid, eintrag, mandant_nr, datum) SELECT {}, eintrag, mandant_nr, datum FROM TB_EINTRAG",
+            FTS,
+            get_fts_rowid("TB_EINTRAG")
+        ));
+    }
+    for sql in sqls {
+        sql_query(sql)
+            .execute(db.c)
+            .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    }
+    let rows = diesel::select(diesel::dsl::sql::<BigInt>(
+        format!("(SELECT count(*) FROM {})", FTS).as_str(),
+    ))
+    .get_result::<i64>(db.c)
+    .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(rows as usize)
+}
+
+/// Get list by full-text search ordered by relevance.
+/// * query: Affected FTS5 query with phrases in quotes and prefixes with asterisk.
+/// * search: Affected search patterns of get_list_search.
+pub fn get_list_fts(
+    db: &DbContext,
+    query: &str,
+    search: &[String; 9],
+    puid: &Option<String>,
+    from: &Option<NaiveDate>,
+    to: &Option<NaiveDate>,
+) -> Result<Vec<TbEintragFts>> {
+    let mut from1 = 0;
+    let mut fromsql = db.daten.get_today();
+    let mut to1 = 0;
+    let mut tosql = db.daten.get_today();
+    let (puid1, puidsql, subsql) = get_position_sql(puid);
+    let sql = format!( "SELECT a.datum datum, a.eintrag eintrag, snippet({0}, 0, '[', ']', '...', 16) snippet, bm25({0}) score
+      FROM {0} f JOIN TB_Eintrag a ON a.mandant_nr=f.mandant_nr AND a.datum=f.datum
+      WHERE {0} MATCH ? AND f.mandant_nr=? AND (0=? OR a.datum>=?) AND (0=? OR a.datum<=?){1}
+       AND ((0=? OR a.eintrag like ?) OR (0=? OR a.eintrag like ?) OR (0=? OR a.eintrag like ?))
+       AND ((0=? OR a.eintrag like ?) OR (0=? OR a.eintrag like ?) OR (0=? OR a.eintrag like ?))
+       AND (0=? OR NOT a.eintrag like ?) AND (0=? OR NOT a.eintrag like ?) AND (0=? OR NOT a.eintrag like ?)
+      ORDER BY score, a.datum", FTS, subsql);
+    if let Some(d0) = from {
+        from1 = 1;
+        fromsql = *d0;
+    }
+    if let Some(d0) = to {
+        to1 = 1;
+        tosql = *d0;
+    }
+    let search1 = functions::iif_i32(search[0].is_empty(), 0, 1);
+    let search2 = functions::iif_i32(search[1].is_empty() && search1 == 0, 0, 1);
+    let search3 = functions::iif_i32(search[2].is_empty() && search2 == 0, 0, 1);
+    let search4 = functions::iif_i32(search[3].is_empty(), 0, 1);
+    let search5 = functions::iif_i32(search[4].is_empty() && search4 == 0, 0, 1);
+    let search6 = functions::iif_i32(search[5].is_empty() && search5 == 0, 0, 1);
+    let search7 = functions::iif_i32(search[6].is_empty(), 0, 1);
+    let search8 = functions::iif_i32(search[7].is_empty(), 0, 1);
+    let search9 = functions::iif_i32(search[8].is_empty(), 0, 1);
+    let list = sql_query(sql)
+        .bind::<Text, _>(query)
+        .bind::<Integer, _>(db.daten.mandant_nr)
+        .bind::<Integer, _>(from1)
+        .bind::<Date, _>(fromsql)
+        .bind::<Integer, _>(to1)
+        .bind::<Date, _>(tosql)
+        .bind::<Integer, _>(puid1)
+        .bind::<Text, _>(puidsql.to_string())
+        .bind::<Text, _>(puidsql)
+        .bind::<Integer, _>(search1)
+        .bind::<Text, _>(search[0].to_string())
+        .bind::<Integer, _>(search2)
+        .bind::<Text, _>(search[1].to_string())
+        .bind::<Integer, _>(search3)
+        .bind::<Text, _>(search[2].to_string())
+        .bind::<Integer, _>(search4)
+        .bind::<Text, _>(search[3].to_string())
+        .bind::<Integer, _>(search5)
+        .bind::<Text, _>(search[4].to_string())
+        .bind::<Integer, _>(search6)
+        .bind::<Text, _>(search[5].to_string())
+        .bind::<Integer, _>(search7)
+        .bind::<Text, _>(search[6].to_string())
+        .bind::<Integer, _>(search8)
+        .bind::<Text, _>(search[7].to_string())
+        .bind::<Integer, _>(search9)
+        .bind::<Text, _>(search[8].to_string())
+        .load::<TbEintragFts>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
@@ -233,26 +233,7 @@ pub fn delete(db: &mut DbContext, b: &TbEintragOrt) -> Result<()> {
 
 /// Get list.
 pub fn get_list_ext2(db: &DbContext, date: &NaiveDate) -> Result<Vec<TbEintragOrtExt>> {
-    let join = TB_EINTRAG_ORT::table
-        .filter(
-            TB_EINTRAG_ORT::mandant_nr.eq(db.daten.mandant_nr).and(
-                TB_EINTRAG_ORT::datum_von
-                    .le(date)
-                    .and(TB_EINTRAG_ORT::datum_bis.ge(date)),
-            ),
-        )
-        .inner_join(
-            TB_ORT::table.on(TB_ORT::mandant_nr
-                .eq(TB_EINTRAG_ORT::mandant_nr)
-                .and(TB_ORT::uid.eq(TB_EINTRAG_ORT::ort_uid))),
-        )
-        .order_by((
-            TB_EINTRAG_ORT::mandant_nr,
-            TB_EINTRAG_ORT::ort_uid,
-            TB_EINTRAG_ORT::datum_von,
-        ))
-        .load::<(TbEintragOrt, TbOrt)>(db.c)
-        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    let join = get_list_join(db, date, date)?;
     let mut l: Vec<TbEintragOrtExt> = Vec::new();
     for j in join {
         l.push(TbEintragOrtExt {
@@ -274,6 +255,35 @@ pub fn get_list_ext2(db: &DbContext, date: &NaiveDate) -> Result<Vec<TbEintragOr
     Ok(l)
 }
 
+/// Get list of links with positions which overlap a period.
+pub fn get_list_join(
+    db: &DbContext,
+    from: &NaiveDate,
+    to: &NaiveDate,
+) -> Result<Vec<(TbEintragOrt, TbOrt)>> {
+    let join = TB_EINTRAG_ORT::table
+        .filter(
+            TB_EINTRAG_ORT::mandant_nr.eq(db.daten.mandant_nr).and(
+                TB_EINTRAG_ORT::datum_von
+                    .le(to)
+                    .and(TB_EINTRAG_ORT::datum_bis.ge(from)),
+            ),
+        )
+        .inner_join(
+            TB_ORT::table.on(TB_ORT::mandant_nr
+                .eq(TB_EINTRAG_ORT::mandant_nr)
+                .and(TB_ORT::uid.eq(TB_EINTRAG_ORT::ort_uid))),
+        )
+        .order_by((
+            TB_EINTRAG_ORT::mandant_nr,
+            TB_EINTRAG_ORT::ort_uid,
+            TB_EINTRAG_ORT::datum_von,
+        ))
+        .load::<(TbEintragOrt, TbOrt)>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(join)
+}
+
 /// Get list.
 pub fn get_list_ext(
     db: &DbContext,
@@ -0,0 +1,231 @@
+use super::DbContext;
+use crate::{config::RsbpError, services::undo::UndoEntry, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use rsbp_rep::{models::TbOrtAdresse, schema::*};
+
+/// Undo a dataset.
+pub fn undo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<TbOrtAdresse>(or)?;
+    let oa = UndoEntry::from_str::<TbOrtAdresse>(ac)?;
+    if let (Some(o), Some(_a)) = (&oo, &oa) {
+        // Update
+        update(db, o)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        delete(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        insert(db, o)?;
+    }
+    Ok(())
+}
+
+/// Redo a dataset.
+pub fn redo(db: &mut DbContext, or: &String, ac: &String) -> Result<()> {
+    let oo = UndoEntry::from_str::<TbOrtAdresse>(or)?;
+    let oa = UndoEntry::from_str::<TbOrtAdresse>(ac)?;
+    if let (Some(_o), Some(a)) = (&oo, &oa) {
+        // Update
+        update(db, a)?;
+    } else if let Some(a) = &oa {
+        // Insert
+        insert(db, a)?;
+    } else if let Some(o) = &oo {
+        // Delete
+        delete(db, o)?;
+    }
+    Ok(())
+}
+
+/// Save dataset with all values.
+#[allow(dead_code)]
+pub fn save0(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    ort_uid_: &String,
+    adresse_uid_: &String,
+    angelegt_von_: &Option<String>,
+    angelegt_am_: &Option<NaiveDateTime>,
+    geaendert_von_: &Option<String>,
+    geaendert_am_: &Option<NaiveDateTime>,
+) -> Result<TbOrtAdresse> {
+    let op = TB_ORT_ADRESSE::table
+        .filter(
+            TB_ORT_ADRESSE::mandant_nr
+                .eq(mandant_nr_)
+                .and(TB_ORT_ADRESSE::ort_uid.eq(ort_uid_.clone())),
+        )
+        .first::<TbOrtAdresse>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    let mut p = TbOrtAdresse {
+        mandant_nr: *mandant_nr_,
+        ort_uid: ort_uid_.clone(),
+        adresse_uid: adresse_uid_.clone(),
+        angelegt_von: None,
+        angelegt_am: None,
+        geaendert_von: None,
+        geaendert_am: None,
+    };
+    if let Some(pu) = op {
+        if p != pu {
+            p.angelegt_von = pu.angelegt_von;
+            p.angelegt_am = pu.angelegt_am;
+            p.geaendert_von = pu.geaendert_von;
+            p.geaendert_am = pu.geaendert_am;
+            if p.angelegt_von.is_none() || !angelegt_von_.is_none() {
+                super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+            }
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+            update(db, &p)?;
+        }
+    } else {
+        super::mach_angelegt(&mut p, db.daten, angelegt_von_, angelegt_am_);
+        if !geaendert_von_.is_none() {
+            super::mach_geaendert(&mut p, db.daten, geaendert_von_, geaendert_am_);
+        }
+        insert(db, &p)?;
+    }
+    return Ok(p);
+}
+
+/// Save dataset without revision columns.
+#[allow(dead_code)]
+pub fn save(
+    db: &mut DbContext,
+    mandant_nr_: &i32,
+    ort_uid_: &String,
+    adresse_uid_: &String,
+) -> Result<TbOrtAdresse> {
+    save0(
+        db,
+        mandant_nr_,
+        ort_uid_,
+        adresse_uid_,
+        &None,
+        &None,
+        &None,
+        &None,
+    )
+}
+
+/// Get dataset by primary key.
+#[allow(dead_code)]
+pub fn get(db: &DbContext, mandant_nr_: &i32, ort_uid_: &String) -> Result<Option<TbOrtAdresse>> {
+    let p = TB_ORT_ADRESSE::table
+        .filter(
+            TB_ORT_ADRESSE::mandant_nr
+                .eq(mandant_nr_)
+                .and(TB_ORT_ADRESSE::ort_uid.eq(ort_uid_.clone())),
+        )
+        .first::<TbOrtAdresse>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get dataset by primary key.
+pub fn get2(db: &DbContext, b: &TbOrtAdresse) -> Result<Option<TbOrtAdresse>> {
+    let p = TB_ORT_ADRESSE::table
+        .filter(
+            TB_ORT_ADRESSE::mandant_nr
+                .eq(b.mandant_nr)
+                .and(TB_ORT_ADRESSE::ort_uid.eq(b.ort_uid.clone())),
+        )
+        .first::<TbOrtAdresse>(db.c)
+        .optional()
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(p)
+}
+
+/// Get list.
+#[allow(dead_code)]
+pub fn get_list(db: &DbContext, mandant_nr_: i32) -> Result<Vec<TbOrtAdresse>> {
+    let list = TB_ORT_ADRESSE::table
+        .filter(TB_ORT_ADRESSE::mandant_nr.eq(mandant_nr_))
+        .load::<TbOrtAdresse>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
+
+/// Insert a dataset.
+pub fn insert<'a>(db: &mut DbContext, b: &'a TbOrtAdresse) -> Result<&'a TbOrtAdresse> {
+    let rows = diesel::insert_into(TB_ORT_ADRESSE::table)
+        .values(b)
+        .execute(db.c)?;
+    if rows <= 0 {
+        return Err(RsbpError::NotFound);
+    }
+    db.ul.add(&UndoEntry::tb_ort_adresse(None, Some(b)));
+    Ok(b)
+}
+
+/// Update a dataset.
+pub fn update<'a>(db: &mut DbContext, b: &'a TbOrtAdresse) -> Result<&'a TbOrtAdresse> {
+    let oo = get2(&db, b)?;
+    let rows = diesel::update(
+        TB_ORT_ADRESSE::table.filter(
+            TB_ORT_ADRESSE::mandant_nr
+                .eq(b.mandant_nr)
+                .and(TB_ORT_ADRESSE::ort_uid.eq(b.ort_uid.clone())),
+        ),
+    )
+    .set((
+        TB_ORT_ADRESSE::adresse_uid.eq(b.adresse_uid.as_str()),
+        TB_ORT_ADRESSE::angelegt_von.eq(b.angelegt_von.as_ref()),
+        TB_ORT_ADRESSE::angelegt_am.eq(b.angelegt_am),
+        TB_ORT_ADRESSE::geaendert_von.eq(b.geaendert_von.as_ref()),
+        TB_ORT_ADRESSE::geaendert_am.eq(b.geaendert_am),
+    ))
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::tb_ort_adresse(Some(&o), Some(b)));
+    }
+    Ok(b)
+}
+
+/// Delete a dataset.
+pub fn delete(db: &mut DbContext, b: &TbOrtAdresse) -> Result<()> {
+    let oo = get2(db, b)?;
+    let rows = diesel::delete(
+        TB_ORT_ADRESSE::table.filter(
+            TB_ORT_ADRESSE::mandant_nr
+                .eq(b.mandant_nr)
+                .and(TB_ORT_ADRESSE::ort_uid.eq(b.ort_uid.clone())),
+        ),
+    )
+    .execute(db.c)?;
+    if rows <= 0 || oo.is_none() {
+        return Err(RsbpError::NotFound);
+    }
+    if let Some(o) = oo {
+        db.ul.add(&UndoEntry::tb_ort_adresse(Some(&o), None));
+    }
+    Ok(())
+}
+
+/// Get list by position and address.
+pub fn get_list_ext(
+    db: &DbContext,
+    ort_uid_: Option<&String>,
+    adresse_uid_: Option<&String>,
+) -> Result<Vec<TbOrtAdresse>> {
+    let mut q = TB_ORT_ADRESSE::table
+        .into_boxed()
+        .filter(TB_ORT_ADRESSE::mandant_nr.eq(db.daten.mandant_nr));
+    if let Some(u) = ort_uid_ {
+        q = q.filter(TB_ORT_ADRESSE::ort_uid.eq(u));
+    }
+    if let Some(a) = adresse_uid_ {
+        q = q.filter(TB_ORT_ADRESSE::adresse_uid.eq(a));
+    }
+    let list = q
+        .order(TB_ORT_ADRESSE::ort_uid)
+        .load::<TbOrtAdresse>(db.c)
+        .map_err(|source: diesel::result::Error| RsbpError::DieselError { source })?;
+    Ok(list)
+}
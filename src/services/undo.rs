@@ -5,7 +5,8 @@ use rsbp_rep::models::{
     AdAdresse, AdPerson, AdSitz, Benutzer, ByteDaten, FzBuch, FzBuchautor, FzBuchserie,
     FzBuchstatus, FzFahrrad, FzFahrradstand, FzNotiz, HhBilanz, HhBuchung, HhEreignis, HhKonto,
     HhPeriode, MaMandant, MaParameter, SbEreignis, SbFamilie, SbKind, SbPerson, SbQuelle, SoKurse,
-    TbEintrag, TbEintragOrt, TbOrt, WpAnlage, WpBuchung, WpKonfiguration, WpStand, WpWertpapier,
+    TbEintrag, TbEintragOrt, TbOrt, TbOrtAdresse, WpAnlage, WpBuchung, WpKonfiguration, WpStand,
+    WpWertpapier,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock};
@@ -41,6 +42,7 @@ pub enum UndoEntry {
     TbEintrag { original: String, actual: String },
     TbEintragOrt { original: String, actual: String },
     TbOrt { original: String, actual: String },
+    TbOrtAdresse { original: String, actual: String },
     WpAnlage { original: String, actual: String },
     WpBuchung { original: String, actual: String },
     WpKonfiguration { original: String, actual: String },
@@ -221,6 +223,12 @@ impl UndoEntry {
             actual: UndoEntry::to_string(actual),
         }
     }
+    pub fn tb_ort_adresse(original: Option<&TbOrtAdresse>, actual: Option<&TbOrtAdresse>) -> Self {
+        UndoEntry::TbOrtAdresse {
+            original: UndoEntry::to_string(original),
+            actual: UndoEntry::to_string(actual),
+        }
+    }
     pub fn wp_anlage(original: Option<&WpAnlage>, actual: Option<&WpAnlage>) -> Self {
         UndoEntry::WpAnlage {
             original: UndoEntry::to_string(original),
@@ -373,13 +381,13 @@ impl UndoRedoStack {
                 //println!("e: {:?}", e);
                 match e {
                     UndoEntry::AdAdresse { original, actual } => {
-                        // TODO reps::ad_adresse::undo(db, original, actual)?;
+                        reps::ad_adresse::undo(db, original, actual)?;
                     }
                     UndoEntry::AdPerson { original, actual } => {
                         reps::ad_person::undo(db, original, actual)?;
                     }
                     UndoEntry::AdSitz { original, actual } => {
-                        // reps::ad_sitz::undo(db, original, actual)?;
+                        reps::ad_sitz::undo(db, original, actual)?;
                     }
                     UndoEntry::Benutzer { original, actual } => {
                         reps::benutzer::undo(db, original, actual)?;
@@ -388,25 +396,25 @@ impl UndoRedoStack {
                         // reps::byte_daten::undo(db, original, actual)?;
                     }
                     UndoEntry::FzBuch { original, actual } => {
-                        // reps::fz_buch::undo(db, original, actual)?;
+                        reps::fz_buch::undo(db, original, actual)?;
                     }
                     UndoEntry::FzBuchautor { original, actual } => {
-                        // reps::fz_buchautor::undo(db, original, actual)?;
+                        reps::fz_buchautor::undo(db, original, actual)?;
                     }
                     UndoEntry::FzBuchserie { original, actual } => {
-                        // reps::fz_buchserie::undo(db, original, actual)?;
+                        reps::fz_buchserie::undo(db, original, actual)?;
                     }
                     UndoEntry::FzBuchstatus { original, actual } => {
-                        // reps::fz_buchstatus::undo(db, original, actual)?;
+                        reps::fz_buchstatus::undo(db, original, actual)?;
                     }
                     UndoEntry::FzFahrrad { original, actual } => {
-                        // reps::fz_fahrrad::undo(db, original, actual)?;
+                        reps::fz_fahrrad::undo(db, original, actual)?;
                     }
                     UndoEntry::FzFahrradstand { original, actual } => {
-                        // reps::fz_fahrradstand::undo(db, original, actual)?;
+                        reps::fz_fahrradstand::undo(db, original, actual)?;
                     }
                     UndoEntry::FzNotiz { original, actual } => {
-                        // reps::fz_notiz::undo(db, original, actual)?;
+                        reps::fz_notiz::undo(db, original, actual)?;
                     }
                     UndoEntry::HhBilanz { original, actual } => {
                         // reps::hh_bilanz::undo(db, original, actual)?;
@@ -430,19 +438,19 @@ impl UndoRedoStack {
                         reps::ma_parameter::undo(db, original, actual)?;
                     }
                     UndoEntry::SbEreignis { original, actual } => {
-                        // reps::sb_ereignis::undo(db, original, actual)?;
+                        reps::sb_ereignis::undo(db, original, actual)?;
                     }
                     UndoEntry::SbFamilie { original, actual } => {
-                        // reps::sb_familie::undo(db, original, actual)?;
+                        reps::sb_familie::undo(db, original, actual)?;
                     }
                     UndoEntry::SbKind { original, actual } => {
-                        // reps::sb_kind::undo(db, original, actual)?;
+                        reps::sb_kind::undo(db, original, actual)?;
                     }
                     UndoEntry::SbPerson { original, actual } => {
-                        // reps::sb_person::undo(db, original, actual)?;
+                        reps::sb_person::undo(db, original, actual)?;
                     }
                     UndoEntry::SbQuelle { original, actual } => {
-                        // reps::sb_quelle::undo(db, original, actual)?;
+                        reps::sb_quelle::undo(db, original, actual)?;
                     }
                     UndoEntry::SoKurse { original, actual } => {
                         // reps::so_kurse::undo(db, original, actual)?;
@@ -456,6 +464,9 @@ impl UndoRedoStack {
                     UndoEntry::TbOrt { original, actual } => {
                         reps::tb_ort::undo(db, original, actual)?;
                     }
+                    UndoEntry::TbOrtAdresse { original, actual } => {
+                        reps::tb_ort_adresse::undo(db, original, actual)?;
+                    }
                     UndoEntry::WpAnlage { original, actual } => {
                         // reps::wp_anlage::undo(db, original, actual)?;
                     }
@@ -503,13 +514,13 @@ impl UndoRedoStack {
                 //println!("e: {:?}", e);
                 match e {
                     UndoEntry::AdAdresse { original, actual } => {
-                        // TODO reps::ad_adresse::redo(db, original, actual)?;
+                        reps::ad_adresse::redo(db, original, actual)?;
                     }
                     UndoEntry::AdPerson { original, actual } => {
                         reps::ad_person::redo(db, original, actual)?;
                     }
                     UndoEntry::AdSitz { original, actual } => {
-                        // reps::ad_sitz::redo(db, original, actual)?;
+                        reps::ad_sitz::redo(db, original, actual)?;
                     }
                     UndoEntry::Benutzer { original, actual } => {
                         reps::benutzer::redo(db, original, actual)?;
@@ -518,25 +529,25 @@ impl UndoRedoStack {
                         // reps::byte_daten::redo(db, original, actual)?;
                     }
                     UndoEntry::FzBuch { original, actual } => {
-                        // reps::fz_buch::redo(db, original, actual)?;
+                        reps::fz_buch::redo(db, original, actual)?;
                     }
                     UndoEntry::FzBuchautor { original, actual } => {
-                        // reps::fz_buchautor::redo(db, original, actual)?;
+                        reps::fz_buchautor::redo(db, original, actual)?;
                     }
                     UndoEntry::FzBuchserie { original, actual } => {
-                        // reps::fz_buchserie::redo(db, original, actual)?;
+                        reps::fz_buchserie::redo(db, original, actual)?;
                     }
                     UndoEntry::FzBuchstatus { original, actual } => {
-                        // reps::fz_buchstatus::redo(db, original, actual)?;
+                        reps::fz_buchstatus::redo(db, original, actual)?;
                     }
                     UndoEntry::FzFahrrad { original, actual } => {
-                        // reps::fz_fahrrad::redo(db, original, actual)?;
+                        reps::fz_fahrrad::redo(db, original, actual)?;
                     }
                     UndoEntry::FzFahrradstand { original, actual } => {
-                        // reps::fz_fahrradstand::redo(db, original, actual)?;
+                        reps::fz_fahrradstand::redo(db, original, actual)?;
                     }
                     UndoEntry::FzNotiz { original, actual } => {
-                        // reps::fz_notiz::redo(db, original, actual)?;
+                        reps::fz_notiz::redo(db, original, actual)?;
                     }
                     UndoEntry::HhBilanz { original, actual } => {
                         // reps::hh_bilanz::redo(db, original, actual)?;
@@ -560,19 +571,19 @@ impl UndoRedoStack {
                         reps::ma_parameter::redo(db, original, actual)?;
                     }
                     UndoEntry::SbEreignis { original, actual } => {
-                        // reps::sb_ereignis::redo(db, original, actual)?;
+                        reps::sb_ereignis::redo(db, original, actual)?;
                     }
                     UndoEntry::SbFamilie { original, actual } => {
-                        // reps::sb_familie::redo(db, original, actual)?;
+                        reps::sb_familie::redo(db, original, actual)?;
                     }
                     UndoEntry::SbKind { original, actual } => {
-                        // reps::sb_kind::redo(db, original, actual)?;
+                        reps::sb_kind::redo(db, original, actual)?;
                     }
                     UndoEntry::SbPerson { original, actual } => {
-                        // reps::sb_person::redo(db, original, actual)?;
+                        reps::sb_person::redo(db, original, actual)?;
                     }
                     UndoEntry::SbQuelle { original, actual } => {
-                        // reps::sb_quelle::redo(db, original, actual)?;
+                        reps::sb_quelle::redo(db, original, actual)?;
                     }
                     UndoEntry::SoKurse { original, actual } => {
                         // reps::so_kurse::redo(db, original, actual)?;
@@ -586,6 +597,9 @@ impl UndoRedoStack {
                     UndoEntry::TbOrt { original, actual } => {
                         reps::tb_ort::redo(db, original, actual)?;
                     }
+                    UndoEntry::TbOrtAdresse { original, actual } => {
+                        reps::tb_ort_adresse::redo(db, original, actual)?;
+                    }
                     UndoEntry::WpAnlage { original, actual } => {
                         // reps::wp_anlage::redo(db, original, actual)?;
                     }